@@ -22,6 +22,9 @@ use crate::parse::{
 pub struct DataWorld {
     pub name: String,
     pub archetypes: Vec<DataArchetype>,
+
+    #[speedy(skip)]
+    pub derives: Vec<String>, // Not serialized due to being used in world generation only
 }
 
 #[derive(Debug, Readable, Writable)]
@@ -29,12 +32,16 @@ pub struct DataArchetype {
     pub id: u8,
     pub name: String,
     pub components: Vec<DataComponent>,
+
+    #[speedy(skip)]
+    pub derives: Vec<String>, // Not serialized due to being used in world generation only
 }
 
 #[derive(Debug, Readable, Writable)]
 pub struct DataComponent {
     pub id: u8,
     pub name: DataComponentName,
+    pub implements: Vec<String>,
 
     #[speedy(skip)]
     pub _default: Option<Expr>, // Preparation for future support for feature(default_field_values)
@@ -57,6 +64,12 @@ pub enum DataComponentGeneric {
 impl DataWorld {
     pub fn new(mut parse: ParseCfgDecorated<ParseEcsWorld>) -> syn::Result<Self> {
         let cfg_lookup = parse.cfg_lookup;
+        let world_derives = parse
+            .inner
+            .derives
+            .iter()
+            .map(Ident::to_string)
+            .collect::<Vec<_>>();
 
         let mut archetypes = Vec::new();
         let mut archetype_ids = HashMap::new();
@@ -93,20 +106,34 @@ impl DataWorld {
                 components.push(DataComponent {
                     id: last_component_id.expect("internal error"),
                     name: DataComponentName::new(&component.name),
+                    implements: component.implements.iter().map(Ident::to_string).collect(),
                     _default: component.default.clone(),
                 });
             }
 
+            // Traits derived for the world are derived for each of its archetypes too
+            let mut derives = world_derives.clone();
+            for name in archetype.derives.iter().map(Ident::to_string) {
+                if derives.contains(&name) == false {
+                    derives.push(name);
+                }
+            }
+
             archetypes.push(DataArchetype {
                 id: last_archetype_id.expect("internal error"),
                 name: archetype.name.to_string(),
                 components,
+                derives,
             })
         }
 
+        // A trait implementation declared on a component in one archetype applies everywhere
+        propagate_implements(&mut archetypes);
+
         Ok(DataWorld {
             name: parse.inner.name.to_string(),
             archetypes,
+            derives: world_derives,
         })
     }
 
@@ -150,6 +177,26 @@ impl DataArchetype {
 
         Ok(found)
     }
+
+    pub fn describe_components(&self) -> String {
+        let components = self
+            .components
+            .iter()
+            .map(|component| format!("`{}`", component.name))
+            .collect::<Vec<_>>();
+
+        format!("components of `{}`: {}", self.name, components.join(", "))
+    }
+
+    pub fn bind_implements(&self, trait_name: &Ident) -> Vec<ParseComponentName> {
+        let trait_name = trait_name.to_string();
+
+        self.components
+            .iter()
+            .filter(|component| component.implements.contains(&trait_name))
+            .map(|component| component.name.as_parse())
+            .collect()
+    }
 }
 
 impl DataComponentName {
@@ -242,6 +289,27 @@ impl Display for DataComponentName {
     }
 }
 
+fn propagate_implements(archetypes: &mut [DataArchetype]) {
+    let mut implements = HashMap::<String, Vec<String>>::new();
+
+    for archetype in archetypes.iter() {
+        for component in archetype.components.iter() {
+            let found = implements.entry(component.name.to_string()).or_default();
+            for trait_name in component.implements.iter() {
+                if found.contains(trait_name) == false {
+                    found.push(trait_name.clone());
+                }
+            }
+        }
+    }
+
+    for archetype in archetypes.iter_mut() {
+        for component in archetype.components.iter_mut() {
+            component.implements = implements[&component.name.to_string()].clone();
+        }
+    }
+}
+
 fn evaluate_cfgs(cfg_lookup: &HashMap<String, bool>, cfgs: &[ParseAttributeCfg]) -> bool {
     for cfg in cfgs {
         let predicate = cfg.predicate.to_string();
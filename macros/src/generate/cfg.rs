@@ -1,7 +1,8 @@
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
-use crate::parse::HasCfgPredicates;
+use crate::generate::generate_query_struct_lookup;
+use crate::parse::{HasCfgPredicates, HasQueryStructs};
 
 pub fn generate_cfg_checks_outer<S: HasCfgPredicates>(
     name: &str,
@@ -55,11 +56,18 @@ pub fn generate_cfg_checks_outer<S: HasCfgPredicates>(
     )
 }
 
-pub fn generate_cfg_checks_inner<S: HasCfgPredicates>(
+pub fn generate_cfg_checks_inner<S: HasCfgPredicates + HasQueryStructs>(
     name: &str,
     source: &S,
     raw: TokenStream,
 ) -> TokenStream {
+    // Query structs need their fields spliced in before we can check any cfgs
+    if let Some((param, query_struct)) = source.next_unresolved_struct() {
+        let expand = format_ident!("__expand_ecs_{}", name);
+        let target = quote!(::gecs::__internal::#expand);
+        return generate_query_struct_lookup(target, param, query_struct, raw);
+    }
+
     let predicates = source.collect_all_cfg_predicates();
     let mut macros = Vec::<TokenStream>::with_capacity(predicates.len());
 
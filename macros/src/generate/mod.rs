@@ -2,8 +2,10 @@
 
 mod cfg;
 mod query;
+mod system;
 mod world;
 
 pub use cfg::*;
 pub use query::*;
+pub use system::*;
 pub use world::*;
@@ -1,7 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2::{Delimiter, Ident, Spacing, Span, TokenStream, TokenTree};
 use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{Data, DataStruct, DeriveInput, Expr, Fields, Type};
+use xxhash_rust::xxh3::xxh3_128;
 
 use crate::data::{DataArchetype, DataWorld};
 use crate::parse::ParseEcsComponentId;
@@ -11,10 +13,24 @@ use crate::parse::{
     ParseCfgDecorated,
     ParseComponentName,
     ParseQueryFind, //.
+    ParseQueryFindEach,
+    ParseQueryFold,
+    ParseAttributeExpectArchetypes,
+    ParseQueryArchetypeFind,
+    ParseQueryArchetypeIter,
+    ParseQueryArchetypes,
     ParseQueryIter,
+    ParseQueryIterBudget,
     ParseQueryIterDestroy,
+    ParseQueryIterPairs,
+    ParseQueryIterSorted,
+    ParseQueryIterator,
+    ParseQueryNested,
     ParseQueryParam,
     ParseQueryParamType,
+    ParseQueryPredicate,
+    ParseQueryStruct,
+    ParseQueryStructResolve,
 };
 
 #[allow(non_snake_case)]
@@ -38,10 +54,25 @@ pub enum FetchMode {
     Borrow,
 }
 
-#[allow(non_snake_case)]
 pub fn generate_query_find(
     mode: FetchMode, //.
     query: ParseCfgDecorated<ParseQueryFind>,
+) -> syn::Result<TokenStream> {
+    generate_find(mode, false, query)
+}
+
+pub fn generate_query_try_find(
+    mode: FetchMode, //.
+    query: ParseCfgDecorated<ParseQueryFind>,
+) -> syn::Result<TokenStream> {
+    generate_find(mode, true, query)
+}
+
+#[allow(non_snake_case)]
+fn generate_find(
+    mode: FetchMode,
+    try_find: bool,
+    query: ParseCfgDecorated<ParseQueryFind>,
 ) -> syn::Result<TokenStream> {
     let mut query_data = query.inner;
     let world_data = DataWorld::from_base64(&query_data.world_data);
@@ -52,6 +83,34 @@ pub fn generate_query_find(
     }
 
     let bound_params = bind_query_params(&world_data, &query_data.params)?;
+
+    // If an entity parameter pins the query to one archetype, say why that archetype failed
+    if bound_params.is_empty()
+        && let Some(error) = explain_query_mismatch(&world_data, &query_data.params)
+    {
+        return Err(error);
+    }
+
+    check_expected_archetypes(&query_data.expect, &world_data, |name| {
+        bound_params.contains_key(name)
+    })?;
+
+    // A typed entity handle pins the query to one archetype as well, but we only know the
+    // handle's type after type checking, so ecs_find! checks it with a compile-time assertion
+    // (ecs_try_find! instead reports the mismatch at runtime)
+    let check_key = match try_find {
+        true => None,
+        false => generate_find_check_key(&world_data, &query_data.params, |name| {
+            bound_params.contains_key(name)
+        }),
+    };
+    let entity = &query_data.entity;
+    let entity = match check_key {
+        Some(check_key) => quote!(#check_key(#entity)),
+        None => entity.to_token_stream(),
+    };
+
+    strip_filters(&mut query_data.params);
     // NOTE: Beyond this point, query.params is only safe to use for information that
     // does not change depending on the type of the parameter (e.g. mutability). Anything
     // that might change after OneOf binding etc. must use the bound query params in
@@ -65,7 +124,6 @@ pub fn generate_query_find(
 
     // Variables and fields
     let world = &query_data.world;
-    let entity = &query_data.entity;
     let body = &query_data.body;
     let arg = query_data.params.iter().map(to_name).collect::<Vec<_>>();
     let attrs = query_data
@@ -90,8 +148,38 @@ pub fn generate_query_find(
         None => quote!(),
     };
 
+    // When reporting failures, say why the entity couldn't be found. Binding may also fail
+    // in that case (on a runtime borrow conflict), so the closure call is wrapped in Ok.
+    let (on_stale, on_direct, then) = match try_find {
+        true => (
+            quote!(.ok_or(::gecs::error::FindError::Stale)),
+            quote!(.ok_or(::gecs::error::FindError::DirectInvalidated)),
+            quote!(and_then),
+        ),
+        false => (quote!(), quote!(), quote!(map)),
+    };
+    let wrap = match try_find {
+        true => quote!(Ok),
+        false => quote!(),
+    };
+
     let mut queries = Vec::<TokenStream>::new();
+    let mut mismatches = Vec::<TokenStream>::new();
+    let select = quote_spanned!(Span::mixed_site() => select);
     for archetype in world_data.archetypes {
+        if try_find && bound_params.contains_key(&archetype.name) == false {
+            let Archetype = format_ident!("{}", archetype.name);
+            let ArchetypeDirect = format_ident!("{}Direct", archetype.name);
+
+            mismatches.push(quote!(
+                #__WorldSelectTotal::#Archetype(_) | #__WorldSelectTotal::#ArchetypeDirect(_) => {
+                    Err(::gecs::error::FindError::ArchetypeMismatch {
+                        archetype_id: <#Archetype as ::gecs::__internal::Archetype>::ARCHETYPE_ID,
+                    })
+                }
+            ));
+        }
+
         if let Some(bound_params) = bound_params.get(&archetype.name) {
             // Types and traits
             let Archetype = format_ident!("{}", archetype.name);
@@ -128,7 +216,7 @@ pub fn generate_query_find(
 
             #[rustfmt::skip]
             let bind = match mode {
-                FetchMode::Borrow => bound_params.iter().map(find_bind_borrow).collect::<Vec<_>>(),
+                FetchMode::Borrow => bound_params.iter().map(|p| find_bind_borrow(p, try_find)).collect::<Vec<_>>(),
                 FetchMode::Mut => bound_params.iter().map(find_bind_mut).collect::<Vec<_>>(),
             };
 
@@ -142,7 +230,7 @@ pub fn generate_query_find(
                     let archetype = #get_archetype;
                     let version = archetype.version();
 
-                    #fetch.map(#map_args closure(#(#attrs #bind),*))
+                    #fetch #on_stale.#then(#map_args #wrap(closure(#(#attrs #bind),*)))
                 }
                 #__WorldSelectTotal::#ArchetypeDirect(#resolved_entity) => {
                     // Alias the current archetype for use in the closure.
@@ -153,7 +241,7 @@ pub fn generate_query_find(
                     let archetype = #get_archetype;
                     let version = archetype.version();
 
-                    #fetch.map(#map_args closure(#(#attrs #bind),*))
+                    #fetch #on_direct.#then(#map_args #wrap(closure(#(#attrs #bind),*)))
                 }
             ));
         }
@@ -165,15 +253,83 @@ pub fn generate_query_find(
             "query matched no archetypes in world",
         ))
     } else {
-        Ok(quote!(
-            {
-                match #__WorldSelectTotal::try_from(#entity).expect("invalid entity type") {
-                    #(#queries)*
-                    _ => None,
+        match try_find {
+            true => Ok(quote!(
+                {
+                    match #__WorldSelectTotal::try_from(#entity) {
+                        Ok(#select) => match #select {
+                            #(#queries)*
+                            #(#mismatches)*
+                        },
+                        Err(_) => Err(::gecs::error::FindError::InvalidEntityType),
+                    }
+                }
+            )),
+            false => Ok(quote!(
+                {
+                    match #__WorldSelectTotal::try_from(#entity).expect("invalid entity type") {
+                        #(#queries)*
+                        _ => None,
+                    }
                 }
+            )),
+        }
+    }
+}
+
+/// Generates an assertion that the entity handle given to a find query isn't typed to an
+/// archetype the query can never match, or nothing if the query matches every archetype.
+#[allow(non_snake_case)]
+fn generate_find_check_key(
+    world_data: &DataWorld,
+    params: &[ParseQueryParam],
+    is_matched: impl Fn(&str) -> bool,
+) -> Option<TokenStream> {
+    let mut checks = Vec::new();
+    for archetype in world_data.archetypes.iter() {
+        if is_matched(&archetype.name) {
+            continue;
+        }
+
+        let Archetype = format_ident!("{}", archetype.name);
+        let reason = params
+            .iter()
+            .filter(|param| param.is_cfg_enabled)
+            .find_map(|param| explain_param_mismatch(archetype, param))
+            .unwrap_or_else(|| format!("query can't match archetype `{}`", archetype.name));
+        let message = format!(
+            "entity handle is typed to archetype `{}`, which this query can never match: {} ({})",
+            archetype.name,
+            reason,
+            archetype.describe_components(),
+        );
+
+        checks.push(quote!(
+            if id == <#Archetype as ::gecs::__internal::Archetype>::ARCHETYPE_ID {
+                panic!("{}", #message);
             }
-        ))
+        ));
+    }
+
+    if checks.is_empty() {
+        return None;
     }
+
+    let check_key = quote_spanned!(Span::mixed_site() => check_key);
+    Some(quote!(
+        {
+            #[inline(always)]
+            fn #check_key<K: ::gecs::__internal::EntityKeyArchetype>(key: K) -> K {
+                const {
+                    if let Some(id) = <K as ::gecs::__internal::EntityKeyArchetype>::ARCHETYPE_ID {
+                        #(#checks)*
+                    }
+                }
+                key
+            }
+            #check_key
+        }
+    ))
 }
 
 fn check_needs_direct(bound_params: &[ParseQueryParam]) -> bool {
@@ -217,25 +373,49 @@ fn find_bind_mut(param: &ParseQueryParam) -> TokenStream {
         ParseQueryParamType::OneOf(_) => {
             panic!("must unpack OneOf first")
         }
+        ParseQueryParamType::Dyn(trait_name, bound) => {
+            let name = Ident::new(&bound[0].as_snake_name(), Span::call_site());
+            match param.is_mut {
+                true => quote!(found.#name as &mut dyn #trait_name),
+                false => quote!(found.#name as &dyn #trait_name),
+            }
+        }
+        ParseQueryParamType::DynSlice(trait_name, bound) => {
+            let name = to_snake_idents(bound);
+            match param.is_mut {
+                true => quote!(&mut [#(found.#name as &mut dyn #trait_name),*]),
+                false => quote!(&[#(found.#name as &dyn #trait_name),*]),
+            }
+        }
         ParseQueryParamType::Option(_) => {
             todo!() // Not yet implemented
         }
-        ParseQueryParamType::With(_) => {
-            todo!() // Not yet implemented
+        ParseQueryParamType::With(_) | ParseQueryParamType::Without(_) => {
+            panic!("must strip filters first")
         }
-        ParseQueryParamType::Without(_) => {
-            todo!() // Not yet implemented
+        ParseQueryParamType::Struct(_) => {
+            panic!("must expand query structs first")
         }
     }
 }
 
 #[rustfmt::skip]
-fn find_bind_borrow(param: &ParseQueryParam) -> TokenStream {
+fn find_bind_borrow(param: &ParseQueryParam, try_find: bool) -> TokenStream {
+    // When reporting failures, return a conflicting runtime borrow rather than panicking
+    let get = |name: &dyn ToTokens| match try_find {
+        true => quote!(found.try_component::<#name>().map_err(::gecs::error::FindError::__from_borrow)?),
+        false => quote!(found.component::<#name>()),
+    };
+    let get_mut = |name: &dyn ToTokens| match try_find {
+        true => quote!(found.try_component_mut::<#name>().map_err(::gecs::error::FindError::__from_borrow)?),
+        false => quote!(found.component_mut::<#name>()),
+    };
+
     match &param.param_type {
         ParseQueryParamType::Component(name) => {
             match param.is_mut { 
-                true => quote!(&mut found.component_mut::<#name>()),
-                false => quote!(&found.component::<#name>()),
+                true => { let borrowed = get_mut(name); quote!(&mut *#borrowed) }
+                false => { let borrowed = get(name); quote!(&*#borrowed) }
             }
         }
         ParseQueryParamType::Entity(_) => {
@@ -259,22 +439,34 @@ fn find_bind_borrow(param: &ParseQueryParam) -> TokenStream {
         ParseQueryParamType::OneOf(_) => {
             panic!("must unpack OneOf first")
         }
+        ParseQueryParamType::Dyn(trait_name, bound) => {
+            let name = &bound[0];
+            match param.is_mut {
+                true => { let borrowed = get_mut(name); quote!(&mut *#borrowed as &mut dyn #trait_name) }
+                false => { let borrowed = get(name); quote!(&*#borrowed as &dyn #trait_name) }
+            }
+        }
+        ParseQueryParamType::DynSlice(trait_name, bound) => {
+            match param.is_mut {
+                true => { let borrowed = bound.iter().map(|b| get_mut(b)); quote!(&mut [#(&mut *#borrowed as &mut dyn #trait_name),*]) }
+                false => { let borrowed = bound.iter().map(|b| get(b)); quote!(&[#(&*#borrowed as &dyn #trait_name),*]) }
+            }
+        }
         ParseQueryParamType::Option(_) => {
             todo!() // Not yet implemented
         }
-        ParseQueryParamType::With(_) => {
-            todo!() // Not yet implemented
+        ParseQueryParamType::With(_) | ParseQueryParamType::Without(_) => {
+            panic!("must strip filters first")
         }
-        ParseQueryParamType::Without(_) => {
-            todo!() // Not yet implemented
+        ParseQueryParamType::Struct(_) => {
+            panic!("must expand query structs first")
         }
     }
 }
 
 #[allow(non_snake_case)]
-pub fn generate_query_iter(
-    mode: FetchMode, //.
-    query: ParseCfgDecorated<ParseQueryIter>,
+pub fn generate_query_find_each(
+    query: ParseCfgDecorated<ParseQueryFindEach>, //.
 ) -> syn::Result<TokenStream> {
     let mut query_data = query.inner;
     let world_data = DataWorld::from_base64(&query_data.world_data);
@@ -285,14 +477,17 @@ pub fn generate_query_iter(
     }
 
     let bound_params = bind_query_params(&world_data, &query_data.params)?;
-    // NOTE: Beyond this point, query.params is only safe to use for information that
-    // does not change depending on the type of the parameter (e.g. mutability). Anything
-    // that might change after OneOf binding etc. must use the bound query params in
-    // bound_params for the given archetype. Note that it's faster to use query.params
-    // where available, since it avoids redundant computation for each archetype.
+    check_expected_archetypes(&query_data.expect, &world_data, |name| {
+        bound_params.contains_key(name)
+    })?;
+    strip_filters(&mut query_data.params);
+
+    // Types
+    let __WorldSelectTotal = format_ident!("__{}SelectTotal", world_data.name);
 
     // Variables and fields
     let world = &query_data.world;
+    let handles = &query_data.handles;
     let body = &query_data.body;
     let arg = query_data.params.iter().map(to_name).collect::<Vec<_>>();
     let attrs = query_data
@@ -300,66 +495,67 @@ pub fn generate_query_iter(
         .iter()
         .map(to_attributes)
         .collect::<Vec<_>>();
-
-    // Special cases
     let maybe_mut = query_data
         .params
         .iter()
         .map(to_maybe_mut)
         .collect::<Vec<_>>();
 
+    // We want these to be hygenic because they're declared above the closure.
+    let missed = quote_spanned!(Span::mixed_site() => missed);
+    let handle = quote_spanned!(Span::mixed_site() => handle);
+    let entity = quote_spanned!(Span::mixed_site() => entity);
+
+    let mut buckets = Vec::<TokenStream>::new();
+    let mut resolves = Vec::<TokenStream>::new();
     let mut queries = Vec::<TokenStream>::new();
     for archetype in world_data.archetypes {
         if let Some(bound_params) = bound_params.get(&archetype.name) {
             // Types and traits
             let Archetype = format_ident!("{}", archetype.name);
+            let ArchetypeDirect = format_ident!("{}Direct", archetype.name);
             let Type = bound_params
                 .iter()
                 .map(|p| to_type(p, &archetype))
                 .collect::<Vec<_>>(); // Bind-dependent!
 
             // Variables
+            let bucket = Ident::new(
+                &format!("bucket_{}", util::to_snake(&archetype.name)),
+                Span::mixed_site(),
+            );
             let archetype = format_ident!("{}", util::to_snake(&archetype.name));
+            let bind = bound_params.iter().map(iter_bind_mut).collect::<Vec<_>>();
 
-            // Fetch the archetype directly to allow queries to be sneaky with
-            // direct archetype access to get cross-archetype nested mutability
-            #[rustfmt::skip]
-            let get_archetype = match mode {
-                FetchMode::Borrow => quote!(&#world.#archetype),
-                FetchMode::Mut => quote!(&mut #world.#archetype),
-            };
-
-            #[rustfmt::skip]
-            let get_slices = match mode {
-                FetchMode::Borrow => quote!(()),
-                FetchMode::Mut => quote!(archetype.get_all_slices_mut()),
-            };
+            buckets.push(quote!(let mut #bucket = Vec::<usize>::new();));
 
-            #[rustfmt::skip]
-            let bind = match mode {
-                FetchMode::Borrow => bound_params.iter().map(iter_bind_borrow).collect::<Vec<_>>(),
-                FetchMode::Mut => bound_params.iter().map(iter_bind_mut).collect::<Vec<_>>(),
-            };
+            resolves.push(quote!(
+                #__WorldSelectTotal::#Archetype(#entity) =>
+                    ::gecs::__internal::ArchetypeCanResolve::resolve_for(&#world.#archetype, #entity)
+                        .map(|idx| #bucket.push(idx)),
+                #__WorldSelectTotal::#ArchetypeDirect(#entity) =>
+                    ::gecs::__internal::ArchetypeCanResolve::resolve_for(&#world.#archetype, #entity)
+                        .map(|idx| #bucket.push(idx)),
+            ));
 
             queries.push(quote!(
-                {
+                if #bucket.is_empty() == false {
                     // Alias the current archetype for use in the closure
                     type MatchedArchetype = #Archetype;
                     // The closure needs to be made per-archetype because of OneOf types
                     let mut closure = |#(#attrs #arg: &#maybe_mut #Type),*| #body;
 
-                    let archetype = #get_archetype;
+                    let archetype = &mut #world.#archetype;
                     let version = archetype.version();
-                    let len = archetype.len();
-                    let slices = #get_slices;
+                    let slices = archetype.get_all_slices_mut();
 
-                    for idx in 0..len {
+                    for idx in #bucket.iter().copied() {
                         match closure(#(#attrs #bind),*).into() {
                             EcsStep::Continue => {
                                 // Continue
                             },
                             EcsStep::Break => {
-                                return;
+                                return #missed;
                             },
                         }
                     }
@@ -376,15 +572,35 @@ pub fn generate_query_iter(
     } else {
         Ok(quote!(
             // Use a closure so we can use return to cancel other archetype iterations
-            (||{#(#queries)*})()
+            (|| {
+                let mut #missed = Vec::new();
+                #(#buckets)*
+
+                // Resolve every handle up front, grouping the resulting indices by archetype
+                for #handle in #handles {
+                    let found = match #__WorldSelectTotal::try_from(#handle)
+                        .expect("invalid entity type")
+                    {
+                        #(#resolves)*
+                        _ => None,
+                    };
+
+                    if found.is_none() {
+                        #missed.push(#handle);
+                    }
+                }
+
+                #(#queries)*
+                #missed
+            })()
         ))
     }
 }
 
 #[allow(non_snake_case)]
-pub fn generate_query_iter_destroy(
-    mode: FetchMode,
-    query: ParseCfgDecorated<ParseQueryIterDestroy>,
+pub fn generate_query_iter(
+    mode: FetchMode, //.
+    query: ParseCfgDecorated<ParseQueryIter>,
 ) -> syn::Result<TokenStream> {
     let mut query_data = query.inner;
     let world_data = DataWorld::from_base64(&query_data.world_data);
@@ -395,6 +611,10 @@ pub fn generate_query_iter_destroy(
     }
 
     let bound_params = bind_query_params(&world_data, &query_data.params)?;
+    check_expected_archetypes(&query_data.expect, &world_data, |name| {
+        bound_params.contains_key(name)
+    })?;
+    strip_filters(&mut query_data.params);
     // NOTE: Beyond this point, query.params is only safe to use for information that
     // does not change depending on the type of the parameter (e.g. mutability). Anything
     // that might change after OneOf binding etc. must use the bound query params in
@@ -418,37 +638,83 @@ pub fn generate_query_iter_destroy(
         .map(to_maybe_mut)
         .collect::<Vec<_>>();
 
+    if let Some(nested) = query_data.nested.as_mut() {
+        if let FetchMode::Borrow = mode {
+            return Err(syn::Error::new_spanned(
+                &nested.name,
+                "nested queries are not supported with ecs_iter_borrow!",
+            ));
+        }
+
+        for param in nested.params.iter_mut() {
+            param.is_cfg_enabled = is_cfg_enabled(param, &query.cfg_lookup);
+        }
+    }
+
+    // Nested queries are bound separately and checked for conflicts against the outer query
+    let nested_bound_params = match query_data.nested.as_ref() {
+        Some(nested) => Some(bind_query_params(&world_data, &nested.params)?),
+        None => None,
+    };
+    if let Some(nested) = query_data.nested.as_mut() {
+        strip_filters(&mut nested.params);
+    }
+
     let mut queries = Vec::<TokenStream>::new();
-    for archetype in world_data.archetypes {
+    for archetype in world_data.archetypes.iter() {
+        if let (Some(nested), Some(nested_bound_params)) = (&query_data.nested, &nested_bound_params) {
+            if let Some(bound_params) = bound_params.get(&archetype.name) {
+                queries.push(generate_query_iter_nested(
+                    &query_data.world,
+                    &world_data,
+                    archetype,
+                    &query_data.params,
+                    bound_params,
+                    &query_data.body,
+                    nested,
+                    nested_bound_params,
+                )?);
+            }
+            continue;
+        }
+
         if let Some(bound_params) = bound_params.get(&archetype.name) {
             // Types and traits
             let Archetype = format_ident!("{}", archetype.name);
             let Type = bound_params
                 .iter()
-                .map(|p| to_type(p, &archetype))
+                .map(|p| to_type(p, archetype))
                 .collect::<Vec<_>>(); // Bind-dependent!
 
             // Variables
             let archetype = format_ident!("{}", util::to_snake(&archetype.name));
 
+            // Read-only queries still take the archetype's loan mutably, so nothing else can
+            // touch it, but only read through a restricted view. This lets them run on parts
+            // of a world that were split for (possibly concurrent) read-only access.
+            let is_shared = matches!(mode, FetchMode::Mut)
+                && bound_params.iter().all(|param| param.is_mut == false);
+
             // Fetch the archetype directly to allow queries to be sneaky with
             // direct archetype access to get cross-archetype nested mutability
             #[rustfmt::skip]
-            let get_archetype = match mode {
-                FetchMode::Borrow => quote!(&#world.#archetype),
-                FetchMode::Mut => quote!(&mut #world.#archetype),
+            let get_archetype = match (mode, is_shared) {
+                (FetchMode::Borrow, _) => quote!(&#world.#archetype),
+                (FetchMode::Mut, true) => quote!(::gecs::__internal::ArchetypeSource::<MatchedArchetype>::archetype_read(&mut #world.#archetype)),
+                (FetchMode::Mut, false) => quote!(::gecs::__internal::ArchetypeSource::<MatchedArchetype>::archetype_mut(&mut #world.#archetype)),
             };
 
             #[rustfmt::skip]
-            let get_slices = match mode {
-                FetchMode::Borrow => panic!("borrow unsupported for iter_destroy"),
-                FetchMode::Mut => quote!(archetype.get_all_slices_mut()),
+            let get_slices = match (mode, is_shared) {
+                (FetchMode::Borrow, _) | (FetchMode::Mut, true) => quote!(()),
+                (FetchMode::Mut, false) => quote!(archetype.get_all_slices_mut()),
             };
 
             #[rustfmt::skip]
-            let bind = match mode {
-                FetchMode::Borrow => panic!("borrow unsupported for iter_destroy"),
-                FetchMode::Mut => bound_params.iter().map(iter_bind_mut).collect::<Vec<_>>(),
+            let bind = match (mode, is_shared) {
+                (FetchMode::Borrow, _) => bound_params.iter().map(iter_bind_borrow).collect::<Vec<_>>(),
+                (FetchMode::Mut, true) => bound_params.iter().map(iter_bind_shared).collect::<Vec<_>>(),
+                (FetchMode::Mut, false) => bound_params.iter().map(iter_bind_mut).collect::<Vec<_>>(),
             };
 
             queries.push(quote!(
@@ -461,25 +727,14 @@ pub fn generate_query_iter_destroy(
                     let archetype = #get_archetype;
                     let version = archetype.version();
                     let len = archetype.len();
+                    let slices = #get_slices;
 
-                    // Iterate in reverse order to still visit each entity once.
-                    // Note: This assumes that we remove entities by swapping.
-                    for idx in (0..len).rev() {
-                        let slices = #get_slices;
+                    for idx in 0..len {
                         match closure(#(#attrs #bind),*).into() {
-                            EcsStepDestroy::Continue => {
+                            EcsStep::Continue => {
                                 // Continue
                             },
-                            EcsStepDestroy::Break => {
-                                return;
-                            },
-                            EcsStepDestroy::ContinueDestroy => {
-                                let entity = slices.entity[idx];
-                                archetype.destroy(entity);
-                            },
-                            EcsStepDestroy::BreakDestroy => {
-                                let entity = slices.entity[idx];
-                                archetype.destroy(entity);
+                            EcsStep::Break => {
                                 return;
                             },
                         }
@@ -502,62 +757,1740 @@ pub fn generate_query_iter_destroy(
     }
 }
 
-#[rustfmt::skip]
-fn iter_bind_mut(param: &ParseQueryParam) -> TokenStream {
-    match &param.param_type {
-        ParseQueryParamType::Component(name) => { 
-            let ident = Ident::new(&name.as_snake_name(), Span::call_site());
-            match param.is_mut { 
-                true => quote!(&mut slices.#ident[idx]),
-                false => quote!(&slices.#ident[idx]),
-            }
-        }
-        ParseQueryParamType::Entity(_) => {
-            quote!(&slices.entity[idx])
-        }
-        ParseQueryParamType::EntityWild => {
-            quote!(&slices.entity[idx])
-        }
-        ParseQueryParamType::EntityAny => {
-            quote!(&slices.entity[idx].into())
-        }
-        ParseQueryParamType::EntityDirect(_) => {
-            quote!(&::gecs::__internal::new_entity_direct::<MatchedArchetype>(idx, version))
-        }
-        ParseQueryParamType::EntityDirectWild => {
-            quote!(&::gecs::__internal::new_entity_direct::<MatchedArchetype>(idx, version))
-        }
-        ParseQueryParamType::EntityDirectAny => {
-            quote!(&::gecs::__internal::new_entity_direct::<MatchedArchetype>(idx, version).into())
-        }
-        ParseQueryParamType::OneOf(_) => {
-            panic!("must unpack OneOf first")
-        }
-        ParseQueryParamType::Option(_) => {
-            todo!("Option not yet supported")
+/// Generates the iteration over a single archetype for an `ecs_iter!` with a nested query.
+/// The nested query is checked for conflicts with the outer query here, so at runtime it
+/// can access the world's data directly rather than through runtime borrows.
+#[allow(non_snake_case)]
+#[allow(clippy::too_many_arguments)]
+fn generate_query_iter_nested(
+    world: &Expr,
+    world_data: &DataWorld,
+    outer: &DataArchetype,
+    params: &[ParseQueryParam],
+    bound_params: &[ParseQueryParam],
+    body: &Expr,
+    nested: &ParseQueryNested,
+    nested_bound_params: &HashMap<String, Vec<ParseQueryParam>>,
+) -> syn::Result<TokenStream> {
+    // Types and traits
+    let __WorldSelectTotal = format_ident!("__{}SelectTotal", world_data.name);
+    let Archetype = format_ident!("{}", outer.name);
+    let Type = bound_params
+        .iter()
+        .map(|p| to_type(p, outer))
+        .collect::<Vec<_>>(); // Bind-dependent!
+
+    // Variables and fields
+    let field = format_ident!("{}", util::to_snake(&outer.name));
+    let arg = params.iter().map(to_name).collect::<Vec<_>>();
+    let attrs = params.iter().map(to_attributes).collect::<Vec<_>>();
+    let maybe_mut = params.iter().map(to_maybe_mut).collect::<Vec<_>>();
+
+    let name = &nested.name;
+    let nested_body = &nested.body;
+    let nested_arg = nested.params.iter().map(to_name).collect::<Vec<_>>();
+    let nested_attrs = nested.params.iter().map(to_attributes).collect::<Vec<_>>();
+    let nested_maybe_mut = nested.params.iter().map(to_maybe_mut).collect::<Vec<_>>();
+    let nested_ret = match &nested.ret {
+        Some(ret) => quote!(-> #ret),
+        None => quote!(),
+    };
+
+    // We want these to be hygenic because they're declared above both query closures.
+    let archetype_ref = quote_spanned!(Span::mixed_site() => archetype);
+    let version = quote_spanned!(Span::mixed_site() => version);
+    let len = quote_spanned!(Span::mixed_site() => len);
+    let slices = quote_spanned!(Span::mixed_site() => slices);
+    let resolver = quote_spanned!(Span::mixed_site() => resolver);
+    let entity = quote_spanned!(Span::mixed_site() => entity);
+    let index = quote_spanned!(Span::mixed_site() => index);
+
+    let mut uses_resolver = false;
+    let mut arms = Vec::<TokenStream>::new();
+    for archetype in world_data.archetypes.iter() {
+        if let Some(nested_bound) = nested_bound_params.get(&archetype.name) {
+            // Types and traits
+            let NestedArchetype = format_ident!("{}", archetype.name);
+            let NestedArchetypeDirect = format_ident!("{}Direct", archetype.name);
+            let NestedType = nested_bound
+                .iter()
+                .map(|p| to_type(p, archetype))
+                .collect::<Vec<_>>(); // Bind-dependent!
+
+            // Within the outer archetype, we can only use the columns the outer query doesn't
+            // conflict with, and must look the entity up without touching the archetype itself
+            let fetch = match archetype.name == outer.name {
+                true => {
+                    check_nested_conflicts(outer, bound_params, nested, nested_bound)?;
+                    uses_resolver = true;
+
+                    quote!(
+                        let version = #version;
+                        let #index = #resolver.resolve(#entity)?;
+                    )
+                }
+                false => {
+                    let field = format_ident!("{}", util::to_snake(&archetype.name));
+
+                    quote!(
+                        let #archetype_ref = &mut #world.#field;
+                        let version = #archetype_ref.version();
+                        let #index = #archetype_ref.resolve(#entity)?;
+                        let #slices = #archetype_ref.get_all_slices_mut();
+                    )
+                }
+            };
+
+            let bind = nested_bound
+                .iter()
+                .map(|p| iter_bind_mut_at(p, &slices, &index))
+                .collect::<Vec<_>>();
+
+            let arm_body = quote!({
+                // Alias the current archetype for use in the closure
+                type MatchedArchetype = #NestedArchetype;
+                // The closure needs to be made per-archetype because of OneOf types
+                let mut closure = |#(#nested_attrs #nested_arg: &#nested_maybe_mut #NestedType),*| #nested_ret #nested_body;
+
+                #fetch
+                Some(closure(#(#nested_attrs #bind),*))
+            });
+
+            arms.push(quote!(
+                #__WorldSelectTotal::#NestedArchetype(#entity) => #arm_body
+                #__WorldSelectTotal::#NestedArchetypeDirect(#entity) => #arm_body
+            ));
         }
-        ParseQueryParamType::With(_) => {
-            todo!("With not yet supported")
+    }
+
+    let get_slices = match uses_resolver {
+        true => quote!(let (#slices, #resolver) = #archetype_ref.data.get_all_slices_mut_resolver::<<#Archetype as ::gecs::__internal::Archetype>::Slices<'_>>();),
+        false => quote!(let #slices = #archetype_ref.get_all_slices_mut();),
+    };
+
+    let bind = bound_params
+        .iter()
+        .map(|p| iter_bind_mut_at(p, &slices, &quote!(idx)))
+        .collect::<Vec<_>>();
+
+    Ok(quote!(
+        {
+            // Alias the current archetype for use in the closure
+            type MatchedArchetype = #Archetype;
+
+            let #archetype_ref = &mut #world.#field;
+            let #version = #archetype_ref.version();
+            let #len = #archetype_ref.len();
+            #get_slices
+
+            // The nested query captures only the data it needs, disjoint from the outer query
+            let mut #name = |#entity| {
+                match #__WorldSelectTotal::try_from(#entity).expect("invalid entity type") {
+                    #(#arms)*
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
+            };
+
+            // The closure needs to be made per-archetype because of OneOf types
+            let mut closure = |#(#attrs #arg: &#maybe_mut #Type),*| #body;
+
+            let version = #version;
+            for idx in 0..#len {
+                match closure(#(#attrs #bind),*).into() {
+                    EcsStep::Continue => {
+                        // Continue
+                    },
+                    EcsStep::Break => {
+                        return;
+                    },
+                }
+            }
         }
-        ParseQueryParamType::Without(_) => {
-            todo!("Without not yet supported")
+    ))
+}
+
+fn check_nested_conflicts(
+    archetype: &DataArchetype,
+    outer_params: &[ParseQueryParam],
+    nested: &ParseQueryNested,
+    nested_params: &[ParseQueryParam],
+) -> syn::Result<()> {
+    let outer_access = to_component_access(outer_params);
+    for (component, is_mut) in to_component_access(nested_params) {
+        for (other, other_mut) in outer_access.iter() {
+            if (component == *other) && (is_mut || *other_mut) {
+                return Err(syn::Error::new_spanned(
+                    &nested.name,
+                    format!(
+                        "nested query access to {} in {} conflicts with the outer query, \
+                        use ecs_iter_borrow! to check this at runtime instead",
+                        component, archetype.name,
+                    ),
+                ));
+            }
         }
     }
+
+    Ok(())
 }
 
-#[rustfmt::skip]
-fn iter_bind_borrow(param: &ParseQueryParam) -> TokenStream {
-    match &param.param_type {
-        ParseQueryParamType::Component(name) => {
-            match param.is_mut {
-                true => quote!(&mut archetype.borrow_slice_mut::<#name>()[idx]),
-                false => quote!(&archetype.borrow_slice::<#name>()[idx]),
+fn to_component_access(params: &[ParseQueryParam]) -> Vec<(String, bool)> {
+    let mut result = Vec::new();
+    for param in params {
+        match &param.param_type {
+            ParseQueryParamType::Component(name) => {
+                result.push((name.to_string(), param.is_mut));
             }
+            ParseQueryParamType::Dyn(_, bound) => {
+                result.push((bound[0].to_string(), param.is_mut));
+            }
+            ParseQueryParamType::DynSlice(_, bound) => {
+                result.extend(bound.iter().map(|name| (name.to_string(), param.is_mut)));
+            }
+            _ => {} // Entity handles are only ever read
         }
-        ParseQueryParamType::Entity(_) => {
-            quote!(&archetype.entities()[idx])
-        }
-        ParseQueryParamType::EntityWild => {
+    }
+    result
+}
+
+pub fn generate_query_iter_archetype(
+    query: ParseQueryArchetypeIter, //.
+) -> syn::Result<TokenStream> {
+    // Variables and fields
+    let archetype = &query.archetype;
+    let body = &query.body;
+    let arg = query.params.iter().map(to_name).collect::<Vec<_>>();
+    let attrs = query.params.iter().map(to_attributes).collect::<Vec<_>>();
+    let maybe_mut = query.params.iter().map(to_maybe_mut).collect::<Vec<_>>();
+    let Type = query
+        .params
+        .iter()
+        .map(to_type_generic)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    // We want this to be hygenic because it's declared above the closure.
+    let archetype_ref = quote_spanned!(Span::mixed_site() => archetype);
+    let len = quote_spanned!(Span::mixed_site() => len);
+    let (setup, bind) = archetype_query_bind(&query.params, &archetype_ref, &quote!(idx))?;
+
+    Ok(quote!(
+        {
+            let #archetype_ref = &mut *#archetype;
+            #setup
+
+            // The closure is declared after our setup, but can't see any of its variables
+            let mut closure = |#(#attrs #arg: &#maybe_mut #Type),*| #body;
+
+            for idx in 0..#len {
+                match closure(#(#attrs #bind),*).into() {
+                    ::gecs::__internal::EcsStep::Continue => {
+                        // Continue
+                    },
+                    ::gecs::__internal::EcsStep::Break => {
+                        break;
+                    },
+                }
+            }
+        }
+    ))
+}
+
+pub fn generate_query_find_archetype(
+    query: ParseQueryArchetypeFind, //.
+) -> syn::Result<TokenStream> {
+    // Variables and fields
+    let archetype = &query.archetype;
+    let entity = &query.entity;
+    let body = &query.body;
+    let arg = query.params.iter().map(to_name).collect::<Vec<_>>();
+    let attrs = query.params.iter().map(to_attributes).collect::<Vec<_>>();
+    let maybe_mut = query.params.iter().map(to_maybe_mut).collect::<Vec<_>>();
+    let Type = query
+        .params
+        .iter()
+        .map(to_type_generic)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    // Explicit return value on the query
+    let ret = match &query.ret {
+        Some(ret) => quote!(-> #ret),
+        None => quote!(),
+    };
+
+    // We want these to be hygenic because they're declared above the closure.
+    let archetype_ref = quote_spanned!(Span::mixed_site() => archetype);
+    let index = quote_spanned!(Span::mixed_site() => index);
+    let (setup, bind) = archetype_query_bind(&query.params, &archetype_ref, &index)?;
+
+    Ok(quote!(
+        {
+            let #archetype_ref = &mut *#archetype;
+            match ::gecs::__internal::Archetype::resolve(&*#archetype_ref, #entity) {
+                Some(#index) => {
+                    #setup
+
+                    // The closure is declared after our setup, but can't see any of its variables
+                    let mut closure = |#(#attrs #arg: &#maybe_mut #Type),*| #ret #body;
+
+                    Some(closure(#(#attrs #bind),*))
+                }
+                None => None,
+            }
+        }
+    ))
+}
+
+/// Generates the setup for an archetype query, which takes each component's slice from the
+/// archetype up front, along with the bindings for each parameter at the given index.
+fn archetype_query_bind(
+    params: &[ParseQueryParam],
+    archetype: &TokenStream,
+    idx: &TokenStream,
+) -> syn::Result<(TokenStream, Vec<TokenStream>)> {
+    // We want these to be hygenic because they're declared above the closure.
+    let marker = quote_spanned!(Span::mixed_site() => marker);
+    let version = quote_spanned!(Span::mixed_site() => version);
+    let slices = quote_spanned!(Span::mixed_site() => slices);
+    let entities = quote_spanned!(Span::mixed_site() => entities);
+    let len = quote_spanned!(Span::mixed_site() => len);
+
+    let mut seen = HashSet::new();
+    let mut take = Vec::<TokenStream>::new();
+    let mut bind = Vec::<TokenStream>::new();
+    for (slot, param) in params.iter().enumerate() {
+        let attrs = to_attributes(param);
+
+        #[rustfmt::skip]
+        let param_bind = match &param.param_type {
+            ParseQueryParamType::Component(name) => {
+                if seen.insert(name.to_string()) == false {
+                    return Err(syn::Error::new_spanned(
+                        &param.name,
+                        format!("duplicate component {} in query", name),
+                    ));
+                }
+
+                let slice = format_ident!("slice_{}", slot, span = Span::mixed_site());
+                take.push(quote!(
+                    #attrs
+                    let #slice = ::gecs::__internal::take_archetype_slice::<_, #name>(#marker, &mut #slices, #len);
+                ));
+
+                match param.is_mut {
+                    true => quote!(&mut #slice[#idx]),
+                    false => quote!(&#slice[#idx]),
+                }
+            }
+            ParseQueryParamType::Entity(_) | ParseQueryParamType::EntityWild => {
+                quote!(&#entities[#idx])
+            }
+            ParseQueryParamType::EntityAny => {
+                quote!(&#entities[#idx].into())
+            }
+            ParseQueryParamType::EntityDirect(_) | ParseQueryParamType::EntityDirectWild => {
+                quote!(&::gecs::__internal::archetype_entity_direct(#marker, #idx, #version))
+            }
+            ParseQueryParamType::EntityDirectAny => {
+                quote!(&::gecs::__internal::archetype_entity_direct(#marker, #idx, #version).into())
+            }
+            _ => unreachable!(), // Rejected by to_type_generic
+        };
+
+        bind.push(param_bind);
+    }
+
+    let setup = quote!(
+        let #marker = ::gecs::__internal::archetype_marker(&*#archetype);
+        let #version = ::gecs::__internal::Archetype::version(&*#archetype);
+        let #len = ::gecs::__internal::Archetype::len(&*#archetype);
+        let mut #slices = ::gecs::__internal::Archetype::get_all_slices_mut(#archetype);
+        let #entities = ::gecs::__internal::archetype_slices_entities(#marker, &#slices);
+        #(#take)*
+        // Silence warnings for any of the above that the query doesn't use
+        let _ = (&#version, &#entities, &mut #slices);
+    );
+
+    Ok((setup, bind))
+}
+
+#[allow(non_snake_case)]
+pub fn generate_derive_ecs_query(input: DeriveInput, raw_input: &str) -> syn::Result<TokenStream> {
+    let input_hash = xxh3_128(raw_input.as_bytes());
+    let __ecs_query_struct_hash = format_ident!("__ecs_query_struct_{}", input_hash);
+
+    let fields = match &input.data {
+        Data::Struct(DataStruct { fields: Fields::Named(fields), .. }) => &fields.named,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "EcsQuery can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let mut names = Vec::with_capacity(fields.len());
+    let mut types = Vec::with_capacity(fields.len());
+    let mut params = Vec::with_capacity(fields.len());
+
+    for field in fields.iter() {
+        let name = field.ident.as_ref().unwrap();
+        let Type::Reference(reference) = &field.ty else {
+            return Err(syn::Error::new_spanned(&field.ty, "expected `&` or `&mut`"));
+        };
+
+        // Each field is passed to queries as a closure parameter with its lifetime elided
+        let maybe_mut = &reference.mutability;
+        let elem = &reference.elem;
+        let param = quote!(#name: &#maybe_mut #elem);
+
+        let parsed = syn::parse2::<ParseQueryParam>(param.clone())
+            .map_err(|err| syn::Error::new_spanned(&field.ty, err))?;
+
+        match parsed.param_type {
+            ParseQueryParamType::OneOf(_)
+            | ParseQueryParamType::Option(_)
+            | ParseQueryParamType::With(_)
+            | ParseQueryParamType::Without(_)
+            | ParseQueryParamType::Struct(_) => {
+                return Err(syn::Error::new_spanned(
+                    &field.ty,
+                    "unsupported type for a query struct field",
+                ));
+            }
+            _ => {}
+        }
+
+        names.push(name);
+        types.push(&field.ty);
+        params.push(param);
+    }
+
+    let Name = &input.ident;
+    let vis = &input.vis;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote!(
+        impl #impl_generics #Name #ty_generics #where_clause {
+            #[doc(hidden)]
+            #[inline(always)]
+            #[allow(clippy::too_many_arguments)]
+            pub fn __ecs_query_new(#(#names: #types),*) -> Self {
+                Self { #(#names),* }
+            }
+        }
+
+        #[macro_export]
+        #[doc(hidden)]
+        macro_rules! #__ecs_query_struct_hash {
+            ($($args:tt)*) => {
+                ::gecs::__internal::__resolve_ecs_query_struct!({ #(#params),* }, $($args)*)
+            }
+        }
+
+        // Shares the struct's name so that importing the struct also imports its macro
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        #vis use #__ecs_query_struct_hash as #Name;
+    ))
+}
+
+pub fn generate_query_struct_lookup(
+    target: TokenStream,
+    param: &Ident,
+    query_struct: &ParseQueryStruct,
+    raw: TokenStream,
+) -> TokenStream {
+    let path = &query_struct.path;
+    let path_string = path.to_token_stream().to_string();
+
+    // The struct's macro will call back into __resolve_ecs_query_struct with its fields
+    quote!(
+        {
+            #path!(#target, #param, #path_string, { #raw })
+        }
+    )
+}
+
+pub fn generate_query_struct_resolve(resolve: ParseQueryStructResolve) -> syn::Result<TokenStream> {
+    let ParseQueryStructResolve { fields, target, name, path, raw } = resolve;
+    let mut tokens = raw.into_iter().collect::<Vec<_>>();
+
+    // Attach the fields to the struct's type, as in `m: Movement @ { pos: &mut Pos }`
+    let Some(end) = find_query_struct_param(&tokens, &name, &path.value()) else {
+        return Err(syn::Error::new_spanned(name, "failed to resolve query struct"));
+    };
+    tokens.splice(end..end, quote!(@ { #fields }));
+
+    let raw = tokens.into_iter().collect::<TokenStream>();
+    Ok(quote!(#target!(#raw)))
+}
+
+#[allow(non_snake_case)]
+pub fn generate_query_iter_budget(
+    query: ParseCfgDecorated<ParseQueryIterBudget>, //.
+) -> syn::Result<TokenStream> {
+    let mut query_data = query.inner;
+    let world_data = DataWorld::from_base64(&query_data.world_data);
+
+    // Precompute the cfg-enabled status of any parameter in the predicate.
+    for param in query_data.params.iter_mut() {
+        param.is_cfg_enabled = is_cfg_enabled(param, &query.cfg_lookup);
+    }
+
+    let bound_params = bind_query_params(&world_data, &query_data.params)?;
+    check_expected_archetypes(&query_data.expect, &world_data, |name| {
+        bound_params.contains_key(name)
+    })?;
+    strip_filters(&mut query_data.params);
+
+    // Variables and fields
+    let world = &query_data.world;
+    let cursor = &query_data.cursor;
+    let budget = &query_data.budget;
+    let body = &query_data.body;
+    let arg = query_data.params.iter().map(to_name).collect::<Vec<_>>();
+    let attrs = query_data
+        .params
+        .iter()
+        .map(to_attributes)
+        .collect::<Vec<_>>();
+    let maybe_mut = query_data
+        .params
+        .iter()
+        .map(to_maybe_mut)
+        .collect::<Vec<_>>();
+
+    // We want these to be hygenic because they're declared above the closure.
+    let cursor_ref = quote_spanned!(Span::mixed_site() => cursor);
+    let remaining = quote_spanned!(Span::mixed_site() => remaining);
+    let resume = quote_spanned!(Span::mixed_site() => resume);
+    let active = quote_spanned!(Span::mixed_site() => active);
+
+    let mut archetype_ids = Vec::<TokenStream>::new();
+    let mut queries = Vec::<TokenStream>::new();
+    for archetype in world_data.archetypes {
+        if let Some(bound_params) = bound_params.get(&archetype.name) {
+            // Types and traits
+            let Archetype = format_ident!("{}", archetype.name);
+            let Type = bound_params
+                .iter()
+                .map(|p| to_type(p, &archetype))
+                .collect::<Vec<_>>(); // Bind-dependent!
+
+            // Variables
+            let archetype = format_ident!("{}", util::to_snake(&archetype.name));
+            let bind = bound_params.iter().map(iter_bind_mut).collect::<Vec<_>>();
+            let archetype_id = quote!(<#Archetype as ::gecs::__internal::Archetype>::ARCHETYPE_ID);
+
+            archetype_ids.push(archetype_id.clone());
+            queries.push(quote!(
+                // Skip over archetypes until we reach the one the cursor stopped in
+                if #active == false && #resume.map(|(id, _, _)| id) == Some(#archetype_id) {
+                    #active = true;
+                }
+
+                if #active {
+                    // Alias the current archetype for use in the closure
+                    type MatchedArchetype = #Archetype;
+                    // The closure needs to be made per-archetype because of OneOf types
+                    let mut closure = |#(#attrs #arg: &#maybe_mut #Type),*| #body;
+
+                    let archetype = &mut #world.#archetype;
+                    let version = archetype.version();
+                    let len = archetype.len();
+                    let slices = archetype.get_all_slices_mut();
+
+                    // Restart this archetype if it may have been reordered since we stopped
+                    let start = match #resume.take() {
+                        Some((_, idx, resume_version)) if resume_version == version => idx,
+                        _ => 0,
+                    };
+
+                    for idx in start..len {
+                        if #remaining == 0 {
+                            #cursor_ref.set_position(#archetype_id, idx, version);
+                            return false;
+                        }
+                        #remaining -= 1;
+
+                        match closure(#(#attrs #bind),*).into() {
+                            EcsStep::Continue => {
+                                // Continue
+                            },
+                            EcsStep::Break => {
+                                #cursor_ref.set_position(#archetype_id, idx + 1, version);
+                                return false;
+                            },
+                        }
+                    }
+                }
+            ));
+        }
+    }
+
+    if queries.is_empty() {
+        Err(syn::Error::new_spanned(
+            world,
+            "query matched no archetypes in world",
+        ))
+    } else {
+        Ok(quote!(
+            // Use a closure so we can use return to cancel other archetype iterations
+            (|| -> bool {
+                let #cursor_ref: &mut ::gecs::iter::IterCursor = #cursor;
+                let mut #remaining: usize = #budget;
+
+                // Start over if the cursor stopped in an archetype this query doesn't match
+                let mut #resume = #cursor_ref.take_position();
+                if let Some((id, _, _)) = #resume {
+                    if #(id != #archetype_ids)&&* {
+                        #resume = None;
+                    }
+                }
+                let mut #active = #resume.is_none();
+
+                #(#queries)*
+                true
+            })()
+        ))
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn generate_query_iter_sorted(
+    query: ParseCfgDecorated<ParseQueryIterSorted>, //.
+) -> syn::Result<TokenStream> {
+    let mut query_data = query.inner;
+    let world_data = DataWorld::from_base64(&query_data.world_data);
+
+    // Precompute the cfg-enabled status of any parameter in either closure.
+    for param in query_data.key_params.iter_mut() {
+        param.is_cfg_enabled = is_cfg_enabled(param, &query.cfg_lookup);
+    }
+    for param in query_data.params.iter_mut() {
+        param.is_cfg_enabled = is_cfg_enabled(param, &query.cfg_lookup);
+    }
+
+    let bound_key_params = bind_query_params(&world_data, &query_data.key_params)?;
+    let bound_params = bind_query_params(&world_data, &query_data.params)?;
+    check_expected_archetypes(&query_data.expect, &world_data, |name| {
+        bound_key_params.contains_key(name) && bound_params.contains_key(name)
+    })?;
+    strip_filters(&mut query_data.key_params);
+    strip_filters(&mut query_data.params);
+
+    // Variables and fields
+    let world = &query_data.world;
+    let key = &query_data.key;
+    let body = &query_data.body;
+    let key_arg = query_data.key_params.iter().map(to_name).collect::<Vec<_>>();
+    let key_attrs = query_data
+        .key_params
+        .iter()
+        .map(to_attributes)
+        .collect::<Vec<_>>();
+    let arg = query_data.params.iter().map(to_name).collect::<Vec<_>>();
+    let attrs = query_data
+        .params
+        .iter()
+        .map(to_attributes)
+        .collect::<Vec<_>>();
+    let maybe_mut = query_data
+        .params
+        .iter()
+        .map(to_maybe_mut)
+        .collect::<Vec<_>>();
+
+    // We want this to be hygenic because it's declared above the closures.
+    let keys = quote_spanned!(Span::mixed_site() => keys);
+
+    let mut gathers = Vec::<TokenStream>::new();
+    let mut fetches = Vec::<TokenStream>::new();
+    let mut dispatches = Vec::<TokenStream>::new();
+    for archetype in world_data.archetypes {
+        // Only visit archetypes matching both the key and the body closures
+        let (Some(bound_key_params), Some(bound_params)) = (
+            bound_key_params.get(&archetype.name),
+            bound_params.get(&archetype.name),
+        ) else {
+            continue;
+        };
+
+        // Types and traits
+        let Archetype = format_ident!("{}", archetype.name);
+        let KeyType = bound_key_params
+            .iter()
+            .map(|p| to_type(p, &archetype))
+            .collect::<Vec<_>>(); // Bind-dependent!
+        let Type = bound_params
+            .iter()
+            .map(|p| to_type(p, &archetype))
+            .collect::<Vec<_>>(); // Bind-dependent!
+
+        // Variables
+        let position = gathers.len();
+        let snake = util::to_snake(&archetype.name);
+        let archetype = format_ident!("{}", snake);
+        let slices = Ident::new(&format!("slices_{}", snake), Span::mixed_site());
+        let version = Ident::new(&format!("version_{}", snake), Span::mixed_site());
+        let key_bind = bound_key_params
+            .iter()
+            .map(iter_bind_mut)
+            .collect::<Vec<_>>();
+        let bind = bound_params
+            .iter()
+            .map(|p| iter_bind_mut_at(p, &quote!(#slices), &quote!(idx)))
+            .collect::<Vec<_>>();
+
+        gathers.push(quote!(
+            {
+                // Alias the current archetype for use in the closure
+                type MatchedArchetype = #Archetype;
+                // The closure needs to be made per-archetype because of OneOf types
+                let mut closure = |#(#key_attrs #key_arg: &#KeyType),*| #key;
+
+                let archetype = &mut #world.#archetype;
+                let version = archetype.version();
+                let len = archetype.len();
+                let slices = archetype.get_all_slices_mut();
+
+                for idx in 0..len {
+                    #keys.push((closure(#(#key_attrs #key_bind),*), #position, idx));
+                }
+            }
+        ));
+
+        fetches.push(quote!(
+            let #version = #world.#archetype.version();
+            let #slices = #world.#archetype.get_all_slices_mut();
+        ));
+
+        dispatches.push(quote!(
+            #position => {
+                // Alias the current archetype for use in the closure
+                type MatchedArchetype = #Archetype;
+                // The closure is made for each entity, since closures for different
+                // archetypes can't all hold mutable captures at the same time
+                let mut closure = |#(#attrs #arg: &#maybe_mut #Type),*| #body;
+                let version = #version;
+
+                closure(#(#attrs #bind),*).into()
+            }
+        ));
+    }
+
+    if gathers.is_empty() {
+        Err(syn::Error::new_spanned(
+            world,
+            "query matched no archetypes in world",
+        ))
+    } else {
+        Ok(quote!(
+            // Use a closure so we can use return to cancel other archetype iterations
+            (|| {
+                // Gather the sort key for every matching entity along with its location
+                let mut #keys = Vec::new();
+                #(#gathers)*
+
+                // Stable sort, so ties stay in the same order as ecs_iter!
+                #keys.sort_by(|a, b| ::std::cmp::Ord::cmp(&a.0, &b.0));
+
+                #(#fetches)*
+                for (_, position, idx) in #keys {
+                    let step: EcsStep = match position {
+                        #(#dispatches)*
+                        _ => unreachable!(),
+                    };
+
+                    match step {
+                        EcsStep::Continue => {
+                            // Continue
+                        },
+                        EcsStep::Break => {
+                            return;
+                        },
+                    }
+                }
+            })()
+        ))
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn generate_query_iter_destroy(
+    mode: FetchMode,
+    query: ParseCfgDecorated<ParseQueryIterDestroy>,
+) -> syn::Result<TokenStream> {
+    let mut query_data = query.inner;
+    let world_data = DataWorld::from_base64(&query_data.world_data);
+
+    // Precompute the cfg-enabled status of any parameter in the predicate.
+    for param in query_data.params.iter_mut() {
+        param.is_cfg_enabled = is_cfg_enabled(param, &query.cfg_lookup);
+    }
+
+    let bound_params = bind_query_params(&world_data, &query_data.params)?;
+    check_expected_archetypes(&query_data.expect, &world_data, |name| {
+        bound_params.contains_key(name)
+    })?;
+    strip_filters(&mut query_data.params);
+    // NOTE: Beyond this point, query.params is only safe to use for information that
+    // does not change depending on the type of the parameter (e.g. mutability). Anything
+    // that might change after OneOf binding etc. must use the bound query params in
+    // bound_params for the given archetype. Note that it's faster to use query.params
+    // where available, since it avoids redundant computation for each archetype.
+
+    // Variables and fields
+    let world = &query_data.world;
+    let body = &query_data.body;
+    let arg = query_data.params.iter().map(to_name).collect::<Vec<_>>();
+    let attrs = query_data
+        .params
+        .iter()
+        .map(to_attributes)
+        .collect::<Vec<_>>();
+
+    // Special cases
+    let maybe_mut = query_data
+        .params
+        .iter()
+        .map(to_maybe_mut)
+        .collect::<Vec<_>>();
+
+    let mut queries = Vec::<TokenStream>::new();
+    for archetype in world_data.archetypes {
+        if let Some(bound_params) = bound_params.get(&archetype.name) {
+            // Types and traits
+            let Archetype = format_ident!("{}", archetype.name);
+            let Type = bound_params
+                .iter()
+                .map(|p| to_type(p, &archetype))
+                .collect::<Vec<_>>(); // Bind-dependent!
+
+            // Variables
+            let archetype = format_ident!("{}", util::to_snake(&archetype.name));
+
+            // Fetch the archetype directly to allow queries to be sneaky with
+            // direct archetype access to get cross-archetype nested mutability
+            #[rustfmt::skip]
+            let get_archetype = match mode {
+                FetchMode::Borrow => quote!(&#world.#archetype),
+                FetchMode::Mut => quote!(&mut #world.#archetype),
+            };
+
+            #[rustfmt::skip]
+            let get_slices = match mode {
+                FetchMode::Borrow => panic!("borrow unsupported for iter_destroy"),
+                FetchMode::Mut => quote!(archetype.get_all_slices_mut()),
+            };
+
+            #[rustfmt::skip]
+            let bind = match mode {
+                FetchMode::Borrow => panic!("borrow unsupported for iter_destroy"),
+                FetchMode::Mut => bound_params.iter().map(iter_bind_mut).collect::<Vec<_>>(),
+            };
+
+            queries.push(quote!(
+                {
+                    // Alias the current archetype for use in the closure
+                    type MatchedArchetype = #Archetype;
+                    // The closure needs to be made per-archetype because of OneOf types
+                    let mut closure = |#(#attrs #arg: &#maybe_mut #Type),*| #body;
+
+                    let archetype = #get_archetype;
+                    let version = archetype.version();
+                    let len = archetype.len();
+
+                    // Iterate in reverse order to still visit each entity once.
+                    // Note: This assumes that we remove entities by swapping.
+                    for idx in (0..len).rev() {
+                        let slices = #get_slices;
+                        match closure(#(#attrs #bind),*).into() {
+                            EcsStepDestroy::Continue => {
+                                // Continue
+                            },
+                            EcsStepDestroy::Break => {
+                                return;
+                            },
+                            EcsStepDestroy::ContinueDestroy => {
+                                let entity = slices.entity[idx];
+                                archetype.destroy(entity);
+                            },
+                            EcsStepDestroy::BreakDestroy => {
+                                let entity = slices.entity[idx];
+                                archetype.destroy(entity);
+                                return;
+                            },
+                        }
+                    }
+                }
+            ));
+        }
+    }
+
+    if queries.is_empty() {
+        Err(syn::Error::new_spanned(
+            world,
+            "query matched no archetypes in world",
+        ))
+    } else {
+        Ok(quote!(
+            // Use a closure so we can use return to cancel other archetype iterations
+            (||{#(#queries)*})()
+        ))
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn generate_query_iterator(
+    query: ParseCfgDecorated<ParseQueryIterator>, //.
+) -> syn::Result<TokenStream> {
+    let mut query_data = query.inner;
+    let world_data = DataWorld::from_base64(&query_data.world_data);
+
+    // Precompute the cfg-enabled status of any parameter in the predicate.
+    for param in query_data.params.iter_mut() {
+        param.is_cfg_enabled = is_cfg_enabled(param, &query.cfg_lookup);
+    }
+
+    // Unlike closure-based queries, we yield the same item type for every archetype, so
+    // we can't allow parameters whose type depends on the archetype they were bound to.
+    for param in query_data.params.iter() {
+        match &param.param_type {
+            ParseQueryParamType::EntityDirect(_)
+            | ParseQueryParamType::EntityDirectAny
+            | ParseQueryParamType::EntityDirectWild => {
+                return Err(syn::Error::new_spanned(
+                    &param.name,
+                    "direct entity handles are not supported in ecs_query!",
+                ));
+            }
+            ParseQueryParamType::DynSlice(..) => {
+                return Err(syn::Error::new_spanned(
+                    &param.name,
+                    "dyn slice parameters are not supported in ecs_query!",
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    let bound_params = bind_query_params(&world_data, &query_data.params)?;
+    check_expected_archetypes(&query_data.expect, &world_data, |name| {
+        bound_params.contains_key(name)
+    })?;
+    strip_filters(&mut query_data.params);
+
+    // Variables and fields
+    let world = &query_data.world;
+    let arg = query_data
+        .params
+        .iter()
+        .filter(|param| param.is_cfg_enabled)
+        .enumerate()
+        .map(|(idx, _)| format_ident!("__{}", idx, span = Span::mixed_site()))
+        .collect::<Vec<_>>();
+
+    let mut bound_types = HashMap::<String, (String, String)>::new();
+    let mut queries = Vec::<TokenStream>::new();
+    for archetype in world_data.archetypes.iter() {
+        if let Some(bound_params) = bound_params.get(&archetype.name) {
+            let bound_params = bound_params
+                .iter()
+                .filter(|param| param.is_cfg_enabled)
+                .collect::<Vec<_>>();
+
+            // Make sure every parameter binds to the same type in every archetype
+            for param in bound_params.iter() {
+                let bound_type = to_type(param, archetype).to_string();
+                let name = param.name.to_string();
+
+                if let Some((found_type, found_archetype)) = bound_types.get(&name) {
+                    if *found_type != bound_type {
+                        return Err(syn::Error::new_spanned(
+                            &param.name,
+                            format!(
+                                "parameter binds to different types across archetypes \
+                                ({} in {}, {} in {}), which is not supported by ecs_query!",
+                                found_type, found_archetype, bound_type, archetype.name,
+                            ),
+                        ));
+                    }
+                } else {
+                    bound_types.insert(name, (bound_type, archetype.name.clone()));
+                }
+            }
+
+            // Variables
+            let archetype = format_ident!("{}", util::to_snake(&archetype.name));
+            let source = bound_params
+                .iter()
+                .map(|param| query_source(param))
+                .collect::<Vec<_>>();
+
+            let zipped = match source.split_first() {
+                None => quote!(slices.entity.iter().map(|_| ())),
+                Some((first, rest)) => {
+                    let mut zipped = first.clone();
+                    let mut pattern = arg[0].to_token_stream();
+                    for (source, arg) in rest.iter().zip(arg[1..].iter()) {
+                        zipped = quote!(#zipped.zip(#source));
+                        pattern = quote!((#pattern, #arg));
+                    }
+                    quote!(#zipped.map(|#pattern| (#(#arg,)*)))
+                }
+            };
+
+            queries.push(quote!(
+                {
+                    let slices = #world.#archetype.get_all_slices_mut();
+                    #zipped
+                }
+            ));
+        }
+    }
+
+    if queries.is_empty() {
+        return Err(syn::Error::new_spanned(
+            world,
+            "query matched no archetypes in world",
+        ));
+    }
+
+    let first = &queries[0];
+    let rest = &queries[1..];
+
+    Ok(quote!(
+        #first #(.chain(#rest))*
+    ))
+}
+
+#[rustfmt::skip]
+pub fn generate_query_archetypes(
+    query: ParseCfgDecorated<ParseQueryArchetypes>, //.
+) -> syn::Result<TokenStream> {
+    let mut query_data = query.inner;
+    let world_data = DataWorld::from_base64(&query_data.world_data);
+
+    // Precompute the cfg-enabled status of each parameter in the query.
+    for param in query_data.params.iter_mut() {
+        param.is_cfg_enabled = is_cfg_enabled(param, &query.cfg_lookup);
+    }
+
+    let bound_params = bind_query_params(&world_data, &query_data.params)?;
+    check_expected_archetypes(&query_data.expect, &world_data, |name| {
+        bound_params.contains_key(name)
+    })?;
+
+    // Unlike other queries, matching no archetypes isn't an error here
+    let mut entries = Vec::<TokenStream>::new();
+    for archetype in world_data.archetypes.iter() {
+        if bound_params.contains_key(&archetype.name) {
+            let id = archetype.id;
+            let name = &archetype.name;
+            entries.push(quote!((#id, #name)));
+        }
+    }
+
+    Ok(quote!(
+        {
+            const ARCHETYPES: &[(::gecs::entity::ArchetypeId, &str)] = &[#(#entries),*];
+            ARCHETYPES
+        }
+    ))
+}
+
+pub fn generate_query_access(
+    query: ParseCfgDecorated<ParseQueryArchetypes>, //.
+) -> syn::Result<TokenStream> {
+    let mut query_data = query.inner;
+    let world_data = DataWorld::from_base64(&query_data.world_data);
+
+    // Precompute the cfg-enabled status of each parameter in the query.
+    for param in query_data.params.iter_mut() {
+        param.is_cfg_enabled = is_cfg_enabled(param, &query.cfg_lookup);
+    }
+
+    let bound_params = bind_query_params(&world_data, &query_data.params)?;
+    check_expected_archetypes(&query_data.expect, &world_data, |name| {
+        bound_params.contains_key(name)
+    })?;
+
+    let mut entries = Vec::<TokenStream>::new();
+    for archetype in world_data.archetypes.iter() {
+        let Some(bound_params) = bound_params.get(&archetype.name) else {
+            continue;
+        };
+
+        // Collect the bound components, rejecting the same aliasing that ecs_iter! can't compile
+        let mut reads = Vec::<String>::new();
+        let mut writes = Vec::<String>::new();
+        for param in bound_params.iter().filter(|param| param.is_cfg_enabled) {
+            let components = match &param.param_type {
+                ParseQueryParamType::Component(name) => std::slice::from_ref(name),
+                ParseQueryParamType::Dyn(_, found) => found,
+                ParseQueryParamType::DynSlice(_, found) => found,
+                _ => continue, // Entity handles aren't component data
+            };
+
+            for component in components.iter().map(ToString::to_string) {
+                if writes.contains(&component) || (param.is_mut && reads.contains(&component)) {
+                    return Err(syn::Error::new(
+                        param.name.span(),
+                        format!(
+                            "query accesses {} in {} mutably and through another parameter",
+                            component, archetype.name,
+                        ),
+                    ));
+                } else if param.is_mut {
+                    writes.push(component);
+                } else if reads.contains(&component) == false {
+                    reads.push(component);
+                }
+            }
+        }
+
+        let id = archetype.id;
+        let name = &archetype.name;
+        entries.push(quote!(
+            ::gecs::system::ArchetypeAccess {
+                id: #id,
+                name: #name,
+                reads: &[#(#reads),*],
+                writes: &[#(#writes),*],
+            }
+        ));
+    }
+
+    Ok(quote!(
+        {
+            const ACCESS: &[::gecs::system::ArchetypeAccess] = &[#(#entries),*];
+            ACCESS
+        }
+    ))
+}
+
+#[allow(non_snake_case)]
+pub fn generate_query_count(
+    query: ParseCfgDecorated<ParseQueryPredicate>, //.
+) -> syn::Result<TokenStream> {
+    let mut query_data = query.inner;
+    let world_data = DataWorld::from_base64(&query_data.world_data);
+
+    // Precompute the cfg-enabled status of any parameter in the predicate.
+    for param in query_data.params.iter_mut() {
+        param.is_cfg_enabled = is_cfg_enabled(param, &query.cfg_lookup);
+    }
+
+    let bound_params = bind_query_params(&world_data, &query_data.params)?;
+    check_expected_archetypes(&query_data.expect, &world_data, |name| {
+        bound_params.contains_key(name)
+    })?;
+    strip_filters(&mut query_data.params);
+
+    let world = &query_data.world;
+    let count = Ident::new("__count", Span::mixed_site());
+
+    let mut queries = Vec::<TokenStream>::new();
+    for archetype in world_data.archetypes.iter() {
+        if let Some(bound_params) = bound_params.get(&archetype.name) {
+            // Without a predicate body the archetype length alone answers the question
+            queries.push(match &query_data.body {
+                None => {
+                    let archetype = format_ident!("{}", util::to_snake(&archetype.name));
+                    quote!(#count += #world.#archetype.len();)
+                }
+                Some(body) => generate_predicate_loop(
+                    world,
+                    archetype,
+                    &query_data.params,
+                    bound_params,
+                    body,
+                    quote!(#count += 1;),
+                ),
+            });
+        }
+    }
+
+    if queries.is_empty() {
+        Err(syn::Error::new_spanned(
+            world,
+            "query matched no archetypes in world",
+        ))
+    } else {
+        Ok(quote!(
+            {
+                let mut #count: usize = 0;
+                #(#queries)*
+                #count
+            }
+        ))
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn generate_query_any(
+    query: ParseCfgDecorated<ParseQueryPredicate>, //.
+) -> syn::Result<TokenStream> {
+    let mut query_data = query.inner;
+    let world_data = DataWorld::from_base64(&query_data.world_data);
+
+    // Precompute the cfg-enabled status of any parameter in the predicate.
+    for param in query_data.params.iter_mut() {
+        param.is_cfg_enabled = is_cfg_enabled(param, &query.cfg_lookup);
+    }
+
+    let bound_params = bind_query_params(&world_data, &query_data.params)?;
+    check_expected_archetypes(&query_data.expect, &world_data, |name| {
+        bound_params.contains_key(name)
+    })?;
+    strip_filters(&mut query_data.params);
+
+    let world = &query_data.world;
+
+    let mut queries = Vec::<TokenStream>::new();
+    for archetype in world_data.archetypes.iter() {
+        if let Some(bound_params) = bound_params.get(&archetype.name) {
+            // Without a predicate body any non-empty archetype answers the question
+            queries.push(match &query_data.body {
+                None => {
+                    let archetype = format_ident!("{}", util::to_snake(&archetype.name));
+                    quote!(if #world.#archetype.is_empty() == false { return true; })
+                }
+                Some(body) => generate_predicate_loop(
+                    world,
+                    archetype,
+                    &query_data.params,
+                    bound_params,
+                    body,
+                    quote!(return true;),
+                ),
+            });
+        }
+    }
+
+    if queries.is_empty() {
+        Err(syn::Error::new_spanned(
+            world,
+            "query matched no archetypes in world",
+        ))
+    } else {
+        Ok(quote!(
+            // Use a closure so we can use return to short-circuit on the first match
+            (|| -> bool { #(#queries)* false })()
+        ))
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn generate_query_fold(
+    query: ParseCfgDecorated<ParseQueryFold>, //.
+) -> syn::Result<TokenStream> {
+    let mut query_data = query.inner;
+    let world_data = DataWorld::from_base64(&query_data.world_data);
+
+    // Precompute the cfg-enabled status of any parameter in the predicate.
+    for param in query_data.params.iter_mut() {
+        param.is_cfg_enabled = is_cfg_enabled(param, &query.cfg_lookup);
+    }
+
+    let bound_params = bind_query_params(&world_data, &query_data.params)?;
+    check_expected_archetypes(&query_data.expect, &world_data, |name| {
+        bound_params.contains_key(name)
+    })?;
+    strip_filters(&mut query_data.params);
+
+    // Variables and fields
+    let world = &query_data.world;
+    let init = &query_data.init;
+    let body = &query_data.body;
+    let acc_mut = &query_data.acc_mut;
+    let acc = &query_data.acc;
+    let acc_type = query_data.acc_type.as_ref().map(|ty| quote!(: #ty));
+    let arg = query_data.params.iter().map(to_name).collect::<Vec<_>>();
+    let attrs = query_data
+        .params
+        .iter()
+        .map(to_attributes)
+        .collect::<Vec<_>>();
+    let maybe_mut = query_data
+        .params
+        .iter()
+        .map(to_maybe_mut)
+        .collect::<Vec<_>>();
+
+    let fold = Ident::new("__acc", Span::mixed_site());
+    let bind_closure = Ident::new("__bind_closure", Span::mixed_site());
+
+    let mut queries = Vec::<TokenStream>::new();
+    for archetype in world_data.archetypes.iter() {
+        if let Some(bound_params) = bound_params.get(&archetype.name) {
+            // Types and traits
+            let Archetype = format_ident!("{}", archetype.name);
+            let Type = bound_params
+                .iter()
+                .map(|p| to_type(p, archetype))
+                .collect::<Vec<_>>(); // Bind-dependent!
+
+            // The accumulator type can't be inferred from the closure body alone, so we
+            // pin the closure signature to the type of the initial value. Attributes are
+            // not permitted in Fn bounds, so only cfg-enabled parameters are listed here.
+            let BoundType = query_data
+                .params
+                .iter()
+                .zip(Type.iter())
+                .filter(|(param, _)| param.is_cfg_enabled)
+                .map(|(param, ty)| {
+                    let maybe_mut = to_maybe_mut(param);
+                    quote!(&#maybe_mut #ty)
+                })
+                .collect::<Vec<_>>();
+
+            // Variables
+            let archetype = format_ident!("{}", util::to_snake(&archetype.name));
+            let bind = bound_params.iter().map(iter_bind_mut).collect::<Vec<_>>();
+
+            queries.push(quote!(
+                {
+                    // Alias the current archetype for use in the closure
+                    type MatchedArchetype = #Archetype;
+
+                    fn #bind_closure<Acc, F>(_: &Acc, f: F) -> F
+                    where
+                        F: FnMut(Acc, #(#BoundType),*) -> Acc,
+                    {
+                        f
+                    }
+
+                    // The closure needs to be made per-archetype because of OneOf types
+                    let mut closure = #bind_closure(
+                        &#fold,
+                        |#acc_mut #acc #acc_type, #(#attrs #arg: &#maybe_mut #Type),*| #body,
+                    );
+
+                    let archetype = &mut #world.#archetype;
+                    let len = archetype.len();
+                    let slices = archetype.get_all_slices_mut();
+
+                    for idx in 0..len {
+                        #fold = closure(#fold, #(#attrs #bind),*);
+                    }
+                }
+            ));
+        }
+    }
+
+    if queries.is_empty() {
+        Err(syn::Error::new_spanned(
+            world,
+            "query matched no archetypes in world",
+        ))
+    } else {
+        Ok(quote!(
+            {
+                let mut #fold = #init;
+                #(#queries)*
+                #fold
+            }
+        ))
+    }
+}
+
+#[allow(non_snake_case)]
+fn generate_predicate_loop(
+    world: &Expr,
+    archetype: &DataArchetype,
+    params: &[ParseQueryParam],
+    bound_params: &[ParseQueryParam],
+    body: &Expr,
+    on_match: TokenStream,
+) -> TokenStream {
+    // Types and traits
+    let Archetype = format_ident!("{}", archetype.name);
+    let Type = bound_params
+        .iter()
+        .map(|p| to_type(p, archetype))
+        .collect::<Vec<_>>(); // Bind-dependent!
+
+    // Variables and fields
+    let arg = params.iter().map(to_name).collect::<Vec<_>>();
+    let attrs = params.iter().map(to_attributes).collect::<Vec<_>>();
+    let maybe_mut = params.iter().map(to_maybe_mut).collect::<Vec<_>>();
+    let bind = bound_params.iter().map(iter_bind_mut).collect::<Vec<_>>();
+    let archetype = format_ident!("{}", util::to_snake(&archetype.name));
+
+    quote!(
+        {
+            // Alias the current archetype for use in the closure
+            type MatchedArchetype = #Archetype;
+            // The closure needs to be made per-archetype because of OneOf types
+            let mut closure = |#(#attrs #arg: &#maybe_mut #Type),*| -> bool { #body };
+
+            let archetype = &mut #world.#archetype;
+            let len = archetype.len();
+            let slices = archetype.get_all_slices_mut();
+
+            for idx in 0..len {
+                if closure(#(#attrs #bind),*) {
+                    #on_match
+                }
+            }
+        }
+    )
+}
+
+#[allow(non_snake_case)]
+pub fn generate_query_iter_pairs(
+    query: ParseCfgDecorated<ParseQueryIterPairs>, //.
+) -> syn::Result<TokenStream> {
+    let mut query_data = query.inner;
+    let world_data = DataWorld::from_base64(&query_data.world_data);
+
+    // Precompute the cfg-enabled status of any parameter in the predicate.
+    for param in query_data.params.iter_mut() {
+        param.is_cfg_enabled = is_cfg_enabled(param, &query.cfg_lookup);
+    }
+
+    for param in query_data.params.iter() {
+        match param.param_type {
+            ParseQueryParamType::EntityDirect(_)
+            | ParseQueryParamType::EntityDirectWild
+            | ParseQueryParamType::EntityDirectAny => {
+                return Err(syn::Error::new_spanned(
+                    &param.name,
+                    "direct entity handles are not supported in ecs_iter_pairs!",
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    let bound_params = bind_query_params(&world_data, &query_data.params)?;
+    check_expected_archetypes(&query_data.expect, &world_data, |name| {
+        bound_params.contains_key(name)
+    })?;
+
+    // The second tuple only has names, so strip it alongside the first tuple's filters
+    let other = query_data
+        .params
+        .iter()
+        .zip(query_data.other.iter())
+        .filter(|(param, _)| !is_filter(param))
+        .map(|(_, name)| name.clone())
+        .collect::<Vec<_>>();
+    strip_filters(&mut query_data.params);
+
+    // Variables and fields
+    let world = &query_data.world;
+    let body = &query_data.body;
+    let arg = query_data.params.iter().map(to_name).collect::<Vec<_>>();
+    let attrs = query_data
+        .params
+        .iter()
+        .map(to_attributes)
+        .collect::<Vec<_>>();
+    let maybe_mut = query_data
+        .params
+        .iter()
+        .map(to_maybe_mut)
+        .collect::<Vec<_>>();
+
+    let matched = world_data
+        .archetypes
+        .iter()
+        .filter_map(|archetype| Some((archetype, bound_params.get(&archetype.name)?)))
+        .collect::<Vec<_>>();
+
+    let slices_a = quote_spanned!(Span::mixed_site() => slices_a);
+    let slices_b = quote_spanned!(Span::mixed_site() => slices_b);
+    let idx_a = quote_spanned!(Span::mixed_site() => idx_a);
+    let idx_b = quote_spanned!(Span::mixed_site() => idx_b);
+
+    let mut queries = Vec::<TokenStream>::new();
+    for (i, (archetype_a, bound_a)) in matched.iter().enumerate() {
+        for (archetype_b, bound_b) in matched[i..].iter() {
+            // Types and traits
+            let TypeA = bound_a
+                .iter()
+                .map(|p| to_type(p, archetype_a))
+                .collect::<Vec<_>>(); // Bind-dependent!
+            let TypeB = bound_b
+                .iter()
+                .map(|p| to_type(p, archetype_b))
+                .collect::<Vec<_>>(); // Bind-dependent!
+
+            // Variables
+            let field_a = format_ident!("{}", util::to_snake(&archetype_a.name));
+            let field_b = format_ident!("{}", util::to_snake(&archetype_b.name));
+
+            let closure = quote!(
+                // The closure needs to be made per-archetype pair because of OneOf types
+                let mut closure = |
+                    #(#attrs #arg: &#maybe_mut #TypeA,)*
+                    #(#attrs #other: &#maybe_mut #TypeB,)*
+                | #body;
+            );
+
+            if archetype_a.name == archetype_b.name {
+                // Both entities come from the same archetype, so mutable columns must be split
+                let mut fetch = Vec::<TokenStream>::new();
+                let mut bind_a = Vec::<TokenStream>::new();
+                let mut bind_b = Vec::<TokenStream>::new();
+                for (idx, param) in bound_a.iter().enumerate() {
+                    match param.is_mut {
+                        true => {
+                            let (fetch_pair, a, b) = pair_bind_split(param, idx);
+                            fetch.push(fetch_pair);
+                            bind_a.push(a);
+                            bind_b.push(b);
+                        }
+                        false => {
+                            bind_a.push(iter_bind_mut_at(param, &slices_a, &idx_a));
+                            bind_b.push(iter_bind_mut_at(param, &slices_a, &idx_b));
+                        }
+                    }
+                }
+
+                queries.push(quote!(
+                    {
+                        #closure
+
+                        let archetype = &mut #world.#field_a;
+                        let len = archetype.len();
+                        let #slices_a = archetype.get_all_slices_mut();
+
+                        for #idx_a in 0..len {
+                            for #idx_b in (#idx_a + 1)..len {
+                                #(#fetch)*
+                                match closure(#(#attrs #bind_a,)* #(#attrs #bind_b,)*).into() {
+                                    EcsStep::Continue => {
+                                        // Continue
+                                    },
+                                    EcsStep::Break => {
+                                        return;
+                                    },
+                                }
+                            }
+                        }
+                    }
+                ));
+            } else {
+                // Different archetypes are disjoint fields of the world, so borrow both at once
+                let bind_a = bound_a
+                    .iter()
+                    .map(|p| iter_bind_mut_at(p, &slices_a, &idx_a))
+                    .collect::<Vec<_>>();
+                let bind_b = bound_b
+                    .iter()
+                    .map(|p| iter_bind_mut_at(p, &slices_b, &idx_b))
+                    .collect::<Vec<_>>();
+
+                queries.push(quote!(
+                    {
+                        #closure
+
+                        let len_a = #world.#field_a.len();
+                        let len_b = #world.#field_b.len();
+                        let #slices_a = #world.#field_a.get_all_slices_mut();
+                        let #slices_b = #world.#field_b.get_all_slices_mut();
+
+                        for #idx_a in 0..len_a {
+                            for #idx_b in 0..len_b {
+                                match closure(#(#attrs #bind_a,)* #(#attrs #bind_b,)*).into() {
+                                    EcsStep::Continue => {
+                                        // Continue
+                                    },
+                                    EcsStep::Break => {
+                                        return;
+                                    },
+                                }
+                            }
+                        }
+                    }
+                ));
+            }
+        }
+    }
+
+    if queries.is_empty() {
+        Err(syn::Error::new_spanned(
+            world,
+            "query matched no archetypes in world",
+        ))
+    } else {
+        Ok(quote!(
+            // Use a closure so we can use return to cancel other archetype iterations
+            (||{#(#queries)*})()
+        ))
+    }
+}
+
+/// Splits a mutable parameter's column(s) to access two rows of the same archetype at once.
+/// Returns the statement fetching both rows and the bind expressions for each side.
+fn pair_bind_split(param: &ParseQueryParam, idx: usize) -> (TokenStream, TokenStream, TokenStream) {
+    let names = match &param.param_type {
+        ParseQueryParamType::Component(name) => std::slice::from_ref(name),
+        ParseQueryParamType::Dyn(_, bound) => &bound[..1],
+        ParseQueryParamType::DynSlice(_, bound) => &bound[..],
+        _ => panic!("invalid mutable pair parameter"),
+    };
+
+    let column = to_snake_idents(names);
+    let row_a = (0..names.len())
+        .map(|n| Ident::new(&format!("__a_{}_{}", idx, n), Span::mixed_site()))
+        .collect::<Vec<_>>();
+    let row_b = (0..names.len())
+        .map(|n| Ident::new(&format!("__b_{}_{}", idx, n), Span::mixed_site()))
+        .collect::<Vec<_>>();
+    let slices = quote_spanned!(Span::mixed_site() => slices_a);
+    let idx_a = quote_spanned!(Span::mixed_site() => idx_a);
+    let idx_b = quote_spanned!(Span::mixed_site() => idx_b);
+
+    let attrs = to_attributes(param);
+    let fetch = quote!(
+        #attrs
+        let (#((#row_a, #row_b),)*) = (
+            #(::gecs::__internal::get_pair_mut(&mut #slices.#column[..], #idx_a, #idx_b),)*
+        );
+    );
+
+    let (bind_a, bind_b) = match &param.param_type {
+        ParseQueryParamType::Dyn(trait_name, _) => (
+            quote!(#(#row_a)* as &mut dyn #trait_name),
+            quote!(#(#row_b)* as &mut dyn #trait_name),
+        ),
+        ParseQueryParamType::DynSlice(trait_name, _) => (
+            quote!(&mut [#(#row_a as &mut dyn #trait_name),*]),
+            quote!(&mut [#(#row_b as &mut dyn #trait_name),*]),
+        ),
+        _ => (quote!(#(#row_a)*), quote!(#(#row_b)*)),
+    };
+
+    (fetch, bind_a, bind_b)
+}
+
+fn query_source(param: &ParseQueryParam) -> TokenStream {
+    match &param.param_type {
+        ParseQueryParamType::Component(name) => {
+            let ident = Ident::new(&name.as_snake_name(), Span::call_site());
+            match param.is_mut {
+                true => quote!(slices.#ident.iter_mut()),
+                false => quote!(slices.#ident.iter()),
+            }
+        }
+        ParseQueryParamType::Entity(_) => {
+            quote!(slices.entity.iter())
+        }
+        ParseQueryParamType::EntityWild => {
+            quote!(slices.entity.iter())
+        }
+        ParseQueryParamType::EntityAny => {
+            quote!(::gecs::__internal::entity_slice_to_any(slices.entity).iter())
+        }
+        ParseQueryParamType::Dyn(trait_name, bound) => {
+            let ident = Ident::new(&bound[0].as_snake_name(), Span::call_site());
+            match param.is_mut {
+                true => quote!(slices.#ident.iter_mut().map(|c| c as &mut dyn #trait_name)),
+                false => quote!(slices.#ident.iter().map(|c| c as &dyn #trait_name)),
+            }
+        }
+        ParseQueryParamType::EntityDirect(_)
+        | ParseQueryParamType::EntityDirectWild
+        | ParseQueryParamType::EntityDirectAny
+        | ParseQueryParamType::DynSlice(..) => {
+            panic!("unsupported parameter type for ecs_query")
+        }
+        ParseQueryParamType::OneOf(_) => {
+            panic!("must unpack OneOf first")
+        }
+        ParseQueryParamType::Option(_) => {
+            todo!("Option not yet supported")
+        }
+        ParseQueryParamType::With(_) | ParseQueryParamType::Without(_) => {
+            panic!("must strip filters first")
+        }
+        ParseQueryParamType::Struct(_) => {
+            panic!("must expand query structs first")
+        }
+    }
+}
+
+#[rustfmt::skip]
+fn iter_bind_mut(param: &ParseQueryParam) -> TokenStream {
+    iter_bind_mut_at(param, &quote!(slices), &quote!(idx))
+}
+
+#[rustfmt::skip]
+fn iter_bind_mut_at(param: &ParseQueryParam, slices: &TokenStream, idx: &TokenStream) -> TokenStream {
+    match &param.param_type {
+        ParseQueryParamType::Component(name) => { 
+            let ident = Ident::new(&name.as_snake_name(), Span::call_site());
+            match param.is_mut { 
+                true => quote!(&mut #slices.#ident[#idx]),
+                false => quote!(&#slices.#ident[#idx]),
+            }
+        }
+        ParseQueryParamType::Entity(_) => {
+            quote!(&#slices.entity[#idx])
+        }
+        ParseQueryParamType::EntityWild => {
+            quote!(&#slices.entity[#idx])
+        }
+        ParseQueryParamType::EntityAny => {
+            quote!(&#slices.entity[#idx].into())
+        }
+        ParseQueryParamType::EntityDirect(_) => {
+            quote!(&::gecs::__internal::new_entity_direct::<MatchedArchetype>(#idx, version))
+        }
+        ParseQueryParamType::EntityDirectWild => {
+            quote!(&::gecs::__internal::new_entity_direct::<MatchedArchetype>(#idx, version))
+        }
+        ParseQueryParamType::EntityDirectAny => {
+            quote!(&::gecs::__internal::new_entity_direct::<MatchedArchetype>(#idx, version).into())
+        }
+        ParseQueryParamType::OneOf(_) => {
+            panic!("must unpack OneOf first")
+        }
+        ParseQueryParamType::Dyn(trait_name, bound) => {
+            let ident = Ident::new(&bound[0].as_snake_name(), Span::call_site());
+            match param.is_mut {
+                true => quote!(&mut #slices.#ident[#idx] as &mut dyn #trait_name),
+                false => quote!(&#slices.#ident[#idx] as &dyn #trait_name),
+            }
+        }
+        ParseQueryParamType::DynSlice(trait_name, bound) => {
+            let ident = to_snake_idents(bound);
+            match param.is_mut {
+                true => quote!(&mut [#(&mut #slices.#ident[#idx] as &mut dyn #trait_name),*]),
+                false => quote!(&[#(&#slices.#ident[#idx] as &dyn #trait_name),*]),
+            }
+        }
+        ParseQueryParamType::Option(_) => {
+            todo!("Option not yet supported")
+        }
+        ParseQueryParamType::With(_) | ParseQueryParamType::Without(_) => {
+            panic!("must strip filters first")
+        }
+        ParseQueryParamType::Struct(_) => {
+            panic!("must expand query structs first")
+        }
+    }
+}
+
+#[rustfmt::skip]
+fn iter_bind_borrow(param: &ParseQueryParam) -> TokenStream {
+    match &param.param_type {
+        ParseQueryParamType::Component(name) => {
+            match param.is_mut {
+                true => quote!(&mut archetype.borrow_slice_mut::<#name>()[idx]),
+                false => quote!(&archetype.borrow_slice::<#name>()[idx]),
+            }
+        }
+        ParseQueryParamType::Entity(_) => {
+            quote!(&archetype.entities()[idx])
+        }
+        ParseQueryParamType::EntityWild => {
             quote!(&archetype.entities()[idx])
         }
         ParseQueryParamType::EntityAny => {
@@ -575,16 +2508,113 @@ fn iter_bind_borrow(param: &ParseQueryParam) -> TokenStream {
         ParseQueryParamType::OneOf(_) => {
             panic!("must unpack OneOf first")
         }
+        ParseQueryParamType::Dyn(trait_name, bound) => {
+            let name = &bound[0];
+            match param.is_mut {
+                true => quote!(&mut archetype.borrow_slice_mut::<#name>()[idx] as &mut dyn #trait_name),
+                false => quote!(&archetype.borrow_slice::<#name>()[idx] as &dyn #trait_name),
+            }
+        }
+        ParseQueryParamType::DynSlice(trait_name, bound) => {
+            match param.is_mut {
+                true => quote!(&mut [#(&mut archetype.borrow_slice_mut::<#bound>()[idx] as &mut dyn #trait_name),*]),
+                false => quote!(&[#(&archetype.borrow_slice::<#bound>()[idx] as &dyn #trait_name),*]),
+            }
+        }
         ParseQueryParamType::Option(_) => {
             todo!("Option not yet supported")
         }
-        ParseQueryParamType::With(_) => {
-            todo!("With not yet supported")
+        ParseQueryParamType::With(_) | ParseQueryParamType::Without(_) => {
+            panic!("must strip filters first")
+        }
+        ParseQueryParamType::Struct(_) => {
+            panic!("must expand query structs first")
+        }
+    }
+}
+
+#[rustfmt::skip]
+fn iter_bind_shared(param: &ParseQueryParam) -> TokenStream {
+    let get_slice = |name: &ParseComponentName| quote!(archetype.get_slice::<#name>());
+
+    match &param.param_type {
+        ParseQueryParamType::Component(name) => {
+            let slice = get_slice(name);
+            quote!(&#slice[idx])
+        }
+        ParseQueryParamType::Dyn(trait_name, bound) => {
+            let slice = get_slice(&bound[0]);
+            quote!(&#slice[idx] as &dyn #trait_name)
+        }
+        ParseQueryParamType::DynSlice(trait_name, bound) => {
+            let slice = bound.iter().map(get_slice);
+            quote!(&[#(&#slice[idx] as &dyn #trait_name),*])
+        }
+        // Everything else is already read-only when borrowed
+        _ => iter_bind_borrow(param),
+    }
+}
+
+fn find_query_struct_param(tokens: &[TokenTree], name: &Ident, path: &str) -> Option<usize> {
+    for (idx, window) in tokens.windows(2).enumerate() {
+        let (TokenTree::Ident(ident), TokenTree::Punct(colon)) = (&window[0], &window[1]) else {
+            continue;
+        };
+
+        if ident != name || colon.as_char() != ':' || colon.spacing() != Spacing::Alone {
+            continue;
+        }
+
+        // Closure parameters follow the opening `|`, a `,`, or an attribute
+        match idx.checked_sub(1).map(|prev| &tokens[prev]) {
+            Some(TokenTree::Punct(punct)) if matches!(punct.as_char(), '|' | ',') => {}
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => {}
+            _ => continue,
+        }
+
+        // The type runs until the next `,` or `|` outside of any generic arguments
+        let start = idx + 2;
+        let mut end = start;
+        let mut depth = 0;
+        while let Some(token) = tokens.get(end) {
+            if let TokenTree::Punct(punct) = token {
+                match punct.as_char() {
+                    '<' => depth += 1,
+                    '>' => depth -= 1,
+                    ',' | '|' if depth == 0 => break,
+                    _ => {}
+                }
+            }
+            end += 1;
+        }
+
+        // Skip references and any query structs that have already been resolved
+        let is_punct = |token: &TokenTree, c: char| match token {
+            TokenTree::Punct(punct) => punct.as_char() == c,
+            _ => false,
+        };
+
+        let ty = &tokens[start..end];
+        if ty.first().is_none_or(|token| is_punct(token, '&'))
+            || ty.iter().any(|token| is_punct(token, '@'))
+        {
+            continue;
         }
-        ParseQueryParamType::Without(_) => {
-            todo!("Without not yet supported")
+
+        let path_end = ty.iter().position(|token| is_punct(token, '<')).unwrap_or(ty.len());
+        if ty[..path_end].iter().cloned().collect::<TokenStream>().to_string() == path {
+            return Some(end);
         }
     }
+
+    None
+}
+
+fn to_snake_idents(names: &[ParseComponentName]) -> Vec<Ident> {
+    names
+        .iter()
+        .map(|name| Ident::new(&name.as_snake_name(), Span::call_site()))
+        .collect()
 }
 
 fn to_name(param: &ParseQueryParam) -> TokenStream {
@@ -592,6 +2622,24 @@ fn to_name(param: &ParseQueryParam) -> TokenStream {
     quote!(#name)
 }
 
+/// Gets the closure parameter type for a query on an archetype that may not be known here.
+#[rustfmt::skip]
+fn to_type_generic(param: &ParseQueryParam) -> syn::Result<TokenStream> {
+    match &param.param_type {
+        ParseQueryParamType::Component(name) => Ok(quote!(#name)),
+        ParseQueryParamType::Entity(ident) => Ok(quote!(Entity<#ident>)),
+        ParseQueryParamType::EntityWild => Ok(quote!(Entity<_>)),
+        ParseQueryParamType::EntityAny => Ok(quote!(EntityAny)),
+        ParseQueryParamType::EntityDirect(ident) => Ok(quote!(EntityDirect<#ident>)),
+        ParseQueryParamType::EntityDirectWild => Ok(quote!(EntityDirect<_>)),
+        ParseQueryParamType::EntityDirectAny => Ok(quote!(EntityDirectAny)),
+        _ => Err(syn::Error::new_spanned(
+            &param.name,
+            "archetype queries only support components and entity handles",
+        )),
+    }
+}
+
 #[rustfmt::skip]
 fn to_type(param: &ParseQueryParam, archetype: &DataArchetype) -> TokenStream {
     let archetype_name = format_ident!("{}", archetype.name);
@@ -604,9 +2652,15 @@ fn to_type(param: &ParseQueryParam, archetype: &DataArchetype) -> TokenStream {
         ParseQueryParamType::EntityDirectWild => quote!(EntityDirect<#archetype_name>),
         ParseQueryParamType::EntityDirectAny => quote!(EntityDirectAny),
         ParseQueryParamType::OneOf(_) => panic!("must unpack OneOf first"),
+        ParseQueryParamType::Dyn(trait_name, _) => quote!(dyn #trait_name),
+        ParseQueryParamType::DynSlice(trait_name, _) => match param.is_mut {
+            true => quote!([&mut dyn #trait_name]),
+            false => quote!([&dyn #trait_name]),
+        },
         ParseQueryParamType::Option(_) => todo!("Option not yet supported"),
-        ParseQueryParamType::With(_) => todo!("With not yet supported"),
-        ParseQueryParamType::Without(_) => todo!("Without not yet supported"),
+        ParseQueryParamType::With(_) => panic!("must strip filters first"),
+        ParseQueryParamType::Without(_) => panic!("must strip filters first"),
+        ParseQueryParamType::Struct(_) => panic!("must expand query structs first"),
     }
 }
 
@@ -636,6 +2690,43 @@ fn is_cfg_enabled(param: &ParseQueryParam, cfg_lookup: &HashMap<String, bool>) -
     true
 }
 
+fn check_expected_archetypes(
+    expect: &Option<ParseAttributeExpectArchetypes>,
+    world_data: &DataWorld,
+    is_match: impl Fn(&str) -> bool,
+) -> syn::Result<()> {
+    let Some(expect) = expect else {
+        return Ok(());
+    };
+
+    for expected in expect.archetypes.iter() {
+        let name = expected.to_string();
+        if world_data.archetypes.iter().all(|archetype| archetype.name != name) {
+            return Err(syn::Error::new_spanned(
+                expected,
+                format!("unknown archetype `{}` in world", name),
+            ));
+        } else if is_match(&name) == false {
+            return Err(syn::Error::new_spanned(
+                expected,
+                format!("query does not match expected archetype `{}`", name),
+            ));
+        }
+    }
+
+    for archetype in world_data.archetypes.iter() {
+        let is_expected = expect.archetypes.iter().any(|expected| *expected == archetype.name);
+        if is_match(&archetype.name) && is_expected == false {
+            return Err(syn::Error::new(
+                expect.span,
+                format!("query matches unexpected archetype `{}`", archetype.name),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 fn bind_query_params(
     world_data: &DataWorld,
     params: &[ParseQueryParam],
@@ -717,7 +2808,7 @@ fn bind_query_params(
                         continue; // Skip this entirely
                     }
 
-                    if let Some(found) = bind_one_of(archetype, args)? {
+                    if let Some(found) = bind_one_of(archetype, args, param.name.span())? {
                         // Convert this to a new Component type
                         binding.push(ParseQueryParam {
                             cfgs: param.cfgs.clone(),
@@ -732,16 +2823,90 @@ fn bind_query_params(
                     }
                 }
 
+                ParseQueryParamType::Dyn(trait_name, _) => {
+                    if param.is_cfg_enabled == false {
+                        continue; // Skip this entirely
+                    }
+
+                    let found = archetype.bind_implements(trait_name);
+
+                    // A single dyn parameter can only match one component in a given archetype
+                    if found.len() > 1 {
+                        return Err(syn::Error::new(
+                            trait_name.span(),
+                            format!(
+                                "dyn {} parameter is ambiguous for {}, matching both {} and {} \
+                                (use &[&dyn {}] to access all of them)",
+                                trait_name, archetype.name, found[0], found[1], trait_name,
+                            ),
+                        ));
+                    }
+
+                    if found.is_empty() {
+                        matches = false;
+                        break; // No need to check more
+                    }
+
+                    binding.push(ParseQueryParam {
+                        cfgs: param.cfgs.clone(),
+                        name: param.name.clone(),
+                        is_mut: param.is_mut,
+                        param_type: ParseQueryParamType::Dyn(trait_name.clone(), found.into()),
+                        is_cfg_enabled: param.is_cfg_enabled,
+                    });
+                }
+
+                ParseQueryParamType::DynSlice(trait_name, _) => {
+                    if param.is_cfg_enabled == false {
+                        continue; // Skip this entirely
+                    }
+
+                    let found = archetype.bind_implements(trait_name);
+
+                    if found.is_empty() {
+                        matches = false;
+                        break; // No need to check more
+                    }
+
+                    binding.push(ParseQueryParam {
+                        cfgs: param.cfgs.clone(),
+                        name: param.name.clone(),
+                        is_mut: param.is_mut,
+                        param_type: ParseQueryParamType::DynSlice(trait_name.clone(), found.into()),
+                        is_cfg_enabled: param.is_cfg_enabled,
+                    });
+                }
+
                 ParseQueryParamType::Option(_) => {
                     todo!("Option not yet supported")
                 }
 
-                ParseQueryParamType::With(_) => {
-                    todo!("With not yet supported")
+                ParseQueryParamType::With(name) => {
+                    if param.is_cfg_enabled == false {
+                        continue; // Skip this entirely
+                    }
+
+                    // Filters only affect archetype matching and are never bound
+                    if archetype.try_bind_component(name)?.is_none() {
+                        matches = false;
+                        break; // No need to check more
+                    }
+                }
+
+                ParseQueryParamType::Without(name) => {
+                    if param.is_cfg_enabled == false {
+                        continue; // Skip this entirely
+                    }
+
+                    // Filters only affect archetype matching and are never bound
+                    if archetype.try_bind_component(name)?.is_some() {
+                        matches = false;
+                        break; // No need to check more
+                    }
                 }
 
-                ParseQueryParamType::Without(_) => {
-                    todo!("Without not yet supported")
+                ParseQueryParamType::Struct(_) => {
+                    panic!("must expand query structs first")
                 }
             }
         }
@@ -755,9 +2920,116 @@ fn bind_query_params(
     Ok(result)
 }
 
+fn strip_filters(params: &mut Vec<ParseQueryParam>) {
+    // Filters only affect which archetypes are matched, so after binding we can
+    // remove them entirely and treat the query as if they were never there.
+    params.retain(|param| !is_filter(param));
+}
+
+fn is_filter(param: &ParseQueryParam) -> bool {
+    matches!(
+        param.param_type,
+        ParseQueryParamType::With(_) | ParseQueryParamType::Without(_)
+    )
+}
+
+fn explain_query_mismatch(
+    world_data: &DataWorld,
+    params: &[ParseQueryParam],
+) -> Option<syn::Error> {
+    let (pinned_param, pinned_name) = params
+        .iter()
+        .filter(|param| param.is_cfg_enabled)
+        .find_map(|param| match &param.param_type {
+            ParseQueryParamType::Entity(name) => Some((param, name)),
+            ParseQueryParamType::EntityDirect(name) => Some((param, name)),
+            _ => None,
+        })?;
+
+    let Some(archetype) = world_data
+        .archetypes
+        .iter()
+        .find(|archetype| *pinned_name == archetype.name)
+    else {
+        return Some(syn::Error::new(
+            pinned_param.name.span(),
+            format!("unknown archetype `{}` in world", pinned_name),
+        ));
+    };
+
+    params
+        .iter()
+        .filter(|param| param.is_cfg_enabled)
+        .find_map(|param| {
+            let reason = explain_param_mismatch(archetype, param)?;
+            Some(syn::Error::new(
+                param.name.span(),
+                format!("{} ({})", reason, archetype.describe_components()),
+            ))
+        })
+}
+
+fn explain_param_mismatch(archetype: &DataArchetype, param: &ParseQueryParam) -> Option<String> {
+    // Binding errors (e.g. ambiguity) were already reported, so we only look for misses here
+    match &param.param_type {
+        ParseQueryParamType::Component(name) | ParseQueryParamType::With(name) => {
+            match archetype.try_bind_component(name) {
+                Ok(None) => Some(format!(
+                    "archetype `{}` has no component `{}`",
+                    archetype.name, name,
+                )),
+                _ => None,
+            }
+        }
+
+        ParseQueryParamType::Without(name) => match archetype.try_bind_component(name) {
+            Ok(Some(_)) => Some(format!(
+                "archetype `{}` has component `{}`, which this parameter excludes",
+                archetype.name, name,
+            )),
+            _ => None,
+        },
+
+        ParseQueryParamType::Entity(name) | ParseQueryParamType::EntityDirect(name) => {
+            match *name == archetype.name {
+                false => Some(format!(
+                    "query can't match both archetype `{}` and archetype `{}`",
+                    name, archetype.name,
+                )),
+                true => None,
+            }
+        }
+
+        ParseQueryParamType::OneOf(args) => match bind_one_of(archetype, args, param.name.span()) {
+            Ok(None) => Some(format!(
+                "archetype `{}` has none of the components {}",
+                archetype.name,
+                args.iter()
+                    .map(|arg| format!("`{}`", arg))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )),
+            _ => None,
+        },
+
+        ParseQueryParamType::Dyn(trait_name, _) | ParseQueryParamType::DynSlice(trait_name, _) => {
+            match archetype.bind_implements(trait_name).is_empty() {
+                true => Some(format!(
+                    "archetype `{}` has no component implementing `{}`",
+                    archetype.name, trait_name,
+                )),
+                false => None,
+            }
+        }
+
+        _ => None,
+    }
+}
+
 fn bind_one_of(
     archetype: &DataArchetype, //.
     one_of_args: &[ParseComponentName],
+    span: Span,
 ) -> syn::Result<Option<ParseQueryParamType>> {
     let mut found: Option<ParseComponentName> = None;
 
@@ -766,10 +3038,13 @@ fn bind_one_of(
             // An OneOf can only match one component in a given archetype
             if let Some(found) = found {
                 return Err(syn::Error::new(
-                    name.span(),
+                    span,
                     format!(
-                        "OneOf parameter is ambiguous for {}, matching both {} and {}",
-                        archetype.name, name, found,
+                        "OneOf parameter is ambiguous for {}, matching both {} and {} ({})",
+                        archetype.name,
+                        name,
+                        found,
+                        archetype.describe_components(),
                     ),
                 ));
             }
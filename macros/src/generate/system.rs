@@ -0,0 +1,174 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{ToTokens, quote};
+use syn::{FnArg, GenericArgument, Ident, ItemFn, Lifetime, Path, PathArguments, ReturnType, Type};
+
+use crate::parse::ParseEcsSystemArgs;
+
+enum SystemParam {
+    Query(TokenStream),
+    Resource(TokenStream),
+}
+
+pub fn generate_ecs_system(
+    args: ParseEcsSystemArgs, //.
+    item: ItemFn,
+) -> syn::Result<TokenStream> {
+    let sig = &item.sig;
+
+    if let Some(token) = sig.constness.as_ref() {
+        return Err(syn::Error::new_spanned(token, "ecs_system functions can't be const"));
+    }
+    if let Some(token) = sig.asyncness.as_ref() {
+        return Err(syn::Error::new_spanned(token, "ecs_system functions can't be async"));
+    }
+    if let Some(token) = sig.unsafety.as_ref() {
+        return Err(syn::Error::new_spanned(token, "ecs_system functions can't be unsafe"));
+    }
+    if let Some(abi) = sig.abi.as_ref() {
+        return Err(syn::Error::new_spanned(abi, "ecs_system functions can't be extern"));
+    }
+    if sig.generics.params.is_empty() == false || sig.generics.where_clause.is_some() {
+        return Err(syn::Error::new_spanned(
+            &sig.generics,
+            "ecs_system functions can't be generic",
+        ));
+    }
+    if let ReturnType::Type(_, ty) = &sig.output {
+        return Err(syn::Error::new_spanned(ty, "ecs_system functions can't return a value"));
+    }
+
+    let mut query_params = Vec::new();
+    let mut resource_params = Vec::new();
+    for input in sig.inputs.iter() {
+        match parse_system_param(input)? {
+            SystemParam::Query(param) => query_params.push(param),
+            SystemParam::Resource(param) => resource_params.push(param),
+        }
+    }
+
+    if query_params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &sig.ident,
+            "ecs_system functions need at least one query parameter",
+        ));
+    }
+
+    let attrs = &item.attrs;
+    let vis = &item.vis;
+    let name = &sig.ident;
+    let name_str = name.to_string();
+    let body = &item.block;
+    let mod_doc = format!("Access metadata for the [`{}`] system.", name_str);
+
+    // The world's query macros are exported from the same module as the world type itself
+    let world_type = args.world.to_token_stream();
+    let ecs_iter = world_macro_path(&args.world, "ecs_iter", false);
+    let ecs_query_access = world_macro_path(&args.world, "ecs_query_access", true);
+
+    // Hygienic, so the system body can't see (and conflict with) the world references
+    let world = Ident::new("world", Span::mixed_site());
+    let parts = Ident::new("parts", Span::mixed_site());
+    let lifetime = Lifetime::new("'world", Span::mixed_site());
+
+    Ok(quote!(
+        #(#attrs)*
+        #vis fn #name<#lifetime>(
+            #world: impl Into<::gecs::schedule::SystemWorld<#lifetime, #world_type>>,
+            #(#resource_params),*
+        ) {
+            let mut #world = #world.into();
+            let mut #parts = #world.parts(&#name::ACCESS);
+            #ecs_iter!(#parts, |#(#query_params),*| #body);
+        }
+
+        #[doc = #mod_doc]
+        #vis mod #name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            /// The component data this system accesses in each archetype it matches.
+            pub const ACCESS: ::gecs::system::SystemAccess = ::gecs::system::SystemAccess {
+                name: #name_str,
+                archetypes: #ecs_query_access!(|#(#query_params),*|),
+            };
+        }
+    ))
+}
+
+fn world_macro_path(world: &Path, name: &str, in_submodule: bool) -> TokenStream {
+    let mut path = world.clone();
+    let name = Ident::new(name, Span::call_site());
+    path.segments.pop();
+    path.segments.pop_punct();
+
+    // A bare world name means the world (and its macros) are visible from this module
+    if path.segments.is_empty() && path.leading_colon.is_none() {
+        return match in_submodule {
+            true => quote!(super::#name),
+            false => quote!(self::#name),
+        };
+    }
+
+    // Relative paths need to climb out of the access module we declare for the system
+    if in_submodule && path.leading_colon.is_none() {
+        match path.segments[0].ident.to_string().as_str() {
+            "self" => path.segments[0].ident = Ident::new("super", path.segments[0].ident.span()),
+            "super" => path.segments.insert(0, Ident::new("super", Span::call_site()).into()),
+            _ => {}
+        }
+    }
+
+    quote!(#path::#name)
+}
+
+fn parse_system_param(input: &FnArg) -> syn::Result<SystemParam> {
+    let input = match input {
+        FnArg::Typed(input) => input,
+        FnArg::Receiver(receiver) => {
+            return Err(syn::Error::new_spanned(
+                receiver,
+                "ecs_system functions can't take self",
+            ));
+        }
+    };
+
+    let attrs = &input.attrs;
+    let pat = &input.pat;
+
+    // Res<T> and ResMut<T> are passed in by the caller, everything else is a query parameter
+    if let Some((resource, is_mut)) = parse_resource_type(&input.ty) {
+        return Ok(SystemParam::Resource(match is_mut {
+            true => quote!(#(#attrs)* #pat: &mut #resource),
+            false => quote!(#(#attrs)* #pat: &#resource),
+        }));
+    }
+
+    let ty = &input.ty;
+    Ok(SystemParam::Query(quote!(#(#attrs)* #pat: #ty)))
+}
+
+fn parse_resource_type(ty: &Type) -> Option<(&Type, bool)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    if path.qself.is_some() || path.path.segments.len() != 1 {
+        return None;
+    }
+
+    let segment = &path.path.segments[0];
+    let is_mut = match segment.ident.to_string().as_str() {
+        "Res" => false,
+        "ResMut" => true,
+        _ => return None,
+    };
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+
+    match args.args.first() {
+        Some(GenericArgument::Type(resource)) if args.args.len() == 1 => Some((resource, is_mut)),
+        _ => None,
+    }
+}
@@ -20,6 +20,7 @@ pub fn generate_world(world_data: &DataWorld, raw_input: &str) -> TokenStream {
     // Types and traits
     let World = format_ident!("{}", world_data.name);
     let WorldCapacity = format_ident!("{}Capacity", world_data.name);
+    let WorldParts = format_ident!("{}Parts", world_data.name);
     let __WorldSelectTotal = format_ident!("__{}SelectTotal", world_data.name);
 
     let Archetype = world_data
@@ -61,6 +62,17 @@ pub fn generate_world(world_data: &DataWorld, raw_input: &str) -> TokenStream {
         .collect::<Vec<_>>();
     let num_archetypes = world_data.archetypes.len();
 
+    // Each distinct component type in the world, for bounds on the world as a whole
+    let mut WorldComponent = Vec::<TokenStream>::new();
+    let mut world_component_names = Vec::<String>::new();
+    for component in world_data.archetypes.iter().flat_map(|archetype| archetype.components.iter()) {
+        if world_component_names.contains(&component.name.to_string()) == false {
+            world_component_names.push(component.name.to_string());
+            let name = &component.name;
+            WorldComponent.push(quote!(#name));
+        }
+    }
+
     // Generated subsections
     let section_archetype = world_data
         .archetypes
@@ -79,6 +91,9 @@ pub fn generate_world(world_data: &DataWorld, raw_input: &str) -> TokenStream {
         .collect::<Vec<_>>();
     let section_event_iter = section_event_iter(&world_data);
     let section_events = section_events_world(&world_data);
+    let section_implements = section_implements(&world_data);
+    let section_query_components = section_query_components(&world_data);
+    let section_derives = section_derives_world(&world_data);
 
     // Documentation helpers
     let world_doc_archetypes = world_data
@@ -96,14 +111,27 @@ pub fn generate_world(world_data: &DataWorld, raw_input: &str) -> TokenStream {
     // Macros
     let __expand_ecs_find_hash = format_ident!("__expand_ecs_find_{}", input_hash);
     let __expand_ecs_find_borrow_hash = format_ident!("__expand_ecs_find_borrow_{}", input_hash);
+    let __expand_ecs_try_find_hash = format_ident!("__expand_ecs_try_find_{}", input_hash);
+    let __expand_ecs_try_find_borrow_hash = format_ident!("__expand_ecs_try_find_borrow_{}", input_hash);
     let __expand_ecs_iter_hash = format_ident!("__expand_ecs_iter_{}", input_hash);
     let __expand_ecs_iter_borrow_hash = format_ident!("__expand_ecs_iter_borrow_{}", input_hash);
     let __expand_ecs_iter_destroy_hash = format_ident!("__expand_ecs_iter_destroy_{}", input_hash);
+    let __expand_ecs_query_hash = format_ident!("__expand_ecs_query_{}", input_hash);
+    let __expand_ecs_count_hash = format_ident!("__expand_ecs_count_{}", input_hash);
+    let __expand_ecs_any_hash = format_ident!("__expand_ecs_any_{}", input_hash);
+    let __expand_ecs_fold_hash = format_ident!("__expand_ecs_fold_{}", input_hash);
+    let __expand_ecs_iter_pairs_hash = format_ident!("__expand_ecs_iter_pairs_{}", input_hash);
+    let __expand_ecs_find_each_hash = format_ident!("__expand_ecs_find_each_{}", input_hash);
+    let __expand_ecs_iter_budget_hash = format_ident!("__expand_ecs_iter_budget_{}", input_hash);
+    let __expand_ecs_iter_sorted_hash = format_ident!("__expand_ecs_iter_sorted_{}", input_hash);
+    let __expand_ecs_query_archetypes_hash = format_ident!("__expand_ecs_query_archetypes_{}", input_hash);
+    let __expand_ecs_query_access_hash = format_ident!("__expand_ecs_query_access_{}", input_hash);
 
     quote!(
         pub use #ecs_world_sealed::{
             #World,
             #WorldCapacity,
+            #WorldParts,
 
             SelectArchetype,
             SelectEntity,
@@ -141,6 +169,15 @@ pub fn generate_world(world_data: &DataWorld, raw_input: &str) -> TokenStream {
             // Will only appear if we have the events feature enabled.
             #section_event_iter
 
+            // Will only appear if any components were declared with #[implements(...)].
+            #section_implements
+
+            // Typed query support, matched per component at compile-time.
+            #section_query_components
+
+            // Debug, plus any traits derived for the world with #[derive(...)].
+            #section_derives
+
             /// The generated ECS world. See [`World`](gecs::traits::World) for more information.
             ///
             /// Contained archetypes[^1]:
@@ -170,6 +207,10 @@ pub fn generate_world(world_data: &DataWorld, raw_input: &str) -> TokenStream {
             impl World for #World {
                 const NUM_ARCHETYPES: usize = #num_archetypes;
 
+                const ARCHETYPES: &'static [(ArchetypeId, &'static str)] = &[
+                    #((#Archetype::ARCHETYPE_ID, #Archetype::NAME),)*
+                ];
+
                 type Capacities = #WorldCapacity;
 
                 // Will only appear if we have the events feature enabled.
@@ -188,6 +229,75 @@ pub fn generate_world(world_data: &DataWorld, raw_input: &str) -> TokenStream {
                         #( #archetype: #Archetype::#with_capacity_new, )*
                     }
                 }
+
+                #[inline]
+                fn archetype_dyn(
+                    &self,
+                    archetype_id: ArchetypeId,
+                ) -> Option<&dyn ::gecs::traits::ArchetypeDyn> {
+                    match archetype_id {
+                        #(#Archetype::ARCHETYPE_ID => Some(&self.#archetype),)*
+                        _ => None,
+                    }
+                }
+
+                #[inline]
+                fn archetype_dyn_mut(
+                    &mut self,
+                    archetype_id: ArchetypeId,
+                ) -> Option<&mut dyn ::gecs::traits::ArchetypeDyn> {
+                    match archetype_id {
+                        #(#Archetype::ARCHETYPE_ID => Some(&mut self.#archetype),)*
+                        _ => None,
+                    }
+                }
+
+                #[inline]
+                fn memory_stats(&self) -> WorldMemoryStats {
+                    WorldMemoryStats {
+                        archetypes: vec![#(self.#archetype.memory_stats(),)*],
+                    }
+                }
+            }
+
+            /// The generated ECS world, split into its archetypes for parallel systems.
+            /// See [`WorldParts`](gecs::schedule::WorldParts) for more information.
+            pub struct #WorldParts<'a> {
+                #(
+                    pub #archetype: ::gecs::schedule::ArchetypePart<'a, #Archetype>,
+                )*
+            }
+
+            impl ::gecs::schedule::WorldParts for #World {
+                type Parts<'a> = #WorldParts<'a>;
+
+                #[inline(always)]
+                unsafe fn split<'a>(
+                    world: ::std::ptr::NonNull<Self>,
+                    access: &::gecs::system::SystemAccess,
+                ) -> #WorldParts<'a> {
+                    let world = world.as_ptr();
+
+                    #WorldParts {
+                        #(
+                            #archetype: unsafe {
+                                // SAFETY: The caller guarantees access to the archetypes in access,
+                                // and we only take a pointer to each archetype, not to the world
+                                ::gecs::schedule::ArchetypePart::new(
+                                    ::std::ptr::NonNull::new_unchecked(&raw mut (*world).#archetype),
+                                    access.get(#Archetype::ARCHETYPE_ID),
+                                )
+                            },
+                        )*
+                    }
+                }
+            }
+
+            // SAFETY: This only applies if every component in the world is Sync
+            unsafe impl ::gecs::schedule::WorldSync for #World
+            where
+                #(for<'a> #WorldComponent: Sync,)*
+            {
             }
 
             impl Clone for #World
@@ -428,6 +538,15 @@ pub fn generate_world(world_data: &DataWorld, raw_input: &str) -> TokenStream {
                         )*
                     }
                 }
+
+                #[inline(always)]
+                pub fn name(self) -> &'static str {
+                    match self {
+                        #(
+                            SelectArchetype::#Archetype => #Archetype::NAME,
+                        )*
+                    }
+                }
             }
 
             impl WorldCanResolve<EntityAny> for #World {
@@ -776,6 +895,35 @@ pub fn generate_world(world_data: &DataWorld, raw_input: &str) -> TokenStream {
                     }
                 }
 
+                #[inline(always)]
+                fn resolve_view_many_mut<const N: usize>(
+                    entities: [Self; N],
+                    world: &'a mut #World,
+                ) -> Option<[Self::ViewMut; N]> {
+                    // Resolve each entity to an index in its own archetype, so that we can then
+                    // access every archetype at once through disjoint borrows of the world
+                    #(let mut #archetype = [None; N];)*
+                    for (pos, entity) in entities.into_iter().enumerate() {
+                        match entity.try_into() {
+                            #(
+                                Ok(SelectEntity::#Archetype(entity)) =>
+                                    #archetype[pos] = Some(world.#archetype.resolve_for(entity)?),
+                            )*
+                            Err(_) => panic!("invalid entity type"),
+                        }
+                    }
+
+                    #(let mut #archetype = world.#archetype.get_view_many_mut_at(#archetype)?;)*
+                    Some(::std::array::from_fn(|pos| {
+                        #(
+                            if let Some(view) = #archetype[pos].take() {
+                                return view.into();
+                            }
+                        )*
+                        unreachable!() // Every entity resolved to exactly one archetype
+                    }))
+                }
+
                 #[inline(always)]
                 fn resolve_borrow(self, world: &'a #World) -> Option<Self::Borrow> {
                     match self.try_into() {
@@ -786,6 +934,36 @@ pub fn generate_world(world_data: &DataWorld, raw_input: &str) -> TokenStream {
                         Err(_) => panic!("invalid entity type"),
                     }
                 }
+
+                #[inline(always)]
+                fn resolve_visit_components(
+                    self,
+                    world: &'a #World,
+                    visitor: &mut impl ComponentVisitor,
+                ) -> bool {
+                    match self.try_into() {
+                        #(
+                            Ok(SelectEntity::#Archetype(entity)) =>
+                                world.#archetype.visit_components(entity, visitor),
+                        )*
+                        Err(_) => panic!("invalid entity type"),
+                    }
+                }
+
+                #[inline(always)]
+                fn resolve_visit_components_mut(
+                    self,
+                    world: &'a mut #World,
+                    visitor: &mut impl ComponentVisitorMut,
+                ) -> bool {
+                    match self.try_into() {
+                        #(
+                            Ok(SelectEntity::#Archetype(entity)) =>
+                                world.#archetype.visit_components_mut(entity, visitor),
+                        )*
+                        Err(_) => panic!("invalid entity type"),
+                    }
+                }
             }
 
             impl<'a> EntityKeySelectable<'a, #World> for EntityDirectAny {
@@ -815,6 +993,35 @@ pub fn generate_world(world_data: &DataWorld, raw_input: &str) -> TokenStream {
                     }
                 }
 
+                #[inline(always)]
+                fn resolve_view_many_mut<const N: usize>(
+                    entities: [Self; N],
+                    world: &'a mut #World,
+                ) -> Option<[Self::ViewMut; N]> {
+                    // Resolve each entity to an index in its own archetype, so that we can then
+                    // access every archetype at once through disjoint borrows of the world
+                    #(let mut #archetype = [None; N];)*
+                    for (pos, entity) in entities.into_iter().enumerate() {
+                        match entity.try_into() {
+                            #(
+                                Ok(SelectEntityDirect::#Archetype(entity)) =>
+                                    #archetype[pos] = Some(world.#archetype.resolve_for(entity)?),
+                            )*
+                            Err(_) => panic!("invalid entity type"),
+                        }
+                    }
+
+                    #(let mut #archetype = world.#archetype.get_view_many_mut_at(#archetype)?;)*
+                    Some(::std::array::from_fn(|pos| {
+                        #(
+                            if let Some(view) = #archetype[pos].take() {
+                                return view.into();
+                            }
+                        )*
+                        unreachable!() // Every entity resolved to exactly one archetype
+                    }))
+                }
+
                 #[inline(always)]
                 fn resolve_borrow(self, world: &'a #World) -> Option<Self::Borrow> {
                     match self.try_into() {
@@ -825,6 +1032,36 @@ pub fn generate_world(world_data: &DataWorld, raw_input: &str) -> TokenStream {
                         Err(_) => panic!("invalid entity type"),
                     }
                 }
+
+                #[inline(always)]
+                fn resolve_visit_components(
+                    self,
+                    world: &'a #World,
+                    visitor: &mut impl ComponentVisitor,
+                ) -> bool {
+                    match self.try_into() {
+                        #(
+                            Ok(SelectEntityDirect::#Archetype(entity)) =>
+                                world.#archetype.visit_components(entity, visitor),
+                        )*
+                        Err(_) => panic!("invalid entity type"),
+                    }
+                }
+
+                #[inline(always)]
+                fn resolve_visit_components_mut(
+                    self,
+                    world: &'a mut #World,
+                    visitor: &mut impl ComponentVisitorMut,
+                ) -> bool {
+                    match self.try_into() {
+                        #(
+                            Ok(SelectEntityDirect::#Archetype(entity)) =>
+                                world.#archetype.visit_components_mut(entity, visitor),
+                        )*
+                        Err(_) => panic!("invalid entity type"),
+                    }
+                }
             }
         }
 
@@ -846,6 +1083,24 @@ pub fn generate_world(world_data: &DataWorld, raw_input: &str) -> TokenStream {
             }
         }
 
+        #[macro_export]
+        #[doc(hidden)]
+        /// See `ecs_try_find` in the `gecs` docs for more information.
+        macro_rules! #__expand_ecs_try_find_hash {
+            ($($args:tt)*) => {
+                ::gecs::__internal::__expand_ecs_try_find!(#WORLD_DATA, $($args)*)
+            }
+        }
+
+        #[macro_export]
+        #[doc(hidden)]
+        /// See `ecs_try_find_borrow` in the `gecs` docs for more information.
+        macro_rules! #__expand_ecs_try_find_borrow_hash {
+            ($($args:tt)*) => {
+                ::gecs::__internal::__expand_ecs_try_find_borrow!(#WORLD_DATA, $($args)*)
+            }
+        }
+
         #[macro_export]
         #[doc(hidden)]
         /// See `ecs_iter` in the `gecs` docs for more information.
@@ -873,19 +1128,417 @@ pub fn generate_world(world_data: &DataWorld, raw_input: &str) -> TokenStream {
             }
         }
 
+        #[macro_export]
+        #[doc(hidden)]
+        /// See `ecs_query` in the `gecs` docs for more information.
+        macro_rules! #__expand_ecs_query_hash {
+            ($($args:tt)*) => {
+                ::gecs::__internal::__expand_ecs_query!(#WORLD_DATA, $($args)*)
+            }
+        }
+
+        #[macro_export]
+        #[doc(hidden)]
+        /// See `ecs_count` in the `gecs` docs for more information.
+        macro_rules! #__expand_ecs_count_hash {
+            ($($args:tt)*) => {
+                ::gecs::__internal::__expand_ecs_count!(#WORLD_DATA, $($args)*)
+            }
+        }
+
+        #[macro_export]
+        #[doc(hidden)]
+        /// See `ecs_any` in the `gecs` docs for more information.
+        macro_rules! #__expand_ecs_any_hash {
+            ($($args:tt)*) => {
+                ::gecs::__internal::__expand_ecs_any!(#WORLD_DATA, $($args)*)
+            }
+        }
+
+        #[macro_export]
+        #[doc(hidden)]
+        /// See `ecs_fold` in the `gecs` docs for more information.
+        macro_rules! #__expand_ecs_fold_hash {
+            ($($args:tt)*) => {
+                ::gecs::__internal::__expand_ecs_fold!(#WORLD_DATA, $($args)*)
+            }
+        }
+
+        #[macro_export]
+        #[doc(hidden)]
+        /// See `ecs_iter_pairs` in the `gecs` docs for more information.
+        macro_rules! #__expand_ecs_iter_pairs_hash {
+            ($($args:tt)*) => {
+                ::gecs::__internal::__expand_ecs_iter_pairs!(#WORLD_DATA, $($args)*)
+            }
+        }
+
+        #[macro_export]
+        #[doc(hidden)]
+        /// See `ecs_find_each` in the `gecs` docs for more information.
+        macro_rules! #__expand_ecs_find_each_hash {
+            ($($args:tt)*) => {
+                ::gecs::__internal::__expand_ecs_find_each!(#WORLD_DATA, $($args)*)
+            }
+        }
+
+        #[macro_export]
+        #[doc(hidden)]
+        /// See `ecs_iter_budget` in the `gecs` docs for more information.
+        macro_rules! #__expand_ecs_iter_budget_hash {
+            ($($args:tt)*) => {
+                ::gecs::__internal::__expand_ecs_iter_budget!(#WORLD_DATA, $($args)*)
+            }
+        }
+
+        #[macro_export]
+        #[doc(hidden)]
+        /// See `ecs_iter_sorted` in the `gecs` docs for more information.
+        macro_rules! #__expand_ecs_iter_sorted_hash {
+            ($($args:tt)*) => {
+                ::gecs::__internal::__expand_ecs_iter_sorted!(#WORLD_DATA, $($args)*)
+            }
+        }
+
+        #[macro_export]
+        #[doc(hidden)]
+        /// See `ecs_query_archetypes` in the `gecs` docs for more information.
+        macro_rules! #__expand_ecs_query_archetypes_hash {
+            ($($args:tt)*) => {
+                ::gecs::__internal::__expand_ecs_query_archetypes!(#WORLD_DATA, $($args)*)
+            }
+        }
+
+        #[macro_export]
+        #[doc(hidden)]
+        /// See `ecs_query_access` in the `gecs` docs for more information.
+        macro_rules! #__expand_ecs_query_access_hash {
+            ($($args:tt)*) => {
+                ::gecs::__internal::__expand_ecs_query_access!(#WORLD_DATA, $($args)*)
+            }
+        }
+
         #[doc(inline)]
         pub use #__expand_ecs_find_hash as ecs_find;
         #[doc(inline)]
         pub use #__expand_ecs_find_borrow_hash as ecs_find_borrow;
         #[doc(inline)]
+        pub use #__expand_ecs_try_find_hash as ecs_try_find;
+        #[doc(inline)]
+        pub use #__expand_ecs_try_find_borrow_hash as ecs_try_find_borrow;
+        #[doc(inline)]
         pub use #__expand_ecs_iter_hash as ecs_iter;
         #[doc(inline)]
         pub use #__expand_ecs_iter_borrow_hash as ecs_iter_borrow;
         #[doc(inline)]
         pub use #__expand_ecs_iter_destroy_hash as ecs_iter_destroy;
+        #[doc(inline)]
+        pub use #__expand_ecs_query_hash as ecs_query;
+        #[doc(inline)]
+        pub use #__expand_ecs_count_hash as ecs_count;
+        #[doc(inline)]
+        pub use #__expand_ecs_any_hash as ecs_any;
+        #[doc(inline)]
+        pub use #__expand_ecs_fold_hash as ecs_fold;
+        #[doc(inline)]
+        pub use #__expand_ecs_iter_pairs_hash as ecs_iter_pairs;
+        #[doc(inline)]
+        pub use #__expand_ecs_find_each_hash as ecs_find_each;
+        #[doc(inline)]
+        pub use #__expand_ecs_iter_budget_hash as ecs_iter_budget;
+        #[doc(inline)]
+        pub use #__expand_ecs_iter_sorted_hash as ecs_iter_sorted;
+        #[doc(inline)]
+        pub use #__expand_ecs_query_archetypes_hash as ecs_query_archetypes;
+        #[doc(inline)]
+        pub use #__expand_ecs_query_access_hash as ecs_query_access;
     )
 }
 
+#[allow(non_snake_case)] // Allow for type-like names to make quote!() clearer
+fn section_implements(world_data: &DataWorld) -> TokenStream {
+    let mut checked = Vec::new();
+    let mut checks = Vec::new();
+
+    for archetype in world_data.archetypes.iter() {
+        for component in archetype.components.iter() {
+            for trait_name in component.implements.iter() {
+                let key = (component.name.to_string(), trait_name.clone());
+                if checked.contains(&key) {
+                    continue;
+                }
+                checked.push(key);
+
+                let Component = &component.name;
+                let Trait = format_ident!("{}", trait_name);
+
+                checks.push(quote!(
+                    const _: () = {
+                        fn assert_implements<T: #Trait>() {}
+                        let _ = assert_implements::<#Component>;
+                    };
+                ));
+            }
+        }
+    }
+
+    quote!(#(#checks)*)
+}
+
+#[allow(non_snake_case)] // Allow for type-like names to make quote!() clearer
+fn section_query_components(world_data: &DataWorld) -> TokenStream {
+    struct QueryComponent {
+        key: String,
+        Component: TokenStream,
+        Archetype: Vec<Ident>,
+        archetype: Vec<Ident>,
+        get_ptr: Vec<Ident>,
+    }
+
+    let World = format_ident!("{}", world_data.name);
+
+    // Group each distinct component type with the archetypes (and columns) that store it
+    let mut components = Vec::<QueryComponent>::new();
+    for archetype in world_data.archetypes.iter() {
+        for (idx, component) in archetype.components.iter().enumerate() {
+            let key = component.name.to_string();
+            let found = match components.iter().position(|found| found.key == key) {
+                Some(found) => found,
+                None => {
+                    let name = &component.name;
+                    components.push(QueryComponent {
+                        key,
+                        Component: quote!(#name),
+                        Archetype: Vec::new(),
+                        archetype: Vec::new(),
+                        get_ptr: Vec::new(),
+                    });
+                    components.len() - 1
+                }
+            };
+
+            let found = &mut components[found];
+            found.Archetype.push(format_ident!("{}", archetype.name));
+            found.archetype.push(format_ident!("{}", util::to_snake(&archetype.name)));
+            found.get_ptr.push(format_ident!("get_ptr_{}", idx));
+        }
+    }
+
+    let impls = components.iter().enumerate().map(|(index, component)| {
+        let QueryComponent {
+            Component,
+            Archetype,
+            archetype,
+            get_ptr,
+            ..
+        } = component;
+
+        quote!(
+            impl WorldHasComponent<#Component> for #World {
+                const QUERY_COMPONENT_INDEX: usize = #index;
+                const QUERY_ARCHETYPES: ArchetypeSet =
+                    ArchetypeSet::new(&[#(#Archetype::ARCHETYPE_ID),*]);
+
+                #[inline(always)]
+                fn resolve_query_column(
+                    &mut self,
+                    archetype_id: ArchetypeId,
+                ) -> Option<::std::ptr::NonNull<#Component>> {
+                    match archetype_id {
+                        #(#Archetype::ARCHETYPE_ID => Some(self.#archetype.data.#get_ptr()),)*
+                        _ => None,
+                    }
+                }
+            }
+        )
+    });
+
+    quote!(#(#impls)*)
+}
+
+#[allow(non_snake_case)] // Allow for type-like names to make quote!() clearer
+fn section_derives_world(world_data: &DataWorld) -> TokenStream {
+    let WORLD_NAME = world_data.name.as_str();
+    let World = format_ident!("{}", world_data.name);
+    let Archetype = world_data
+        .archetypes
+        .iter()
+        .map(|archetype| format_ident!("{}", archetype.name))
+        .collect::<Vec<_>>();
+    let archetype = world_data
+        .archetypes
+        .iter()
+        .map(|archetype| format_ident!("{}", util::to_snake(&archetype.name)))
+        .collect::<Vec<_>>();
+    let archetype_str = archetype.iter().map(Ident::to_string).collect::<Vec<_>>();
+
+    let mut result = quote!(
+        impl ::std::fmt::Debug for #World
+        where
+            #(for<'a> #Archetype: ::std::fmt::Debug,)*
+        {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_struct(#WORLD_NAME)
+                    #(.field(#archetype_str, &self.#archetype))*
+                    .finish()
+            }
+        }
+    );
+
+    if world_data.derives.iter().any(|name| name == "PartialEq") {
+        result.extend(quote!(
+            impl PartialEq for #World
+            where
+                #(for<'a> #Archetype: PartialEq,)*
+            {
+                /// Compares the data in each archetype. See the archetypes for more information.
+                #[inline]
+                fn eq(&self, other: &Self) -> bool {
+                    true #(&& self.#archetype == other.#archetype)*
+                }
+            }
+        ));
+    }
+
+    if world_data.derives.iter().any(|name| name == "Eq") {
+        result.extend(quote!(
+            impl Eq for #World
+            where
+                #(for<'a> #Archetype: Eq,)*
+            {
+            }
+        ));
+    }
+
+    result
+}
+
+#[allow(non_snake_case)] // Allow for type-like names to make quote!() clearer
+fn section_derives_archetype(archetype_data: &DataArchetype) -> TokenStream {
+    let ARCHETYPE_NAME = archetype_data.name.as_str();
+    let COMPONENTS_NAME = format!("{}Components", archetype_data.name);
+    let Archetype = format_ident!("{}", archetype_data.name);
+    let ArchetypeComponents = format_ident!("{}Components", archetype_data.name);
+    let Component = archetype_data
+        .components
+        .iter()
+        .map(|component| {
+            let name = &component.name;
+            quote!(#name)
+        })
+        .collect::<Vec<_>>();
+    let component = archetype_data
+        .components
+        .iter()
+        .map(|component| Ident::new(&component.name.as_snake_name(), Span::call_site()))
+        .collect::<Vec<_>>();
+    let component_str = component.iter().map(Ident::to_string).collect::<Vec<_>>();
+    let try_borrow_slice = (0..archetype_data.components.len())
+        .map(|idx| format_ident!("try_borrow_slice_{}", idx.to_string()))
+        .collect::<Vec<_>>();
+
+    let mut result = quote!(
+        impl ::std::fmt::Debug for #Archetype
+        where
+            #(for<'a> #Component: ::std::fmt::Debug,)*
+        {
+            /// Formats each entity in this archetype along with its component data.
+            ///
+            /// Components that are currently mutably borrowed are formatted as `<borrowed>`.
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                let entities = self.data.get_slice_entities();
+                #(let #component = self.data.#try_borrow_slice().ok();)*
+                #(let #component = #component.as_deref();)*
+
+                let rows = entities.iter().enumerate().map(|(index, entity)| {
+                    let components = DebugWith(move |f: &mut ::std::fmt::Formatter<'_>| {
+                        f.debug_struct(#COMPONENTS_NAME)
+                            #(.field(#component_str, match #component {
+                                Some(slice) => &slice[index] as &dyn ::std::fmt::Debug,
+                                None => &DebugBorrowed,
+                            }))*
+                            .finish()
+                    });
+                    (entity, components)
+                });
+
+                write!(f, "{} ", #ARCHETYPE_NAME)?;
+                f.debug_map().entries(rows).finish()
+            }
+        }
+
+        impl ::std::fmt::Debug for #ArchetypeComponents
+        where
+            #(for<'a> #Component: ::std::fmt::Debug,)*
+        {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_struct(#COMPONENTS_NAME)
+                    #(.field(#component_str, &self.#component))*
+                    .finish()
+            }
+        }
+
+        impl Clone for #ArchetypeComponents
+        where
+            #(for<'a> #Component: Clone,)*
+        {
+            #[inline(always)]
+            fn clone(&self) -> Self {
+                Self {
+                    #(#component: self.#component.clone(),)*
+                }
+            }
+        }
+    );
+
+    if archetype_data.derives.iter().any(|name| name == "PartialEq") {
+        result.extend(quote!(
+            impl PartialEq for #Archetype
+            where
+                #(for<'a> #Component: PartialEq,)*
+            {
+                /// Compares the stored entities and their component data, in storage order.
+                ///
+                /// # Panics
+                ///
+                /// This function will panic if any of its components are mutably borrowed.
+                #[inline]
+                fn eq(&self, other: &Self) -> bool {
+                    self.data == other.data
+                }
+            }
+
+            impl PartialEq for #ArchetypeComponents
+            where
+                #(for<'a> #Component: PartialEq,)*
+            {
+                #[inline]
+                fn eq(&self, other: &Self) -> bool {
+                    true #(&& self.#component == other.#component)*
+                }
+            }
+        ));
+    }
+
+    if archetype_data.derives.iter().any(|name| name == "Eq") {
+        result.extend(quote!(
+            impl Eq for #Archetype
+            where
+                #(for<'a> #Component: Eq,)*
+            {
+            }
+
+            impl Eq for #ArchetypeComponents
+            where
+                #(for<'a> #Component: Eq,)*
+            {
+            }
+        ));
+    }
+
+    result
+}
+
 #[allow(non_snake_case)] // Allow for type-like names to make quote!() clearer
 fn section_archetype(archetype_data: &DataArchetype) -> TokenStream {
     let count = archetype_data.components.len();
@@ -898,6 +1551,12 @@ fn section_archetype(archetype_data: &DataArchetype) -> TokenStream {
         .iter()
         .map(|component| component.id)
         .collect::<Vec<_>>();
+    let ARCHETYPE_NAME = archetype_data.name.as_str();
+    let COMPONENT_NAME = archetype_data
+        .components
+        .iter()
+        .map(|component| component.name.to_string())
+        .collect::<Vec<_>>();
     let component_index = (0..archetype_data.components.len())
         .into_iter()
         .map(|idx| Literal::usize_unsuffixed(idx))
@@ -930,22 +1589,45 @@ fn section_archetype(archetype_data: &DataArchetype) -> TokenStream {
     // Function names
     let get_slice = (0..count)
         .into_iter()
-        .map(|idx| format_ident!("get_slice_{}", idx.to_string()));
+        .map(|idx| format_ident!("get_slice_{}", idx.to_string()))
+        .collect::<Vec<_>>();
+    let get_slice_unchecked = (0..count)
+        .into_iter()
+        .map(|idx| format_ident!("get_slice_unchecked_{}", idx.to_string()))
+        .collect::<Vec<_>>();
     let get_slice_mut = (0..count)
         .into_iter()
-        .map(|idx| format_ident!("get_slice_mut_{}", idx.to_string()));
+        .map(|idx| format_ident!("get_slice_mut_{}", idx.to_string()))
+        .collect::<Vec<_>>();
     let borrow_component = (0..count)
         .into_iter()
-        .map(|idx| format_ident!("borrow_component_{}", idx.to_string()));
+        .map(|idx| format_ident!("borrow_component_{}", idx.to_string()))
+        .collect::<Vec<_>>();
     let borrow_component_mut = (0..count)
         .into_iter()
         .map(|idx| format_ident!("borrow_component_mut_{}", idx.to_string()));
     let borrow_slice = (0..count)
         .into_iter()
-        .map(|idx| format_ident!("borrow_slice_{}", idx.to_string()));
+        .map(|idx| format_ident!("borrow_slice_{}", idx.to_string()))
+        .collect::<Vec<_>>();
     let borrow_slice_mut = (0..count)
         .into_iter()
-        .map(|idx| format_ident!("borrow_slice_mut_{}", idx.to_string()));
+        .map(|idx| format_ident!("borrow_slice_mut_{}", idx.to_string()))
+        .collect::<Vec<_>>();
+    let try_borrow_component = (0..count)
+        .into_iter()
+        .map(|idx| format_ident!("try_borrow_component_{}", idx.to_string()));
+    let try_borrow_component_mut = (0..count)
+        .into_iter()
+        .map(|idx| format_ident!("try_borrow_component_mut_{}", idx.to_string()));
+    let try_borrow_slice = (0..count)
+        .into_iter()
+        .map(|idx| format_ident!("try_borrow_slice_{}", idx.to_string()))
+        .collect::<Vec<_>>();
+    let try_borrow_slice_mut = (0..count)
+        .into_iter()
+        .map(|idx| format_ident!("try_borrow_slice_mut_{}", idx.to_string()))
+        .collect::<Vec<_>>();
 
     // Variables/fields
     let component = archetype_data
@@ -956,6 +1638,7 @@ fn section_archetype(archetype_data: &DataArchetype) -> TokenStream {
 
     // Generated subsections
     let section_events = section_events_archetype(&archetype_data);
+    let section_derives = section_derives_archetype(&archetype_data);
 
     // Documentation helpers
     let archetype_doc_component_types = archetype_data
@@ -998,6 +1681,13 @@ fn section_archetype(archetype_data: &DataArchetype) -> TokenStream {
             #[allow(unconditional_panic)]
             const ARCHETYPE_ID: u8 = #ARCHETYPE_ID;
 
+            const NAME: &'static str = #ARCHETYPE_NAME;
+            const COMPONENT_NAMES: &'static [&'static str] = &[#(#COMPONENT_NAME),*];
+            const COMPONENT_IDS: &'static [u8] = &[#(#COMPONENT_ID),*];
+            const COMPONENT_TYPE_IDS: &'static [::std::any::TypeId] = &[
+                #(::std::any::TypeId::of::<#Component>()),*
+            ];
+
             type Components = #ArchetypeComponents;
 
             type Slices<'a> = #ArchetypeSlices<'a>;
@@ -1073,6 +1763,249 @@ fn section_archetype(archetype_data: &DataArchetype) -> TokenStream {
             fn get_all_slices_mut(&mut self) -> #ArchetypeSlices {
                 self.data.get_all_slices_mut()
             }
+
+            #[inline(always)]
+            fn resolve_slices_entities<'a>(slices: &#ArchetypeSlices<'a>) -> &'a [Entity<Self>]
+            where
+                Self: 'a,
+            {
+                slices.entity
+            }
+
+            #[inline(always)]
+            fn view_at(&mut self, index: usize) -> Option<#ArchetypeView> {
+                self.data.get_view_at(index)
+            }
+
+            #[inline(always)]
+            fn view_mut_at(&mut self, index: usize) -> Option<#ArchetypeViewMut> {
+                self.data.get_view_mut_at(index)
+            }
+
+            #[inline(always)]
+            fn get_view_many_mut_at<const N: usize>(
+                &mut self,
+                indices: [Option<usize>; N],
+            ) -> Option<[Option<#ArchetypeViewMut>; N]> {
+                self.data.get_view_many_mut_at(indices)
+            }
+
+            #[inline]
+            fn memory_stats(&self) -> ArchetypeMemoryStats {
+                self.data.memory_stats(
+                    Self::ARCHETYPE_ID,
+                    Self::NAME,
+                    [#((#COMPONENT_ID, #COMPONENT_NAME),)*],
+                )
+            }
+
+            #[inline]
+            fn visit_components<K: EntityKey>(
+                &self,
+                entity: K,
+                visitor: &mut impl ComponentVisitor,
+            ) -> bool
+            where
+                Self: ArchetypeCanResolve<K>,
+            {
+                match self.borrow(entity) {
+                    Some(borrow) => {
+                        #(visitor.visit::<#Component>(#COMPONENT_NAME, &*borrow.0.#borrow_component());)*
+                        true
+                    }
+                    None => false,
+                }
+            }
+
+            #[inline]
+            fn visit_components_mut<K: EntityKey>(
+                &mut self,
+                entity: K,
+                visitor: &mut impl ComponentVisitorMut,
+            ) -> bool
+            where
+                Self: ArchetypeCanResolve<K>,
+            {
+                match self.view_mut(entity) {
+                    Some(view) => {
+                        #(visitor.visit_mut::<#Component>(#COMPONENT_NAME, view.#component);)*
+                        true
+                    }
+                    None => false,
+                }
+            }
+        }
+
+        impl ::gecs::traits::ArchetypeDyn for #Archetype {
+            #[inline(always)]
+            fn archetype_id(&self) -> ArchetypeId {
+                Self::ARCHETYPE_ID
+            }
+
+            #[inline(always)]
+            fn name(&self) -> &'static str {
+                Self::NAME
+            }
+
+            #[inline(always)]
+            fn component_names(&self) -> &'static [&'static str] {
+                Self::COMPONENT_NAMES
+            }
+
+            #[inline(always)]
+            fn component_type_ids(&self) -> &'static [::std::any::TypeId] {
+                Self::COMPONENT_TYPE_IDS
+            }
+
+            #[inline(always)]
+            fn len(&self) -> usize {
+                self.data.len()
+            }
+
+            #[inline(always)]
+            fn is_empty(&self) -> bool {
+                self.data.is_empty()
+            }
+
+            #[inline(always)]
+            fn capacity(&self) -> usize {
+                self.data.capacity()
+            }
+
+            #[inline(always)]
+            fn entities(&self) -> &[EntityAny] {
+                entity_slice_to_any(self.data.get_slice_entities())
+            }
+
+            #[inline(always)]
+            fn contains(&self, entity: EntityAny) -> bool {
+                Entity::<Self>::try_from(entity)
+                    .ok()
+                    .and_then(|entity| self.data.resolve(entity))
+                    .is_some()
+            }
+
+            #[inline(always)]
+            fn destroy(&mut self, entity: EntityAny) -> bool {
+                Entity::<Self>::try_from(entity)
+                    .ok()
+                    .and_then(|entity| self.data.destroy(entity))
+                    .is_some()
+            }
+
+            #[inline]
+            fn memory_stats(&self) -> ArchetypeMemoryStats {
+                Archetype::memory_stats(self)
+            }
+
+            #[inline]
+            fn component(
+                &mut self,
+                entity: EntityAny,
+                type_id: ::std::any::TypeId,
+            ) -> Option<&dyn ::std::any::Any> {
+                let index = self.data.resolve(Entity::<Self>::try_from(entity).ok()?)?;
+                #(
+                    if type_id == ::std::any::TypeId::of::<#Component>() {
+                        return Some(&self.data.#get_slice()[index]);
+                    }
+                )*
+                None
+            }
+
+            #[inline]
+            fn component_mut(
+                &mut self,
+                entity: EntityAny,
+                type_id: ::std::any::TypeId,
+            ) -> Option<&mut dyn ::std::any::Any> {
+                let index = self.data.resolve(Entity::<Self>::try_from(entity).ok()?)?;
+                #(
+                    if type_id == ::std::any::TypeId::of::<#Component>() {
+                        return Some(&mut self.data.#get_slice_mut()[index]);
+                    }
+                )*
+                None
+            }
+
+            #[inline]
+            fn borrow_component(
+                &self,
+                entity: EntityAny,
+                type_id: ::std::any::TypeId,
+            ) -> Option<Ref<'_, dyn ::std::any::Any>> {
+                let index = self.data.resolve(Entity::<Self>::try_from(entity).ok()?)?;
+                #(
+                    if type_id == ::std::any::TypeId::of::<#Component>() {
+                        return Some(Ref::map(self.data.#borrow_slice(), |slice| {
+                            &slice[index] as &dyn ::std::any::Any
+                        }));
+                    }
+                )*
+                None
+            }
+
+            #[inline]
+            fn borrow_component_mut(
+                &self,
+                entity: EntityAny,
+                type_id: ::std::any::TypeId,
+            ) -> Option<RefMut<'_, dyn ::std::any::Any>> {
+                let index = self.data.resolve(Entity::<Self>::try_from(entity).ok()?)?;
+                #(
+                    if type_id == ::std::any::TypeId::of::<#Component>() {
+                        return Some(RefMut::map(self.data.#borrow_slice_mut(), |slice| {
+                            &mut slice[index] as &mut dyn ::std::any::Any
+                        }));
+                    }
+                )*
+                None
+            }
+
+            #[inline]
+            fn try_borrow_component(
+                &self,
+                entity: EntityAny,
+                type_id: ::std::any::TypeId,
+            ) -> Result<Option<Ref<'_, dyn ::std::any::Any>>, EcsError> {
+                let Some(index) = Entity::<Self>::try_from(entity).ok().and_then(|e| self.data.resolve(e)) else {
+                    return Ok(None);
+                };
+                #(
+                    if type_id == ::std::any::TypeId::of::<#Component>() {
+                        return Ok(Some(Ref::map(
+                            <Self as ArchetypeHas<#Component>>::resolve_try_borrow_slice(self)?,
+                            |slice| &slice[index] as &dyn ::std::any::Any,
+                        )));
+                    }
+                )*
+                Ok(None)
+            }
+
+            #[inline]
+            fn try_borrow_component_mut(
+                &self,
+                entity: EntityAny,
+                type_id: ::std::any::TypeId,
+            ) -> Result<Option<RefMut<'_, dyn ::std::any::Any>>, EcsError> {
+                let Some(index) = Entity::<Self>::try_from(entity).ok().and_then(|e| self.data.resolve(e)) else {
+                    return Ok(None);
+                };
+                #(
+                    if type_id == ::std::any::TypeId::of::<#Component>() {
+                        return Ok(Some(RefMut::map(
+                            <Self as ArchetypeHas<#Component>>::resolve_try_borrow_slice_mut(self)?,
+                            |slice| &mut slice[index] as &mut dyn ::std::any::Any,
+                        )));
+                    }
+                )*
+                Ok(None)
+            }
+
+            #[inline]
+            fn resolve_query_index(&self, entity: EntityAny) -> Option<usize> {
+                self.data.resolve(Entity::<Self>::try_from(entity).ok()?)
+            }
         }
 
         #(
@@ -1084,6 +2017,11 @@ fn section_archetype(archetype_data: &DataArchetype) -> TokenStream {
                     self.data.#get_slice()
                 }
 
+                #[inline(always)]
+                unsafe fn resolve_get_slice_unchecked(&self) -> &[#Component] {
+                    unsafe { self.data.#get_slice_unchecked() }
+                }
+
                 #[inline(always)]
                 fn resolve_get_slice_mut(&mut self) -> &mut [#Component] {
                     self.data.#get_slice_mut()
@@ -1099,6 +2037,30 @@ fn section_archetype(archetype_data: &DataArchetype) -> TokenStream {
                     self.data.#borrow_slice_mut()
                 }
 
+                #[inline(always)]
+                fn resolve_take_slice<'a>(slices: &mut #ArchetypeSlices<'a>) -> &'a mut [#Component]
+                where
+                    Self: 'a,
+                {
+                    ::std::mem::take(&mut slices.#component)
+                }
+
+                #[inline(always)]
+                fn resolve_try_borrow_slice(&self) -> Result<Ref<[#Component]>, EcsError> {
+                    self.data.#try_borrow_slice().map_err(|_| EcsError::BorrowConflict {
+                        archetype: Self::NAME,
+                        component: #COMPONENT_NAME,
+                    })
+                }
+
+                #[inline(always)]
+                fn resolve_try_borrow_slice_mut(&self) -> Result<RefMut<[#Component]>, EcsError> {
+                    self.data.#try_borrow_slice_mut().map_err(|_| EcsError::BorrowConflict {
+                        archetype: Self::NAME,
+                        component: #COMPONENT_NAME,
+                    })
+                }
+
                 #[inline(always)]
                 fn resolve_extract_components(components: &Self::Components) -> &#Component {
                     &components.#component
@@ -1133,6 +2095,22 @@ fn section_archetype(archetype_data: &DataArchetype) -> TokenStream {
                 fn resolve_extract_borrow_mut<'a>(borrow: &'a Self::Borrow<'_>) -> RefMut<'a, #Component> {
                     borrow.0.#borrow_component_mut()
                 }
+
+                #[inline(always)]
+                fn resolve_try_extract_borrow<'a>(borrow: &'a Self::Borrow<'_>) -> Result<Ref<'a, #Component>, EcsError> {
+                    borrow.0.#try_borrow_component().map_err(|_| EcsError::BorrowConflict {
+                        archetype: Self::NAME,
+                        component: #COMPONENT_NAME,
+                    })
+                }
+
+                #[inline(always)]
+                fn resolve_try_extract_borrow_mut<'a>(borrow: &'a Self::Borrow<'_>) -> Result<RefMut<'a, #Component>, EcsError> {
+                    borrow.0.#try_borrow_component_mut().map_err(|_| EcsError::BorrowConflict {
+                        archetype: Self::NAME,
+                        component: #COMPONENT_NAME,
+                    })
+                }
             }
         )*
 
@@ -1226,6 +2204,9 @@ fn section_archetype(archetype_data: &DataArchetype) -> TokenStream {
             }
         }
 
+        // Debug and Clone, plus any traits derived for the archetype with #[derive(...)].
+        #section_derives
+
         /// Access to all of the stored entity and component data within this archetype.
         /// Each index in these parallel slices refers to the components for a given entity.
         /// Component access is mutable, but entity access is fixed (entities can't be moved).
@@ -18,6 +18,75 @@ pub fn ecs_component_id(args: TokenStream) -> TokenStream {
     generate::generate_ecs_component_id(util).into()
 }
 
+/// See `EcsQuery` in the `gecs` docs for more information.
+#[proc_macro_derive(EcsQuery)]
+pub fn derive_ecs_query(args: TokenStream) -> TokenStream {
+    let raw = args.to_string();
+    let input = parse_macro_input!(args as syn::DeriveInput);
+
+    match generate::generate_derive_ecs_query(input, &raw) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+/// See `ecs_system` in the `gecs` docs for more information.
+#[proc_macro_attribute]
+pub fn ecs_system(args: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as ParseEcsSystemArgs);
+    let item = parse_macro_input!(item as syn::ItemFn);
+
+    match generate::generate_ecs_system(args, item) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __resolve_ecs_query_struct(args: TokenStream) -> TokenStream {
+    let resolve = parse_macro_input!(args as ParseQueryStructResolve);
+
+    match generate::generate_query_struct_resolve(resolve) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+/// See `ecs_iter_archetype` in the `gecs` docs for more information.
+#[proc_macro]
+pub fn ecs_iter_archetype(args: TokenStream) -> TokenStream {
+    let raw = args.clone().into(); // We may need to resolve query structs first
+    let query_parse = parse_macro_input!(args as ParseQueryArchetypeIter);
+
+    if let Some((param, query_struct)) = query_parse.next_unresolved_struct() {
+        let target = quote::quote!(::gecs::ecs_iter_archetype);
+        return generate::generate_query_struct_lookup(target, param, query_struct, raw).into();
+    }
+
+    match generate::generate_query_iter_archetype(query_parse) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+/// See `ecs_find_archetype` in the `gecs` docs for more information.
+#[proc_macro]
+pub fn ecs_find_archetype(args: TokenStream) -> TokenStream {
+    let raw = args.clone().into(); // We may need to resolve query structs first
+    let query_parse = parse_macro_input!(args as ParseQueryArchetypeFind);
+
+    if let Some((param, query_struct)) = query_parse.next_unresolved_struct() {
+        let target = quote::quote!(::gecs::ecs_find_archetype);
+        return generate::generate_query_struct_lookup(target, param, query_struct, raw).into();
+    }
+
+    match generate::generate_query_find_archetype(query_parse) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
 /// See `ecs_world` in the `gecs` docs for more information.
 #[proc_macro]
 pub fn ecs_world(args: TokenStream) -> TokenStream {
@@ -33,6 +102,38 @@ pub fn __expand_ecs_world(args: TokenStream) -> TokenStream {
     generate::generate_cfg_checks_outer("world", &world_parse, raw).into()
 }
 
+/// See `ecs_world_from_file` in the `gecs` docs for more information.
+#[cfg(feature = "world_file")]
+#[proc_macro]
+pub fn ecs_world_from_file(args: TokenStream) -> TokenStream {
+    let file = parse_macro_input!(args as ParseWorldFile);
+
+    let file_body = match file.read() {
+        Ok(file_body) => file_body,
+        Err(err) => return err.into_compile_error().into(),
+    };
+
+    // The file is converted to an ecs_world! body, which is then parsed as usual
+    let world_parse = match syn::parse2::<ParseEcsWorld>(file_body.body.clone()) {
+        Ok(world_parse) => world_parse,
+        Err(err) => {
+            let message = format!("in world file `{}`: {}", file_body.path, err);
+            return syn::Error::new(err.span(), message).into_compile_error().into();
+        }
+    };
+
+    let path = file_body.path;
+    let world = generate::generate_cfg_checks_outer("world", &world_parse, file_body.body);
+
+    quote::quote!(
+        // Makes the compiler rebuild this world when the file changes
+        const _: &[u8] = include_bytes!(#path);
+
+        #world
+    )
+    .into()
+}
+
 #[proc_macro]
 #[doc(hidden)]
 pub fn __impl_ecs_world(args: TokenStream) -> TokenStream {
@@ -138,3 +239,231 @@ pub fn __impl_ecs_iter_destroy(args: TokenStream) -> TokenStream {
         Err(err) => err.into_compile_error().into(),
     }
 }
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __expand_ecs_query(args: TokenStream) -> TokenStream {
+    let raw = args.clone().into(); // We'll need to parse twice
+    let query_parse = parse_macro_input!(args as ParseQueryIterator);
+    generate::generate_cfg_checks_inner("query", &query_parse, raw).into()
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __impl_ecs_query(args: TokenStream) -> TokenStream {
+    let query_parse = parse_macro_input!(args as ParseCfgDecorated<ParseQueryIterator>);
+
+    match generate::generate_query_iterator(query_parse) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __expand_ecs_count(args: TokenStream) -> TokenStream {
+    let raw = args.clone().into(); // We'll need to parse twice
+    let query_parse = parse_macro_input!(args as ParseQueryPredicate);
+    generate::generate_cfg_checks_inner("count", &query_parse, raw).into()
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __impl_ecs_count(args: TokenStream) -> TokenStream {
+    let query_parse = parse_macro_input!(args as ParseCfgDecorated<ParseQueryPredicate>);
+
+    match generate::generate_query_count(query_parse) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __expand_ecs_any(args: TokenStream) -> TokenStream {
+    let raw = args.clone().into(); // We'll need to parse twice
+    let query_parse = parse_macro_input!(args as ParseQueryPredicate);
+    generate::generate_cfg_checks_inner("any", &query_parse, raw).into()
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __impl_ecs_any(args: TokenStream) -> TokenStream {
+    let query_parse = parse_macro_input!(args as ParseCfgDecorated<ParseQueryPredicate>);
+
+    match generate::generate_query_any(query_parse) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __expand_ecs_fold(args: TokenStream) -> TokenStream {
+    let raw = args.clone().into(); // We'll need to parse twice
+    let query_parse = parse_macro_input!(args as ParseQueryFold);
+    generate::generate_cfg_checks_inner("fold", &query_parse, raw).into()
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __impl_ecs_fold(args: TokenStream) -> TokenStream {
+    let query_parse = parse_macro_input!(args as ParseCfgDecorated<ParseQueryFold>);
+
+    match generate::generate_query_fold(query_parse) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __expand_ecs_iter_pairs(args: TokenStream) -> TokenStream {
+    let raw = args.clone().into(); // We'll need to parse twice
+    let query_parse = parse_macro_input!(args as ParseQueryIterPairs);
+    generate::generate_cfg_checks_inner("iter_pairs", &query_parse, raw).into()
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __impl_ecs_iter_pairs(args: TokenStream) -> TokenStream {
+    let query_parse = parse_macro_input!(args as ParseCfgDecorated<ParseQueryIterPairs>);
+
+    match generate::generate_query_iter_pairs(query_parse) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __expand_ecs_find_each(args: TokenStream) -> TokenStream {
+    let raw = args.clone().into(); // We'll need to parse twice
+    let query_parse = parse_macro_input!(args as ParseQueryFindEach);
+    generate::generate_cfg_checks_inner("find_each", &query_parse, raw).into()
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __impl_ecs_find_each(args: TokenStream) -> TokenStream {
+    let query_parse = parse_macro_input!(args as ParseCfgDecorated<ParseQueryFindEach>);
+
+    match generate::generate_query_find_each(query_parse) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __expand_ecs_iter_budget(args: TokenStream) -> TokenStream {
+    let raw = args.clone().into(); // We'll need to parse twice
+    let query_parse = parse_macro_input!(args as ParseQueryIterBudget);
+    generate::generate_cfg_checks_inner("iter_budget", &query_parse, raw).into()
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __impl_ecs_iter_budget(args: TokenStream) -> TokenStream {
+    let query_parse = parse_macro_input!(args as ParseCfgDecorated<ParseQueryIterBudget>);
+
+    match generate::generate_query_iter_budget(query_parse) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __expand_ecs_iter_sorted(args: TokenStream) -> TokenStream {
+    let raw = args.clone().into(); // We'll need to parse twice
+    let query_parse = parse_macro_input!(args as ParseQueryIterSorted);
+    generate::generate_cfg_checks_inner("iter_sorted", &query_parse, raw).into()
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __impl_ecs_iter_sorted(args: TokenStream) -> TokenStream {
+    let query_parse = parse_macro_input!(args as ParseCfgDecorated<ParseQueryIterSorted>);
+
+    match generate::generate_query_iter_sorted(query_parse) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __expand_ecs_try_find(args: TokenStream) -> TokenStream {
+    let raw = args.clone().into(); // We'll need to parse twice
+    let query_parse = parse_macro_input!(args as ParseQueryFind);
+    generate::generate_cfg_checks_inner("try_find", &query_parse, raw).into()
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __impl_ecs_try_find(args: TokenStream) -> TokenStream {
+    let query_parse = parse_macro_input!(args as ParseCfgDecorated<ParseQueryFind>);
+
+    match generate::generate_query_try_find(FetchMode::Mut, query_parse) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __expand_ecs_try_find_borrow(args: TokenStream) -> TokenStream {
+    let raw = args.clone().into(); // We'll need to parse twice
+    let query_parse = parse_macro_input!(args as ParseQueryFind);
+    generate::generate_cfg_checks_inner("try_find_borrow", &query_parse, raw).into()
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __impl_ecs_try_find_borrow(args: TokenStream) -> TokenStream {
+    let query_parse = parse_macro_input!(args as ParseCfgDecorated<ParseQueryFind>);
+
+    match generate::generate_query_try_find(FetchMode::Borrow, query_parse) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __expand_ecs_query_archetypes(args: TokenStream) -> TokenStream {
+    let raw = args.clone().into(); // We'll need to parse twice
+    let query_parse = parse_macro_input!(args as ParseQueryArchetypes);
+    generate::generate_cfg_checks_inner("query_archetypes", &query_parse, raw).into()
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __impl_ecs_query_archetypes(args: TokenStream) -> TokenStream {
+    let query_parse = parse_macro_input!(args as ParseCfgDecorated<ParseQueryArchetypes>);
+
+    match generate::generate_query_archetypes(query_parse) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __expand_ecs_query_access(args: TokenStream) -> TokenStream {
+    let raw = args.clone().into(); // We'll need to parse twice
+    let query_parse = parse_macro_input!(args as ParseQueryArchetypes);
+    generate::generate_cfg_checks_inner("query_access", &query_parse, raw).into()
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn __impl_ecs_query_access(args: TokenStream) -> TokenStream {
+    let query_parse = parse_macro_input!(args as ParseCfgDecorated<ParseQueryArchetypes>);
+
+    match generate::generate_query_access(query_parse) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
@@ -1,6 +1,7 @@
 use proc_macro2::{Span, TokenStream};
 use syn::parse::{Parse, ParseStream};
-use syn::{bracketed, parenthesized, LitInt, Token};
+use syn::punctuated::Punctuated;
+use syn::{bracketed, parenthesized, Ident, LitInt, Token};
 
 use super::*;
 
@@ -9,6 +10,11 @@ mod kw {
 
     syn::custom_keyword!(archetype_id);
     syn::custom_keyword!(component_id);
+
+    syn::custom_keyword!(implements);
+    syn::custom_keyword!(derive);
+
+    syn::custom_keyword!(expect_archetypes);
 }
 
 pub(super) fn parse_attributes(input: ParseStream) -> syn::Result<Vec<ParseAttribute>> {
@@ -30,6 +36,9 @@ pub enum ParseAttributeData {
     Cfg(ParseAttributeCfg),
     ArchetypeId(ParseAttributeId),
     ComponentId(ParseAttributeId),
+    Implements(ParseAttributeImplements),
+    Derive(ParseAttributeDerive),
+    ExpectArchetypes(ParseAttributeExpectArchetypes),
 }
 
 #[derive(Clone, Debug)]
@@ -42,6 +51,22 @@ pub struct ParseAttributeId {
     pub value: u8,
 }
 
+#[derive(Debug)]
+pub struct ParseAttributeImplements {
+    pub traits: Vec<Ident>,
+}
+
+#[derive(Debug)]
+pub struct ParseAttributeDerive {
+    pub traits: Vec<Ident>,
+}
+
+#[derive(Debug)]
+pub struct ParseAttributeExpectArchetypes {
+    pub span: Span,
+    pub archetypes: Vec<Ident>,
+}
+
 impl Parse for ParseAttribute {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         input.parse::<Token![#]>()?;
@@ -59,6 +84,15 @@ impl Parse for ParseAttribute {
         } else if lookahead.peek(kw::component_id) {
             content.parse::<kw::component_id>()?;
             ParseAttributeData::ComponentId(content.parse()?)
+        } else if lookahead.peek(kw::implements) {
+            content.parse::<kw::implements>()?;
+            ParseAttributeData::Implements(content.parse()?)
+        } else if lookahead.peek(kw::derive) {
+            content.parse::<kw::derive>()?;
+            ParseAttributeData::Derive(content.parse()?)
+        } else if lookahead.peek(kw::expect_archetypes) {
+            content.parse::<kw::expect_archetypes>()?;
+            ParseAttributeData::ExpectArchetypes(content.parse()?)
         } else {
             return Err(lookahead.error());
         };
@@ -79,6 +113,80 @@ impl Parse for ParseAttributeId {
     }
 }
 
+impl Parse for ParseAttributeImplements {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let args;
+        parenthesized!(args in input);
+
+        // Grab the comma-separated list of trait names
+        let traits = Punctuated::<Ident, Token![,]>::parse_terminated(&args)?
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        if traits.is_empty() {
+            return Err(syn::Error::new(
+                args.span(),
+                "implements attribute must name at least one trait",
+            ));
+        }
+
+        Ok(Self { traits })
+    }
+}
+
+impl Parse for ParseAttributeDerive {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        const SUPPORTED: &[&str] = &["PartialEq", "Eq"];
+        const ALWAYS_IMPLEMENTED: &[&str] = &["Debug", "Clone"];
+
+        let args;
+        parenthesized!(args in input);
+
+        // Grab the comma-separated list of trait names
+        let traits = Punctuated::<Ident, Token![,]>::parse_terminated(&args)?
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        for name in traits.iter() {
+            let name_string = name.to_string();
+            if ALWAYS_IMPLEMENTED.contains(&name_string.as_str()) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!(
+                        "`{}` is always implemented for generated worlds and archetypes, \
+                        so it can't be derived",
+                        name_string,
+                    ),
+                ));
+            }
+
+            if SUPPORTED.contains(&name_string.as_str()) == false {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "unsupported derive, expected `PartialEq` or `Eq`",
+                ));
+            }
+        }
+
+        Ok(Self { traits })
+    }
+}
+
+impl Parse for ParseAttributeExpectArchetypes {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let args;
+        parenthesized!(args in input);
+
+        // Grab the comma-separated list of archetype names (which may be empty)
+        let span = args.span();
+        let archetypes = Punctuated::<Ident, Token![,]>::parse_terminated(&args)?
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        Ok(Self { span, archetypes })
+    }
+}
+
 impl HasAttributeId for ParseArchetype {
     fn name_to_string(&self) -> String {
         self.name.to_string()
@@ -3,11 +3,17 @@ mod cfg;
 mod id;
 mod name;
 mod query;
+mod system;
 mod world;
+#[cfg(feature = "world_file")]
+mod world_file;
 
 pub use attribute::*;
 pub use cfg::*;
 pub use id::*;
 pub use name::*;
 pub use query::*;
+pub use system::*;
 pub use world::*;
+#[cfg(feature = "world_file")]
+pub use world_file::*;
@@ -1,17 +1,21 @@
 use std::collections::HashSet;
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream};
 use syn::token::{Colon, Comma, Gt, Lt, Mut};
-use syn::{Expr, Ident, LitStr, Token, Type};
+use syn::{braced, bracketed, parenthesized, Expr, Ident, LitStr, Path, PathArguments, Token, Type};
 
 use super::{
-    parse_attributes, HasCfgPredicates, ParseAttributeCfg, ParseAttributeData, ParseComponentName,
+    parse_attributes, HasCfgPredicates, ParseAttributeCfg, ParseAttributeData,
+    ParseAttributeExpectArchetypes, ParseComponentName,
 };
 
 mod kw {
     syn::custom_keyword!(archetype);
     syn::custom_keyword!(cfg);
+    syn::custom_keyword!(nested);
 
     syn::custom_keyword!(Entity);
     syn::custom_keyword!(EntityAny);
@@ -28,16 +32,75 @@ mod kw {
 pub struct ParseQueryFind {
     pub world_data: String,
     pub world: Expr,
+    pub expect: Option<ParseAttributeExpectArchetypes>,
     pub entity: Expr,
     pub params: Vec<ParseQueryParam>,
     pub ret: Option<Type>,
     pub body: Expr,
 }
 
+#[derive(Debug)]
+pub struct ParseQueryFindEach {
+    pub world_data: String,
+    pub world: Expr,
+    pub expect: Option<ParseAttributeExpectArchetypes>,
+    pub handles: Expr,
+    pub params: Vec<ParseQueryParam>,
+    pub body: Expr,
+}
+
 #[derive(Debug)]
 pub struct ParseQueryIter {
     pub world_data: String,
     pub world: Expr,
+    pub expect: Option<ParseAttributeExpectArchetypes>,
+    pub params: Vec<ParseQueryParam>,
+    pub body: Expr,
+    pub nested: Option<ParseQueryNested>,
+}
+
+#[derive(Debug)]
+pub struct ParseQueryArchetypeIter {
+    pub archetype: Expr,
+    pub params: Vec<ParseQueryParam>,
+    pub body: Expr,
+}
+
+#[derive(Debug)]
+pub struct ParseQueryArchetypeFind {
+    pub archetype: Expr,
+    pub entity: Expr,
+    pub params: Vec<ParseQueryParam>,
+    pub ret: Option<Type>,
+    pub body: Expr,
+}
+
+#[derive(Debug)]
+pub struct ParseQueryNested {
+    pub name: Ident,
+    pub params: Vec<ParseQueryParam>,
+    pub ret: Option<Type>,
+    pub body: Expr,
+}
+
+#[derive(Debug)]
+pub struct ParseQueryIterBudget {
+    pub world_data: String,
+    pub world: Expr,
+    pub expect: Option<ParseAttributeExpectArchetypes>,
+    pub cursor: Expr,
+    pub budget: Expr,
+    pub params: Vec<ParseQueryParam>,
+    pub body: Expr,
+}
+
+#[derive(Debug)]
+pub struct ParseQueryIterSorted {
+    pub world_data: String,
+    pub world: Expr,
+    pub expect: Option<ParseAttributeExpectArchetypes>,
+    pub key_params: Vec<ParseQueryParam>,
+    pub key: Expr,
     pub params: Vec<ParseQueryParam>,
     pub body: Expr,
 }
@@ -46,42 +109,471 @@ pub struct ParseQueryIter {
 pub struct ParseQueryIterDestroy {
     pub world_data: String,
     pub world: Expr,
+    pub expect: Option<ParseAttributeExpectArchetypes>,
+    pub params: Vec<ParseQueryParam>,
+    pub body: Expr,
+}
+
+#[derive(Debug)]
+pub struct ParseQueryIterator {
+    pub world_data: String,
+    pub world: Expr,
+    pub expect: Option<ParseAttributeExpectArchetypes>,
+    pub params: Vec<ParseQueryParam>,
+}
+
+#[derive(Debug)]
+pub struct ParseQueryArchetypes {
+    pub world_data: String,
+    pub expect: Option<ParseAttributeExpectArchetypes>,
+    pub params: Vec<ParseQueryParam>,
+}
+
+#[derive(Debug)]
+pub struct ParseQueryPredicate {
+    pub world_data: String,
+    pub world: Expr,
+    pub expect: Option<ParseAttributeExpectArchetypes>,
+    pub params: Vec<ParseQueryParam>,
+    pub body: Option<Expr>,
+}
+
+#[derive(Debug)]
+pub struct ParseQueryFold {
+    pub world_data: String,
+    pub world: Expr,
+    pub expect: Option<ParseAttributeExpectArchetypes>,
+    pub init: Expr,
+    pub acc_mut: Option<Mut>,
+    pub acc: Ident,
+    pub acc_type: Option<Type>,
     pub params: Vec<ParseQueryParam>,
     pub body: Expr,
 }
 
-#[derive(Clone, Debug)]
-pub struct ParseQueryParam {
-    pub cfgs: Vec<ParseAttributeCfg>,
-    pub name: Ident,
-    pub is_mut: bool,
-    pub param_type: ParseQueryParamType,
+#[derive(Debug)]
+pub struct ParseQueryIterPairs {
+    pub world_data: String,
+    pub world: Expr,
+    pub expect: Option<ParseAttributeExpectArchetypes>,
+    pub params: Vec<ParseQueryParam>,
+    pub other: Vec<Ident>,
+    pub body: Expr,
+}
+
+#[derive(Clone, Debug)]
+pub struct ParseQueryParam {
+    pub cfgs: Vec<ParseAttributeCfg>,
+    pub name: Ident,
+    pub is_mut: bool,
+    pub param_type: ParseQueryParamType,
+
+    // Set during generation
+    pub is_cfg_enabled: bool,
+}
+
+#[derive(Clone, Debug)]
+#[allow(dead_code)]
+pub enum ParseQueryParamType {
+    Component(ParseComponentName), // CompFoo or CompFoo<T>
+
+    // Entity Types
+    Entity(Ident),       // Entity<A>
+    EntityWild,          // Entity<_>
+    EntityAny,           // EntityAny
+    EntityDirect(Ident), // EntityDirect<A>
+    EntityDirectWild,    // EntityDirect<_>
+    EntityDirectAny,     // EntityDirectAny
+
+    // Special Types
+    OneOf(Box<[ParseComponentName]>), // OneOf<CompFoo, CompBar<T>>
+    Dyn(Ident, Box<[ParseComponentName]>), // dyn Trait (components bound per-archetype)
+    DynSlice(Ident, Box<[ParseComponentName]>), // [&dyn Trait] (components bound per-archetype)
+    Option(Ident),                    // Option<CompFoo> -- TODO: RESERVED
+    With(ParseComponentName),         // With<CompFoo> (archetype filter only)
+    Without(ParseComponentName),      // Without<CompFoo> (archetype filter only)
+    Struct(Box<ParseQueryStruct>),    // Movement (a #[derive(EcsQuery)] struct, taken by value)
+}
+
+#[derive(Clone, Debug)]
+pub struct ParseQueryStruct {
+    pub path: Path,
+    pub fields: Option<Vec<ParseQueryParam>>, // Filled in once resolved via the struct's macro
+}
+
+#[derive(Debug)]
+pub struct ParseQueryStructResolve {
+    pub fields: TokenStream,
+    pub target: Path,
+    pub name: Ident,
+    pub path: LitStr,
+    pub raw: TokenStream,
+}
+
+pub trait HasQueryStructs {
+    fn next_unresolved_struct(&self) -> Option<(&Ident, &ParseQueryStruct)>;
+}
+
+impl Parse for ParseQueryFind {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Parse out the hidden serialized world data
+        let world_data = input.parse::<LitStr>()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the meta-arguments for the query
+        let world = input.parse()?;
+        input.parse::<Comma>()?;
+        let entity = input.parse()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the closure arguments
+        let expect = parse_expect_archetypes(input)?;
+        input.parse::<Token![|]>()?;
+        let mut params = parse_params(&input)?;
+        input.parse::<Token![|]>()?;
+
+        // Parse a return type, if there is one
+        let ret = match input.parse::<Option<Token![->]>>()? {
+            Some(_) => Some(input.parse::<Type>()?),
+            None => None,
+        };
+
+        // Parse the rest of the body, including the braces (if any)
+        let mut body = input.parse::<Expr>()?;
+        expand_query_structs(&mut params, Some(&mut body));
+
+        Ok(Self {
+            world_data: world_data.value(),
+            world,
+            expect,
+            entity,
+            params,
+            ret,
+            body,
+        })
+    }
+}
+
+impl Parse for ParseQueryFindEach {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Parse out the hidden serialized world data
+        let world_data = input.parse::<LitStr>()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the meta-arguments for the query
+        let world = input.parse()?;
+        input.parse::<Comma>()?;
+        let handles = input.parse()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the closure arguments
+        let expect = parse_expect_archetypes(input)?;
+        input.parse::<Token![|]>()?;
+        let mut params = parse_params(&input)?;
+        input.parse::<Token![|]>()?;
+
+        // Parse the rest of the body, including the braces (if any)
+        let mut body = input.parse::<Expr>()?;
+        expand_query_structs(&mut params, Some(&mut body));
+
+        Ok(Self {
+            world_data: world_data.value(),
+            world,
+            expect,
+            handles,
+            params,
+            body,
+        })
+    }
+}
+
+impl Parse for ParseQueryIter {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Parse out the hidden serialized world data
+        let world_data = input.parse::<LitStr>()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the meta-arguments for the query
+        let world = input.parse()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the closure arguments
+        let expect = parse_expect_archetypes(input)?;
+        input.parse::<Token![|]>()?;
+        let mut params = parse_params(&input)?;
+        input.parse::<Token![|]>()?;
+
+        // Parse the rest of the body, including the braces (if any)
+        let mut body = input.parse::<Expr>()?;
+        expand_query_structs(&mut params, Some(&mut body));
+
+        // Parse a trailing nested query, if there is one
+        let nested = match input.parse::<Option<Comma>>()? {
+            Some(_) if input.is_empty() == false => Some(input.parse()?),
+            _ => None,
+        };
+
+        Ok(Self {
+            world_data: world_data.value(),
+            world,
+            expect,
+            params,
+            body,
+            nested,
+        })
+    }
+}
+
+impl Parse for ParseQueryArchetypeIter {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Parse out the meta-arguments for the query
+        let archetype = input.parse()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the closure arguments
+        input.parse::<Token![|]>()?;
+        let mut params = parse_params(&input)?;
+        input.parse::<Token![|]>()?;
+
+        // Parse the rest of the body, including the braces (if any)
+        let mut body = input.parse::<Expr>()?;
+        expand_query_structs(&mut params, Some(&mut body));
+
+        Ok(Self {
+            archetype,
+            params,
+            body,
+        })
+    }
+}
+
+impl Parse for ParseQueryArchetypeFind {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Parse out the meta-arguments for the query
+        let archetype = input.parse()?;
+        input.parse::<Comma>()?;
+        let entity = input.parse()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the closure arguments
+        input.parse::<Token![|]>()?;
+        let mut params = parse_params(&input)?;
+        input.parse::<Token![|]>()?;
+
+        // Parse a return type, if there is one
+        let ret = match input.parse::<Option<Token![->]>>()? {
+            Some(_) => Some(input.parse::<Type>()?),
+            None => None,
+        };
+
+        // Parse the rest of the body, including the braces (if any)
+        let mut body = input.parse::<Expr>()?;
+        expand_query_structs(&mut params, Some(&mut body));
+
+        Ok(Self {
+            archetype,
+            entity,
+            params,
+            ret,
+            body,
+        })
+    }
+}
+
+impl Parse for ParseQueryNested {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Parse out the name the nested query will be callable by
+        input.parse::<kw::nested>()?;
+        let name = input.parse::<Ident>()?;
+        input.parse::<Token![=]>()?;
+
+        // Parse out the closure arguments
+        input.parse::<Token![|]>()?;
+        let mut params = parse_params(&input)?;
+        input.parse::<Token![|]>()?;
+
+        // Parse a return type, if there is one
+        let ret = match input.parse::<Option<Token![->]>>()? {
+            Some(_) => Some(input.parse::<Type>()?),
+            None => None,
+        };
+
+        // Parse the rest of the body, including the braces (if any)
+        let mut body = input.parse::<Expr>()?;
+        expand_query_structs(&mut params, Some(&mut body));
+
+        Ok(Self {
+            name,
+            params,
+            ret,
+            body,
+        })
+    }
+}
+
+impl Parse for ParseQueryIterBudget {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Parse out the hidden serialized world data
+        let world_data = input.parse::<LitStr>()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the meta-arguments for the query
+        let world = input.parse()?;
+        input.parse::<Comma>()?;
+        let cursor = input.parse()?;
+        input.parse::<Comma>()?;
+        let budget = input.parse()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the closure arguments
+        let expect = parse_expect_archetypes(input)?;
+        input.parse::<Token![|]>()?;
+        let mut params = parse_params(&input)?;
+        input.parse::<Token![|]>()?;
+
+        // Parse the rest of the body, including the braces (if any)
+        let mut body = input.parse::<Expr>()?;
+        expand_query_structs(&mut params, Some(&mut body));
+
+        Ok(Self {
+            world_data: world_data.value(),
+            world,
+            expect,
+            cursor,
+            budget,
+            params,
+            body,
+        })
+    }
+}
+
+impl Parse for ParseQueryIterSorted {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Parse out the hidden serialized world data
+        let world_data = input.parse::<LitStr>()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the meta-arguments for the query
+        let world = input.parse()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the sort key closure
+        input.parse::<Token![|]>()?;
+        let mut key_params = parse_params(&input)?;
+        input.parse::<Token![|]>()?;
+        let mut key = input.parse::<Expr>()?;
+        input.parse::<Comma>()?;
+        expand_query_structs(&mut key_params, Some(&mut key));
+
+        for param in key_params.iter() {
+            if param.is_mut {
+                return Err(syn::Error::new_spanned(
+                    &param.name,
+                    "sort key parameters must be immutable",
+                ));
+            }
+        }
+
+        // Parse out the closure arguments
+        let expect = parse_expect_archetypes(input)?;
+        input.parse::<Token![|]>()?;
+        let mut params = parse_params(&input)?;
+        input.parse::<Token![|]>()?;
+
+        // Parse the rest of the body, including the braces (if any)
+        let mut body = input.parse::<Expr>()?;
+        expand_query_structs(&mut params, Some(&mut body));
+
+        Ok(Self {
+            world_data: world_data.value(),
+            world,
+            expect,
+            key_params,
+            key,
+            params,
+            body,
+        })
+    }
+}
+
+impl Parse for ParseQueryIterDestroy {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Parse out the hidden serialized world data
+        let world_data = input.parse::<LitStr>()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the meta-arguments for the query
+        let world = input.parse()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the closure arguments
+        let expect = parse_expect_archetypes(input)?;
+        input.parse::<Token![|]>()?;
+        let mut params = parse_params(&input)?;
+        input.parse::<Token![|]>()?;
+
+        // Parse the rest of the body, including the braces (if any)
+        let mut body = input.parse::<Expr>()?;
+        expand_query_structs(&mut params, Some(&mut body));
+
+        Ok(Self {
+            world_data: world_data.value(),
+            world,
+            expect,
+            params,
+            body,
+        })
+    }
+}
+
+impl Parse for ParseQueryIterator {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Parse out the hidden serialized world data
+        let world_data = input.parse::<LitStr>()?;
+        input.parse::<Comma>()?;
 
-    // Set during generation
-    pub is_cfg_enabled: bool,
+        // Parse out the meta-arguments for the query
+        let world = input.parse()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the closure arguments (there is no body)
+        let expect = parse_expect_archetypes(input)?;
+        input.parse::<Token![|]>()?;
+        let params = parse_params(&input)?;
+        input.parse::<Token![|]>()?;
+        reject_query_structs(&params, "ecs_query!")?;
+
+        Ok(Self {
+            world_data: world_data.value(),
+            world,
+            expect,
+            params,
+        })
+    }
 }
 
-#[derive(Clone, Debug)]
-#[allow(dead_code)]
-pub enum ParseQueryParamType {
-    Component(ParseComponentName), // CompFoo or CompFoo<T>
+impl Parse for ParseQueryArchetypes {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Parse out the hidden serialized world data
+        let world_data = input.parse::<LitStr>()?;
+        input.parse::<Comma>()?;
 
-    // Entity Types
-    Entity(Ident),       // Entity<A>
-    EntityWild,          // Entity<_>
-    EntityAny,           // EntityAny
-    EntityDirect(Ident), // EntityDirect<A>
-    EntityDirectWild,    // EntityDirect<_>
-    EntityDirectAny,     // EntityDirectAny
+        // Parse out the closure arguments (there is no world or body)
+        let expect = parse_expect_archetypes(input)?;
+        input.parse::<Token![|]>()?;
+        let mut params = parse_params(&input)?;
+        input.parse::<Token![|]>()?;
+        expand_query_structs(&mut params, None);
 
-    // Special Types
-    OneOf(Box<[ParseComponentName]>), // OneOf<CompFoo, CompBar<T>>
-    Option(Ident),                    // Option<CompFoo> -- TODO: RESERVED
-    With(Ident),                      // With<CompFoo> -- TODO: RESERVED
-    Without(Ident),                   // Without<CompFoo> -- TODO: RESERVED
+        Ok(Self {
+            world_data: world_data.value(),
+            expect,
+            params,
+        })
+    }
 }
 
-impl Parse for ParseQueryFind {
+impl Parse for ParseQueryPredicate {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         // Parse out the hidden serialized world data
         let world_data = input.parse::<LitStr>()?;
@@ -90,35 +582,31 @@ impl Parse for ParseQueryFind {
         // Parse out the meta-arguments for the query
         let world = input.parse()?;
         input.parse::<Comma>()?;
-        let entity = input.parse()?;
-        input.parse::<Comma>()?;
 
         // Parse out the closure arguments
+        let expect = parse_expect_archetypes(input)?;
         input.parse::<Token![|]>()?;
-        let params = parse_params(&input)?;
+        let mut params = parse_params(&input)?;
         input.parse::<Token![|]>()?;
 
-        // Parse a return type, if there is one
-        let ret = match input.parse::<Option<Token![->]>>()? {
-            Some(_) => Some(input.parse::<Type>()?),
-            None => None,
-        };
-
         // Parse the rest of the body, including the braces (if any)
-        let body = input.parse::<Expr>()?;
+        let mut body = match input.is_empty() {
+            true => None,
+            false => Some(input.parse::<Expr>()?),
+        };
+        expand_query_structs(&mut params, body.as_mut());
 
         Ok(Self {
             world_data: world_data.value(),
             world,
-            entity,
+            expect,
             params,
-            ret,
             body,
         })
     }
 }
 
-impl Parse for ParseQueryIter {
+impl Parse for ParseQueryFold {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         // Parse out the hidden serialized world data
         let world_data = input.parse::<LitStr>()?;
@@ -127,25 +615,41 @@ impl Parse for ParseQueryIter {
         // Parse out the meta-arguments for the query
         let world = input.parse()?;
         input.parse::<Comma>()?;
+        let init = input.parse()?;
+        input.parse::<Comma>()?;
 
-        // Parse out the closure arguments
+        // Parse out the closure arguments, starting with the accumulator
+        let expect = parse_expect_archetypes(input)?;
         input.parse::<Token![|]>()?;
-        let params = parse_params(&input)?;
+        let acc_mut = input.parse::<Option<Mut>>()?;
+        let acc = parse_param_name(input)?;
+        let acc_type = match input.parse::<Option<Colon>>()? {
+            Some(_) => Some(input.parse::<Type>()?),
+            None => None,
+        };
+        input.parse::<Option<Token![,]>>()?;
+        let mut params = parse_params(&input)?;
         input.parse::<Token![|]>()?;
 
         // Parse the rest of the body, including the braces (if any)
-        let body = input.parse::<Expr>()?;
+        let mut body = input.parse::<Expr>()?;
+        expand_query_structs(&mut params, Some(&mut body));
 
         Ok(Self {
             world_data: world_data.value(),
             world,
+            expect,
+            init,
+            acc_mut,
+            acc,
+            acc_type,
             params,
             body,
         })
     }
 }
 
-impl Parse for ParseQueryIterDestroy {
+impl Parse for ParseQueryIterPairs {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         // Parse out the hidden serialized world data
         let world_data = input.parse::<LitStr>()?;
@@ -155,18 +659,38 @@ impl Parse for ParseQueryIterDestroy {
         let world = input.parse()?;
         input.parse::<Comma>()?;
 
-        // Parse out the closure arguments
+        // Parse out the closure arguments as two tuples, where the second only has names
+        let expect = parse_expect_archetypes(input)?;
         input.parse::<Token![|]>()?;
-        let params = parse_params(&input)?;
+        let params = parse_pair_params(input)?;
+        reject_query_structs(&params, "ecs_iter_pairs!")?;
+        input.parse::<Comma>()?;
+        let other_tuple;
+        parenthesized!(other_tuple in input);
+        let other = other_tuple.parse_terminated(parse_param_name, Comma)?;
+        input.parse::<Option<Comma>>()?;
         input.parse::<Token![|]>()?;
 
+        if params.len() != other.len() {
+            return Err(syn::Error::new(
+                other_tuple.span(),
+                format!(
+                    "expected {} parameter names to match the first tuple, found {}",
+                    params.len(),
+                    other.len()
+                ),
+            ));
+        }
+
         // Parse the rest of the body, including the braces (if any)
         let body = input.parse::<Expr>()?;
 
         Ok(Self {
             world_data: world_data.value(),
             world,
+            expect,
             params,
+            other: other.into_iter().collect(),
             body,
         })
     }
@@ -192,13 +716,56 @@ impl Parse for ParseQueryParam {
         let name = parse_param_name(input)?;
         input.parse::<Colon>()?;
 
-        input.parse::<Token![&]>()?;
-        let is_mut = input.parse::<Option<Mut>>()?.is_some();
+        // Query structs are taken by value, and their fields are spliced in once resolved
+        if peek_query_struct(input) {
+            if attributes.is_empty() == false {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "cfg attributes are not supported on query structs",
+                ));
+            }
+
+            return Ok(Self {
+                cfgs: attributes,
+                name,
+                is_mut: false,
+                param_type: ParseQueryParamType::Struct(Box::new(input.parse()?)),
+                is_cfg_enabled: true, // Default to true
+            });
+        }
+
+        // Filters like With<A> don't access any data, so they don't need a reference
         let check_span = input.span();
-        let ty = input.parse::<ParseQueryParamType>()?;
+        let is_ref = input.parse::<Option<Token![&]>>()?.is_some();
+        let is_mut = is_ref && input.parse::<Option<Mut>>()?.is_some();
+        let ty = match input.peek(syn::token::Bracket) {
+            true => parse_dyn_slice(input, is_mut)?,
+            false => input.parse::<ParseQueryParamType>()?,
+        };
 
-        // Enforce mutability rules
+        // Enforce reference and mutability rules
         match ty {
+            ParseQueryParamType::With(_) | ParseQueryParamType::Without(_) if is_mut => {
+                Err(syn::Error::new(
+                    check_span,
+                    "mut filter access is forbidden",
+                ))
+            }
+            ParseQueryParamType::With(_) | ParseQueryParamType::Without(_) => {
+                Ok(Self {
+                    cfgs: attributes,
+                    name,
+                    is_mut,
+                    param_type: ty,
+                    is_cfg_enabled: true, // Default to true
+                })
+            }
+            _ if is_ref == false => {
+                Err(syn::Error::new(
+                    check_span,
+                    "expected `&` or `&mut`",
+                ))
+            }
             ParseQueryParamType::Entity(_)
             | ParseQueryParamType::EntityAny
             | ParseQueryParamType::EntityWild
@@ -289,21 +856,30 @@ impl Parse for ParseQueryParamType {
                     break Err(lookahead.error());
                 }
             }
+        } else if lookahead.peek(Token![dyn]) {
+            // dyn Trait
+            input.parse::<Token![dyn]>()?;
+            let trait_name = input.parse::<Ident>()?;
+            Ok(ParseQueryParamType::Dyn(trait_name, Box::new([])))
         } else if lookahead.peek(kw::Option) {
             Err(syn::Error::new(
                 input.span(),
                 "reserved special 'Option' not yet implemented",
             ))
         } else if lookahead.peek(kw::With) {
-            Err(syn::Error::new(
-                input.span(),
-                "reserved special 'With' not yet implemented",
-            ))
+            // With<A>
+            input.parse::<kw::With>()?;
+            input.parse::<Token![<]>()?;
+            let name = input.parse::<ParseComponentName>()?;
+            input.parse::<Token![>]>()?;
+            Ok(ParseQueryParamType::With(name))
         } else if lookahead.peek(kw::Without) {
-            Err(syn::Error::new(
-                input.span(),
-                "reserved special 'Without' not yet implemented",
-            ))
+            // Without<A>
+            input.parse::<kw::Without>()?;
+            input.parse::<Token![<]>()?;
+            let name = input.parse::<ParseComponentName>()?;
+            input.parse::<Token![>]>()?;
+            Ok(ParseQueryParamType::Without(name))
         } else if lookahead.peek(Ident) {
             let name = input.parse::<ParseComponentName>()?;
             Ok(ParseQueryParamType::Component(name))
@@ -313,6 +889,56 @@ impl Parse for ParseQueryParamType {
     }
 }
 
+impl Parse for ParseQueryStruct {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Movement, Movement<'_>, or some::path::Movement
+        let mut path = input.parse::<Path>()?;
+        path.segments.last_mut().unwrap().arguments = PathArguments::None;
+
+        // Resolved structs have their fields attached, as in Movement @ { pos: &mut Pos }
+        let fields = match input.parse::<Option<Token![@]>>()? {
+            Some(_) => {
+                let content;
+                braced!(content in input);
+                let fields = content.parse_terminated(ParseQueryParam::parse, Comma)?;
+                Some(fields.into_iter().collect())
+            }
+            None => None,
+        };
+
+        Ok(Self { path, fields })
+    }
+}
+
+impl Parse for ParseQueryStructResolve {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // Parse out the fields provided by the struct's generated macro
+        let fields;
+        braced!(fields in input);
+        input.parse::<Comma>()?;
+
+        // Parse out the macro to re-invoke, and the parameter being resolved
+        let target = input.parse::<Path>()?;
+        input.parse::<Comma>()?;
+        let name = input.call(Ident::parse_any)?;
+        input.parse::<Comma>()?;
+        let path = input.parse::<LitStr>()?;
+        input.parse::<Comma>()?;
+
+        // Parse out the original (partially resolved) query arguments
+        let raw;
+        braced!(raw in input);
+
+        Ok(Self {
+            fields: fields.parse()?,
+            target,
+            name,
+            path,
+            raw: raw.parse()?,
+        })
+    }
+}
+
 impl HasCfgPredicates for ParseQueryFind {
     fn collect_all_cfg_predicates(&self) -> Vec<TokenStream> {
         get_cfg_predicates(&self.params)
@@ -321,7 +947,11 @@ impl HasCfgPredicates for ParseQueryFind {
 
 impl HasCfgPredicates for ParseQueryIter {
     fn collect_all_cfg_predicates(&self) -> Vec<TokenStream> {
-        get_cfg_predicates(&self.params)
+        let mut result = get_cfg_predicates(&self.params);
+        if let Some(nested) = &self.nested {
+            result.extend(get_cfg_predicates(&nested.params));
+        }
+        result
     }
 }
 
@@ -331,6 +961,142 @@ impl HasCfgPredicates for ParseQueryIterDestroy {
     }
 }
 
+impl HasCfgPredicates for ParseQueryIterator {
+    fn collect_all_cfg_predicates(&self) -> Vec<TokenStream> {
+        get_cfg_predicates(&self.params)
+    }
+}
+
+impl HasCfgPredicates for ParseQueryArchetypes {
+    fn collect_all_cfg_predicates(&self) -> Vec<TokenStream> {
+        get_cfg_predicates(&self.params)
+    }
+}
+
+impl HasCfgPredicates for ParseQueryPredicate {
+    fn collect_all_cfg_predicates(&self) -> Vec<TokenStream> {
+        get_cfg_predicates(&self.params)
+    }
+}
+
+impl HasCfgPredicates for ParseQueryFold {
+    fn collect_all_cfg_predicates(&self) -> Vec<TokenStream> {
+        get_cfg_predicates(&self.params)
+    }
+}
+
+impl HasCfgPredicates for ParseQueryFindEach {
+    fn collect_all_cfg_predicates(&self) -> Vec<TokenStream> {
+        get_cfg_predicates(&self.params)
+    }
+}
+
+impl HasCfgPredicates for ParseQueryIterBudget {
+    fn collect_all_cfg_predicates(&self) -> Vec<TokenStream> {
+        get_cfg_predicates(&self.params)
+    }
+}
+
+impl HasCfgPredicates for ParseQueryIterSorted {
+    fn collect_all_cfg_predicates(&self) -> Vec<TokenStream> {
+        let mut params = self.key_params.clone();
+        params.extend(self.params.iter().cloned());
+        get_cfg_predicates(&params)
+    }
+}
+
+impl HasCfgPredicates for ParseQueryIterPairs {
+    fn collect_all_cfg_predicates(&self) -> Vec<TokenStream> {
+        get_cfg_predicates(&self.params)
+    }
+}
+
+impl HasQueryStructs for ParseQueryFind {
+    fn next_unresolved_struct(&self) -> Option<(&Ident, &ParseQueryStruct)> {
+        find_unresolved_struct(&self.params)
+    }
+}
+
+impl HasQueryStructs for ParseQueryIter {
+    fn next_unresolved_struct(&self) -> Option<(&Ident, &ParseQueryStruct)> {
+        let nested = self.nested.iter().flat_map(|nested| nested.params.iter());
+        find_unresolved_struct(self.params.iter().chain(nested))
+    }
+}
+
+impl HasQueryStructs for ParseQueryArchetypeIter {
+    fn next_unresolved_struct(&self) -> Option<(&Ident, &ParseQueryStruct)> {
+        find_unresolved_struct(&self.params)
+    }
+}
+
+impl HasQueryStructs for ParseQueryArchetypeFind {
+    fn next_unresolved_struct(&self) -> Option<(&Ident, &ParseQueryStruct)> {
+        find_unresolved_struct(&self.params)
+    }
+}
+
+impl HasQueryStructs for ParseQueryIterDestroy {
+    fn next_unresolved_struct(&self) -> Option<(&Ident, &ParseQueryStruct)> {
+        find_unresolved_struct(&self.params)
+    }
+}
+
+impl HasQueryStructs for ParseQueryIterator {
+    fn next_unresolved_struct(&self) -> Option<(&Ident, &ParseQueryStruct)> {
+        None // Rejected during parsing
+    }
+}
+
+impl HasQueryStructs for ParseQueryArchetypes {
+    fn next_unresolved_struct(&self) -> Option<(&Ident, &ParseQueryStruct)> {
+        find_unresolved_struct(&self.params)
+    }
+}
+
+impl HasQueryStructs for ParseQueryPredicate {
+    fn next_unresolved_struct(&self) -> Option<(&Ident, &ParseQueryStruct)> {
+        find_unresolved_struct(&self.params)
+    }
+}
+
+impl HasQueryStructs for ParseQueryFold {
+    fn next_unresolved_struct(&self) -> Option<(&Ident, &ParseQueryStruct)> {
+        find_unresolved_struct(&self.params)
+    }
+}
+
+impl HasQueryStructs for ParseQueryFindEach {
+    fn next_unresolved_struct(&self) -> Option<(&Ident, &ParseQueryStruct)> {
+        find_unresolved_struct(&self.params)
+    }
+}
+
+impl HasQueryStructs for ParseQueryIterBudget {
+    fn next_unresolved_struct(&self) -> Option<(&Ident, &ParseQueryStruct)> {
+        find_unresolved_struct(&self.params)
+    }
+}
+
+impl HasQueryStructs for ParseQueryIterSorted {
+    fn next_unresolved_struct(&self) -> Option<(&Ident, &ParseQueryStruct)> {
+        find_unresolved_struct(self.key_params.iter().chain(self.params.iter()))
+    }
+}
+
+impl HasQueryStructs for ParseQueryIterPairs {
+    fn next_unresolved_struct(&self) -> Option<(&Ident, &ParseQueryStruct)> {
+        None // Rejected during parsing
+    }
+}
+
+fn parse_pair_params(input: ParseStream) -> syn::Result<Vec<ParseQueryParam>> {
+    let tuple;
+    parenthesized!(tuple in input);
+    let params = tuple.parse_terminated(ParseQueryParam::parse, Comma)?;
+    Ok(params.into_iter().collect())
+}
+
 fn parse_params(input: &ParseStream) -> syn::Result<Vec<ParseQueryParam>> {
     let mut result = Vec::<ParseQueryParam>::new();
     loop {
@@ -346,6 +1112,27 @@ fn parse_params(input: &ParseStream) -> syn::Result<Vec<ParseQueryParam>> {
     }
 }
 
+fn parse_dyn_slice(input: ParseStream, is_mut: bool) -> syn::Result<ParseQueryParamType> {
+    // [&dyn Trait] or [&mut dyn Trait]
+    let content;
+    bracketed!(content in input);
+
+    content.parse::<Token![&]>()?;
+    let check_span = content.span();
+    let is_inner_mut = content.parse::<Option<Mut>>()?.is_some();
+    content.parse::<Token![dyn]>()?;
+    let trait_name = content.parse::<Ident>()?;
+
+    if is_mut != is_inner_mut {
+        return Err(syn::Error::new(
+            check_span,
+            "use either &[&dyn Trait] or &mut [&mut dyn Trait]",
+        ));
+    }
+
+    Ok(ParseQueryParamType::DynSlice(trait_name, Box::new([])))
+}
+
 fn parse_param_name(input: ParseStream) -> syn::Result<Ident> {
     let lookahead = input.lookahead1();
     if lookahead.peek(Token![_]) {
@@ -376,3 +1163,113 @@ fn get_cfg_predicates(params: &Vec<ParseQueryParam>) -> Vec<TokenStream> {
 
     result
 }
+
+fn parse_expect_archetypes(
+    input: ParseStream,
+) -> syn::Result<Option<ParseAttributeExpectArchetypes>> {
+    let mut result = None;
+
+    for attribute in parse_attributes(input)?.drain(..) {
+        match attribute.data {
+            ParseAttributeData::ExpectArchetypes(expect) if result.is_none() => {
+                result = Some(expect);
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    attribute.span,
+                    "invalid attribute for this position",
+                ));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn peek_query_struct(input: ParseStream) -> bool {
+    if input.peek(Token![::]) {
+        return true;
+    }
+
+    // Anything else that isn't a reference or a special type must be a query struct
+    match input.fork().call(Ident::parse_any) {
+        Ok(ident) => !matches!(
+            ident.to_string().as_str(),
+            "dyn"
+                | "Entity"
+                | "EntityAny"
+                | "EntityDirect"
+                | "EntityDirectAny"
+                | "OneOf"
+                | "Option"
+                | "With"
+                | "Without"
+        ),
+        Err(_) => false,
+    }
+}
+
+fn expand_query_structs(params: &mut Vec<ParseQueryParam>, body: Option<&mut Expr>) {
+    let mut result = Vec::with_capacity(params.len());
+    let mut bindings = Vec::new();
+
+    for param in params.drain(..) {
+        let fields = match &param.param_type {
+            ParseQueryParamType::Struct(query_struct) => query_struct.fields.as_ref(),
+            _ => None,
+        };
+
+        // Leave unresolved structs in place so we know to go and resolve them
+        let Some(fields) = fields else {
+            result.push(param);
+            continue;
+        };
+
+        // Splice each field in as a regular (hygienic) parameter, then build the struct
+        let mut args = Vec::with_capacity(fields.len());
+        for field in fields.iter() {
+            let span = param.name.span().resolved_at(Span::mixed_site());
+            let arg = format_ident!("__{}_{}", param.name, field.name, span = span);
+            args.push(arg.clone());
+            result.push(ParseQueryParam { name: arg, ..field.clone() });
+        }
+
+        let ParseQueryParamType::Struct(query_struct) = &param.param_type else { unreachable!() };
+        let name = &param.name;
+        let path = &query_struct.path;
+
+        bindings.push(quote!(
+            #[allow(unused_mut)]
+            let mut #name = #path::__ecs_query_new(#(#args),*);
+        ));
+    }
+
+    *params = result;
+
+    if let Some(body) = body
+        && bindings.is_empty() == false
+    {
+        *body = syn::parse_quote!({ #(#bindings)* #body });
+    }
+}
+
+fn reject_query_structs(params: &[ParseQueryParam], query: &str) -> syn::Result<()> {
+    for param in params.iter() {
+        if let ParseQueryParamType::Struct(_) = param.param_type {
+            return Err(syn::Error::new_spanned(
+                &param.name,
+                format!("query structs are not supported in {}", query),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn find_unresolved_struct<'a>(
+    params: impl IntoIterator<Item = &'a ParseQueryParam>,
+) -> Option<(&'a Ident, &'a ParseQueryStruct)> {
+    params.into_iter().find_map(|param| match &param.param_type {
+        ParseQueryParamType::Struct(query_struct) => Some((&param.name, query_struct.as_ref())),
+        _ => None,
+    })
+}
@@ -0,0 +1,34 @@
+use proc_macro2::Span;
+use syn::parse::{Parse, ParseStream};
+use syn::token::Comma;
+use syn::{Path, Token};
+
+mod kw {
+    syn::custom_keyword!(world);
+}
+
+#[derive(Debug)]
+pub struct ParseEcsSystemArgs {
+    pub world: Path,
+}
+
+impl Parse for ParseEcsSystemArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // The world's query macros are found through this path, so we can't assume a default
+        if input.is_empty() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "ecs_system needs a `world = ...` argument naming the world type, \
+                 as in `#[ecs_system(world = crate::EcsWorld)]`",
+            ));
+        }
+
+        // The only supported argument is the world type, as in `world = MyWorld`
+        input.parse::<kw::world>()?;
+        input.parse::<Token![=]>()?;
+        let world = input.parse::<Path>()?;
+        input.parse::<Option<Comma>>()?;
+
+        Ok(Self { world })
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use super::*;
 use proc_macro2::{Span, TokenStream};
@@ -17,6 +17,7 @@ mod kw {
 
     syn::custom_keyword!(ecs_archetype);
     syn::custom_keyword!(ecs_name);
+    syn::custom_keyword!(ecs_template);
 }
 
 pub trait HasAttributeId {
@@ -28,33 +29,46 @@ pub trait HasAttributeId {
 #[derive(Debug)]
 pub struct ParseEcsWorld {
     pub name: Ident,
+    pub derives: Vec<Ident>,
     pub archetypes: Vec<ParseArchetype>,
 }
 
 #[derive(Debug)]
 pub enum ParseItem {
-    ParseName(ParseName),
-    ParseArchetype(ParseArchetype),
+    Name(ParseName),
+    Archetype(ParseArchetype),
+    Template(ParseTemplate),
 }
 
 #[derive(Debug)]
 pub struct ParseName {
     pub name: Ident,
+    pub derives: Vec<Ident>,
 }
 
 #[derive(Debug)]
 pub struct ParseArchetype {
     pub cfgs: Vec<ParseAttributeCfg>,
     pub id: Option<u8>,
+    pub derives: Vec<Ident>,
     pub name: Ident,
+    pub bases: Vec<Ident>,
     pub components: Vec<ParseComponent>,
 }
 
 #[derive(Debug)]
+pub struct ParseTemplate {
+    pub name: Ident,
+    pub bases: Vec<Ident>,
+    pub components: Vec<ParseComponent>,
+}
+
+#[derive(Clone, Debug)]
 pub struct ParseComponent {
     pub cfgs: Vec<ParseAttributeCfg>,
     pub id: Option<u8>,
     pub name: ParseComponentName,
+    pub implements: Vec<Ident>,
     pub default: Option<Expr>,
 }
 
@@ -97,17 +111,30 @@ impl Parse for ParseEcsWorld {
             .collect::<Vec<_>>();
 
         let mut name = format_ident!("EcsWorld");
+        let mut derives = Vec::new();
         let mut archetypes = Vec::new();
 
+        // The full component lists of everything that can be used as a base so far
+        let mut bases = HashMap::new();
+
         for item in items {
             match item {
-                ParseItem::ParseArchetype(item) => {
+                ParseItem::Archetype(mut item) => {
+                    item.components = resolve_bases(&bases, &item.bases, item.components)?;
+                    add_base(&mut bases, &item.name, &item.components);
+
                     // Collect all the archetypes
                     archetypes.push(item);
                 }
-                ParseItem::ParseName(item) => {
+                ParseItem::Template(item) => {
+                    // Templates have no storage, they only exist to be used as bases
+                    let components = resolve_bases(&bases, &item.bases, item.components)?;
+                    add_base(&mut bases, &item.name, &components);
+                }
+                ParseItem::Name(item) => {
                     // TODO: Check for duplicates?
                     name = item.name;
+                    derives.extend(item.derives);
                 }
             }
         }
@@ -121,7 +148,11 @@ impl Parse for ParseEcsWorld {
             ));
         }
 
-        Ok(Self { name, archetypes })
+        Ok(Self {
+            name,
+            derives,
+            archetypes,
+        })
     }
 }
 
@@ -135,6 +166,8 @@ impl Parse for ParseItem {
         let lookahead = input.lookahead1();
         if lookahead.peek(kw::ecs_archetype) {
             parse_item_archetype(input, attributes)
+        } else if lookahead.peek(kw::ecs_template) {
+            parse_item_template(input, attributes)
         } else if lookahead.peek(kw::ecs_name) {
             parse_item_name(input, attributes)
         } else {
@@ -155,7 +188,10 @@ impl Parse for ParseName {
 
         // TODO: Check for duplicates?
 
-        Ok(Self { name })
+        Ok(Self {
+            name,
+            derives: Vec::new(), // This will be filled at the item level
+        })
     }
 }
 
@@ -169,21 +205,32 @@ impl Parse for ParseArchetype {
         let content;
         parenthesized!(content in input);
 
-        let name: Ident = content.parse()?;
+        let (name, bases, components) = parse_archetype_contents(&content)?;
+
+        Ok(Self {
+            cfgs,
+            id: None,
+            derives: Vec::new(), // This will be filled at the item level
+            name,
+            bases,
+            components,
+        })
+    }
+}
 
-        content.parse::<Comma>()?;
+impl Parse for ParseTemplate {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<kw::ecs_template>()?;
+        input.parse::<Token![!]>()?;
 
-        let components: Vec<ParseComponent> =
-            Punctuated::<ParseComponent, Comma>::parse_terminated(&content)?
-                .into_iter()
-                .collect();
+        let content;
+        parenthesized!(content in input);
 
-        // TODO: Check for duplicates?
+        let (name, bases, components) = parse_archetype_contents(&content)?;
 
         Ok(Self {
-            cfgs,
-            id: None,
             name,
+            bases,
             components,
         })
     }
@@ -209,6 +256,7 @@ impl Parse for ParseComponent {
 
         // See if we have a manually-assigned component ID
         let mut component_id = None;
+        let mut implements = Vec::new();
 
         for attribute in attributes.into_iter() {
             match attribute.data {
@@ -222,6 +270,9 @@ impl Parse for ParseComponent {
                     }
                     component_id = Some(id.value);
                 }
+                ParseAttributeData::Implements(data) => {
+                    implements.extend(data.traits);
+                }
                 _ => {
                     return Err(syn::Error::new(
                         attribute.span,
@@ -235,6 +286,7 @@ impl Parse for ParseComponent {
             cfgs,
             id: component_id,
             name,
+            implements,
             default: None, // TODO (default_field_values)
         })
     }
@@ -261,6 +313,9 @@ fn parse_item_archetype(
                 }
                 archetype.id = Some(id.value);
             }
+            ParseAttributeData::Derive(data) => {
+                archetype.derives.extend(data.traits);
+            }
             _ => {
                 return Err(syn::Error::new(
                     attribute.span,
@@ -270,10 +325,10 @@ fn parse_item_archetype(
         }
     }
 
-    Ok(ParseItem::ParseArchetype(archetype))
+    Ok(ParseItem::Archetype(archetype))
 }
 
-fn parse_item_name(
+fn parse_item_template(
     input: ParseStream, //.
     attributes: Vec<ParseAttribute>,
 ) -> syn::Result<ParseItem> {
@@ -284,6 +339,142 @@ fn parse_item_name(
         ));
     }
 
-    let name = input.parse::<ParseName>()?;
-    Ok(ParseItem::ParseName(name))
+    let template = input.parse::<ParseTemplate>()?;
+    Ok(ParseItem::Template(template))
+}
+
+fn parse_item_name(
+    input: ParseStream, //.
+    attributes: Vec<ParseAttribute>,
+) -> syn::Result<ParseItem> {
+    let mut name = input.parse::<ParseName>()?;
+
+    for attribute in attributes.into_iter() {
+        match attribute.data {
+            ParseAttributeData::Derive(data) => {
+                name.derives.extend(data.traits);
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    attribute.span,
+                    "this attribute is not supported here",
+                ));
+            }
+        }
+    }
+
+    Ok(ParseItem::Name(name))
+}
+
+fn parse_archetype_contents(
+    input: ParseStream,
+) -> syn::Result<(Ident, Vec<Ident>, Vec<ParseComponent>)> {
+    let name: Ident = input.parse()?;
+
+    // Bases are optional, as in `Name: BaseA + BaseB`
+    let mut bases = Vec::new();
+    if input.peek(Token![:]) {
+        input.parse::<Token![:]>()?;
+
+        bases.push(input.parse::<Ident>()?);
+        while input.peek(Token![+]) {
+            input.parse::<Token![+]>()?;
+            bases.push(input.parse::<Ident>()?);
+        }
+    }
+
+    // With a base, the list of additional components may be empty
+    if bases.is_empty() || input.is_empty() == false {
+        input.parse::<Comma>()?;
+    }
+
+    let components: Vec<ParseComponent> =
+        Punctuated::<ParseComponent, Comma>::parse_terminated(input)?
+            .into_iter()
+            .collect();
+
+    // TODO: Check for duplicates?
+
+    Ok((name, bases, components))
+}
+
+fn add_base(
+    bases: &mut HashMap<String, Option<Vec<ParseComponent>>>,
+    name: &Ident,
+    components: &[ParseComponent],
+) {
+    // A name declared more than once (e.g. under different cfgs) can't be used as a base
+    bases
+        .entry(name.to_string())
+        .and_modify(|found| *found = None)
+        .or_insert_with(|| Some(components.to_vec()));
+}
+
+fn resolve_bases(
+    bases: &HashMap<String, Option<Vec<ParseComponent>>>,
+    names: &[Ident],
+    components: Vec<ParseComponent>,
+) -> syn::Result<Vec<ParseComponent>> {
+    if names.is_empty() {
+        return Ok(components);
+    }
+
+    let mut result = Vec::new();
+    let mut origins = HashMap::new();
+
+    for (idx, name) in names.iter().enumerate() {
+        if names[..idx].contains(name) {
+            return Err(syn::Error::new(
+                name.span(),
+                format!("base `{}` is listed more than once", name),
+            ));
+        }
+
+        let inherited = match bases.get(&name.to_string()) {
+            Some(Some(inherited)) => inherited,
+            Some(None) => {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("`{}` is declared more than once and can't be used as a base", name),
+                ));
+            }
+            None => {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("`{}` must be declared before it can be used as a base", name),
+                ));
+            }
+        };
+
+        for component in inherited.iter() {
+            let component_name = component.name.to_string();
+            if let Some(origin) = origins.insert(component_name.clone(), name)
+                && origin != name
+            {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!(
+                        "component `{}` is inherited from both `{}` and `{}`",
+                        component_name, origin, name,
+                    ),
+                ));
+            }
+            result.push(component.clone());
+        }
+    }
+
+    for component in components {
+        if let Some(origin) = origins.get(&component.name.to_string()) {
+            return Err(syn::Error::new(
+                component.name.span(),
+                format!(
+                    "component `{}` is already inherited from `{}`",
+                    component.name, origin,
+                ),
+            ));
+        }
+        result.push(component);
+    }
+
+    Ok(result)
 }
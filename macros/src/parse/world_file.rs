@@ -0,0 +1,276 @@
+use std::path::PathBuf;
+
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::LitStr;
+use toml::{Table, Value};
+
+#[derive(Debug)]
+pub struct ParseWorldFile {
+    pub path: LitStr,
+}
+
+/// An `ecs_world!` body read from a world file, along with the file's full path.
+pub struct ParseWorldFileBody {
+    pub path: String,
+    pub body: TokenStream,
+}
+
+impl Parse for ParseWorldFile {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse::<LitStr>()?;
+        input.parse::<Option<syn::Token![,]>>()?;
+        Ok(Self { path })
+    }
+}
+
+impl ParseWorldFile {
+    /// Reads the world file and converts it to the equivalent `ecs_world!` body.
+    pub fn read(&self) -> syn::Result<ParseWorldFileBody> {
+        let span = self.path.span();
+
+        // Like other build-time file access, paths are relative to the crate's manifest
+        let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+        let path = PathBuf::from(root).join(self.path.value());
+        let path_str = path.display().to_string();
+
+        let contents = std::fs::read_to_string(&path).map_err(|err| {
+            syn::Error::new(span, format!("failed to read world file `{}`: {}", path_str, err))
+        })?;
+
+        let table = contents.parse::<Table>().map_err(|err| {
+            syn::Error::new(span, format!("failed to parse world file `{}`: {}", path_str, err))
+        })?;
+
+        let context = WorldFileContext { span, path: &path_str };
+        let body = context.world(&table)?;
+
+        Ok(ParseWorldFileBody {
+            path: path_str,
+            body: respan(body, span),
+        })
+    }
+}
+
+struct WorldFileContext<'a> {
+    span: Span,
+    path: &'a str,
+}
+
+impl WorldFileContext<'_> {
+    fn error(&self, at: &str, message: impl AsRef<str>) -> syn::Error {
+        syn::Error::new(
+            self.span,
+            format!("in world file `{}` at {}: {}", self.path, at, message.as_ref()),
+        )
+    }
+
+    fn world(&self, table: &Table) -> syn::Result<TokenStream> {
+        self.check_keys("the top level", table, &["name", "derive", "archetype"])?;
+
+        let mut items = Vec::new();
+
+        // As in ecs_world!, world derives are attributes on the ecs_name! declaration
+        let derives = self.derives("the top level", table)?;
+        match table.get("name") {
+            Some(name) => {
+                let name = self.ident("`name`", name)?;
+                items.push(quote!(#derives ecs_name!(#name);));
+            }
+            None if derives.is_empty() == false => {
+                return Err(self.error("the top level", "`derive` requires a `name`"));
+            }
+            None => {}
+        }
+
+        // Archetypes and templates share one list, since their declaration order matters
+        let archetypes = match table.get("archetype") {
+            Some(Value::Array(archetypes)) => archetypes.as_slice(),
+            Some(_) => return Err(self.error("`archetype`", "expected an array of tables")),
+            None => &[],
+        };
+
+        for (idx, archetype) in archetypes.iter().enumerate() {
+            let at = format!("archetype {}", idx);
+            let Value::Table(archetype) = archetype else {
+                return Err(self.error(&at, "expected a table"));
+            };
+            items.push(self.archetype(&at, archetype)?);
+        }
+
+        Ok(quote!(#(#items)*))
+    }
+
+    fn archetype(&self, at: &str, table: &Table) -> syn::Result<TokenStream> {
+        let is_template = match table.get("template") {
+            Some(Value::Boolean(value)) => *value,
+            Some(_) => return Err(self.error(at, "`template` must be a boolean")),
+            None => false,
+        };
+
+        let name = match table.get("name") {
+            Some(name) => self.ident(at, name)?,
+            None => return Err(self.error(at, "missing `name`")),
+        };
+        let at = &format!("{} (`{}`)", at, name);
+
+        match is_template {
+            true => self.check_keys(at, table, &["name", "template", "bases", "components"])?,
+            false => self.check_keys(
+                at,
+                table,
+                &["name", "template", "id", "cfg", "derive", "bases", "components"],
+            )?,
+        }
+
+        let mut attributes = self.cfgs(at, table)?;
+        if let Some(id) = table.get("id") {
+            let id = self.id(at, id)?;
+            attributes.extend(quote!(#[archetype_id(#id)]));
+        }
+        attributes.extend(self.derives(at, table)?);
+
+        let bases = self.idents(at, "bases", table)?;
+        let bases = match bases.is_empty() {
+            true => quote!(),
+            false => quote!(: #(#bases)+*),
+        };
+
+        let components = match table.get("components") {
+            Some(Value::Array(components)) => components.as_slice(),
+            Some(_) => return Err(self.error(at, "`components` must be an array")),
+            None => &[],
+        };
+        let components = components
+            .iter()
+            .map(|component| self.component(at, component))
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        let keyword = match is_template {
+            true => format_ident!("ecs_template"),
+            false => format_ident!("ecs_archetype"),
+        };
+
+        Ok(quote!(#attributes #keyword!(#name #bases, #(#components),*);))
+    }
+
+    fn component(&self, at: &str, component: &Value) -> syn::Result<TokenStream> {
+        // A component is either just its name, or a table with its name and attributes
+        let table = match component {
+            Value::String(name) => return self.component_name(at, name),
+            Value::Table(table) => table,
+            _ => return Err(self.error(at, "components must be strings or tables")),
+        };
+
+        let name = match table.get("name") {
+            Some(Value::String(name)) => name,
+            _ => return Err(self.error(at, "component tables need a `name` string")),
+        };
+        let at = &format!("{}, component `{}`", at, name);
+        self.check_keys(at, table, &["name", "id", "cfg", "implements"])?;
+
+        let name = self.component_name(at, name)?;
+        let mut attributes = self.cfgs(at, table)?;
+        if let Some(id) = table.get("id") {
+            let id = self.id(at, id)?;
+            attributes.extend(quote!(#[component_id(#id)]));
+        }
+
+        let implements = self.idents(at, "implements", table)?;
+        if implements.is_empty() == false {
+            attributes.extend(quote!(#[implements(#(#implements),*)]));
+        }
+
+        Ok(quote!(#attributes #name))
+    }
+
+    fn component_name(&self, at: &str, name: &str) -> syn::Result<TokenStream> {
+        // Validated for real when the generated ecs_world! body is parsed
+        syn::parse_str::<TokenStream>(name)
+            .map_err(|_| self.error(at, format!("invalid component name `{}`", name)))
+    }
+
+    fn cfgs(&self, at: &str, table: &Table) -> syn::Result<TokenStream> {
+        let predicates = match table.get("cfg") {
+            Some(Value::String(predicate)) => vec![predicate],
+            Some(Value::Array(predicates)) => predicates
+                .iter()
+                .map(|predicate| match predicate {
+                    Value::String(predicate) => Ok(predicate),
+                    _ => Err(self.error(at, "`cfg` must be a string or array of strings")),
+                })
+                .collect::<syn::Result<Vec<_>>>()?,
+            Some(_) => return Err(self.error(at, "`cfg` must be a string or array of strings")),
+            None => Vec::new(),
+        };
+
+        let mut result = TokenStream::new();
+        for predicate in predicates {
+            let predicate = syn::parse_str::<TokenStream>(predicate)
+                .map_err(|_| self.error(at, format!("invalid cfg predicate `{}`", predicate)))?;
+            result.extend(quote!(#[cfg(#predicate)]));
+        }
+
+        Ok(result)
+    }
+
+    fn derives(&self, at: &str, table: &Table) -> syn::Result<TokenStream> {
+        let derives = self.idents(at, "derive", table)?;
+        match derives.is_empty() {
+            true => Ok(quote!()),
+            false => Ok(quote!(#[derive(#(#derives),*)])),
+        }
+    }
+
+    fn idents(&self, at: &str, key: &str, table: &Table) -> syn::Result<Vec<syn::Ident>> {
+        match table.get(key) {
+            Some(Value::Array(values)) => values.iter().map(|value| self.ident(at, value)).collect(),
+            Some(_) => Err(self.error(at, format!("`{}` must be an array of names", key))),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn ident(&self, at: &str, value: &Value) -> syn::Result<syn::Ident> {
+        let Value::String(name) = value else {
+            return Err(self.error(at, "expected a name string"));
+        };
+
+        syn::parse_str::<syn::Ident>(name)
+            .map_err(|_| self.error(at, format!("invalid name `{}`", name)))
+    }
+
+    fn id(&self, at: &str, value: &Value) -> syn::Result<u8> {
+        match value {
+            Value::Integer(id) => u8::try_from(*id)
+                .map_err(|_| self.error(at, "`id` must be between 0 and 255")),
+            _ => Err(self.error(at, "`id` must be an integer")),
+        }
+    }
+
+    fn check_keys(&self, at: &str, table: &Table, allowed: &[&str]) -> syn::Result<()> {
+        for key in table.keys() {
+            if allowed.contains(&key.as_str()) == false {
+                return Err(self.error(at, format!("unknown key `{}`", key)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Points every token at the given span, so errors in the file point at the macro's argument.
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let mut new = Group::new(group.delimiter(), respan(group.stream(), span));
+                new.set_span(span);
+                token = TokenTree::Group(new);
+            } else {
+                token.set_span(span);
+            }
+            token
+        })
+        .collect()
+}
@@ -1,5 +1,5 @@
 use std::alloc::{self, Layout};
-use std::cell::{Ref, RefCell, RefMut};
+use std::cell::{BorrowError, BorrowMutError, Ref, RefCell, RefMut};
 use std::marker::PhantomData;
 use std::mem::{self, MaybeUninit};
 use std::ptr::{self, NonNull};
@@ -12,8 +12,9 @@ use crate::archetype::iter::*;
 use crate::archetype::slices::*;
 use crate::archetype::slot::{Slot, SlotIndex};
 use crate::archetype::view::*;
-use crate::entity::{Entity, EntityDirect};
+use crate::entity::{ArchetypeId, Entity, EntityDirect};
 use crate::index::{TrimmedIndex, MAX_DATA_CAPACITY};
+use crate::stats::{ArchetypeMemoryStats, ComponentMemoryStats};
 use crate::traits::{Archetype, EntityKey, StorageCanResolve};
 use crate::util::debug_checked_assume;
 use crate::version::ArchetypeVersion;
@@ -128,6 +129,47 @@ macro_rules! declare_storage_n {
                     self.destroyed.clear();
                 }
 
+                /// Gathers memory and occupancy statistics for this storage. The caller
+                /// provides the archetype identity and the (id, name) of each component.
+                pub fn memory_stats(
+                    &self,
+                    archetype_id: ArchetypeId,
+                    name: &'static str,
+                    components: [(u8, &'static str); $n],
+                ) -> ArchetypeMemoryStats {
+                    #[cfg(feature = "events")]
+                    let (event_queue_len, event_queue_capacity) = (
+                        self.created.len() + self.destroyed.len(),
+                        self.created.capacity() + self.destroyed.capacity(),
+                    );
+                    #[cfg(not(feature = "events"))]
+                    let (event_queue_len, event_queue_capacity) = (0, 0);
+
+                    ArchetypeMemoryStats {
+                        archetype_id,
+                        name,
+                        len: self.len,
+                        capacity: self.capacity,
+                        slot_size: mem::size_of::<Slot>(),
+                        entity_size: mem::size_of::<Entity<A>>(),
+                        // Every slot that isn't assigned to a live entity is on the free list.
+                        free_slots: self.capacity - self.len,
+                        event_queue_len,
+                        event_queue_capacity,
+                        components: vec![
+                            #(
+                                ComponentMemoryStats {
+                                    component_id: components[I].0,
+                                    name: components[I].1,
+                                    element_size: mem::size_of::<T~I>(),
+                                    len: self.len,
+                                    capacity: self.capacity,
+                                },
+                            )*
+                        ],
+                    }
+                }
+
                 /// Adds a new entity with the given components to this storage.
                 /// Returns a typed entity handle pointing to the added element.
                 ///
@@ -300,6 +342,23 @@ macro_rules! declare_storage_n {
                     })
                 }
 
+                /// Populates a view struct with our stored data at the given dense index.
+                #[inline(always)]
+                pub fn get_view_at<'a, E: $view<'a, A, #(T~I,)*>>(
+                    &'a mut self,
+                    index: usize,
+                ) -> Option<E>
+                {
+                    (index < self.len).then(|| unsafe {
+                        // SAFETY: We checked above that index < self.len.
+                        // SAFETY: We guarantee that the storage is valid up to self.len.
+                        E::new(
+                            self.entities.slice(self.len).get_unchecked(index),
+                            #(self.d~I.get_mut().slice(self.len).get_unchecked(index),)*
+                        )
+                    })
+                }
+
                 /// Populates a view struct with our stored data for the given entity key.
                 /// This version also returns an EntityDirect to the direct dense position.
                 #[inline(always)]
@@ -348,6 +407,56 @@ macro_rules! declare_storage_n {
                     })
                 }
 
+                /// Populates a mutable view struct with our stored data at the given dense index.
+                #[inline(always)]
+                pub fn get_view_mut_at<'a, E: $view_mut<'a, A, #(T~I,)*>>(
+                    &'a mut self,
+                    index: usize,
+                ) -> Option<E>
+                {
+                    (index < self.len).then(|| unsafe {
+                        // SAFETY: We checked above that index < self.len.
+                        // SAFETY: We guarantee that the storage is valid up to self.len.
+                        E::new(
+                            self.entities.slice(self.len).get_unchecked(index),
+                            #(self.d~I.get_mut().slice_mut(self.len).get_unchecked_mut(index),)*
+                        )
+                    })
+                }
+
+                /// Populates mutable view structs for several dense indices at once. Indices given
+                /// as `None` produce `None` in the output. Returns `None` if any index is out of
+                /// bounds, or if the same index is given more than once.
+                #[inline(always)]
+                pub fn get_view_many_mut_at<'a, E: $view_mut<'a, A, #(T~I,)*>, const M: usize>(
+                    &'a mut self,
+                    indices: [Option<usize>; M],
+                ) -> Option<[Option<E>; M]>
+                {
+                    for (pos, index) in indices.iter().enumerate() {
+                        if let Some(index) = *index {
+                            if index >= self.len || indices[..pos].contains(&Some(index)) {
+                                return None;
+                            }
+                        }
+                    }
+
+                    unsafe {
+                        // SAFETY: We guarantee that the storage is valid up to self.len.
+                        let entities = self.entities.slice(self.len);
+                        #(let d~I = self.d~I.get_mut().slice_mut(self.len).as_mut_ptr();)*
+
+                        // SAFETY: We checked above that every index is in bounds and that no
+                        // index appears twice, so none of the references we create will alias.
+                        Some(indices.map(|index| index.map(|index| {
+                            E::new(
+                                entities.get_unchecked(index),
+                                #(&mut *d~I.add(index),)*
+                            )
+                        })))
+                    }
+                }
+
                 /// Populates a mutable view struct with our stored data for the given entity key.
                 /// This version also returns an EntityDirect to the direct dense position.
                 #[inline(always)]
@@ -391,6 +500,33 @@ macro_rules! declare_storage_n {
                     }
                 }
 
+                /// Gets all mutable slices of our stored data, alongside a resolver that can
+                /// look up entity keys while the slices are held. The resolver only reads our
+                /// slot and entity data, so it can't alias with any of the component slices.
+                #[inline(always)]
+                pub fn get_all_slices_mut_resolver<'a, S: $slices<'a, A, #(T~I,)*>>(
+                    &'a mut self,
+                ) -> (S, StorageResolver<'a, Self>)
+                {
+                    unsafe {
+                        debug_checked_assume!(self.len <= MAX_DATA_CAPACITY as usize);
+                        // SAFETY: We guarantee that the storage is valid up to self.len.
+                        #(let d~I = self.d~I.get_mut().slice_mut(self.len).as_mut_ptr();)*
+                        let len = self.len;
+                        let storage = &*self;
+
+                        // SAFETY: The component data lives in separate allocations from the
+                        // storage itself, and the resolver can't access any component data.
+                        (
+                            S::new(
+                                storage.entities.slice(len),
+                                #(slice::from_raw_parts_mut(d~I, len),)*
+                            ),
+                            StorageResolver { storage },
+                        )
+                    }
+                }
+
                 /// Gets a read-only slice of our currently stored entity handles.
                 #[inline(always)]
                 pub fn get_slice_entities(&self) -> &[Entity<A>] {
@@ -422,6 +558,33 @@ macro_rules! declare_storage_n {
                         }
                     }
 
+                    /// Gets a slice of the given component index without touching its borrow
+                    /// state, so that it can be read from more than one thread at once.
+                    ///
+                    /// # Safety
+                    ///
+                    /// The caller must guarantee that the data isn't mutably accessed (or
+                    /// mutably borrowed) anywhere for the lifetime of the returned slice.
+                    #[doc(hidden)]
+                    #[inline(always)]
+                    pub unsafe fn get_slice_unchecked_~I(&self) -> &[T~I] {
+                        unsafe {
+                            debug_checked_assume!(self.len <= MAX_DATA_CAPACITY as usize);
+                            // SAFETY: We guarantee that the storage is valid up to self.len,
+                            // and the caller guarantees that nothing is mutating the data.
+                            (*self.d~I.as_ptr()).slice(self.len)
+                        }
+                    }
+
+                    /// Gets a raw pointer to the start of the given component index's data.
+                    ///
+                    /// Unlike `get_slice_mut`, this doesn't create a reference to the data itself.
+                    #[doc(hidden)]
+                    #[inline(always)]
+                    pub fn get_ptr_~I(&mut self) -> NonNull<T~I> {
+                        self.d~I.get_mut().0.cast()
+                    }
+
                     /// Borrows the slice of the given component index.
                     #[inline(always)]
                     pub fn borrow_slice_~I(&self) -> Ref<'_, [T~I]> {
@@ -441,6 +604,32 @@ macro_rules! declare_storage_n {
                             slice.slice_mut(self.len)
                         })
                     }
+
+                    /// Borrows the slice of the given component index, failing if it is
+                    /// currently mutably borrowed.
+                    #[inline(always)]
+                    pub fn try_borrow_slice_~I(&self) -> Result<Ref<'_, [T~I]>, BorrowError> {
+                        self.d~I.try_borrow().map(|borrowed| {
+                            Ref::map(borrowed, |slice| unsafe {
+                                debug_checked_assume!(self.len <= MAX_DATA_CAPACITY as usize);
+                                // SAFETY: We guarantee that the storage is valid up to self.len.
+                                slice.slice(self.len)
+                            })
+                        })
+                    }
+
+                    /// Mutably borrows the slice of the given component index, failing if it
+                    /// is currently borrowed.
+                    #[inline(always)]
+                    pub fn try_borrow_slice_mut_~I(&self) -> Result<RefMut<'_, [T~I]>, BorrowMutError> {
+                        self.d~I.try_borrow_mut().map(|borrowed| {
+                            RefMut::map(borrowed, |slice| unsafe {
+                                debug_checked_assume!(self.len <= MAX_DATA_CAPACITY as usize);
+                                // SAFETY: We guarantee that the storage is valid up to self.len.
+                                slice.slice_mut(self.len)
+                            })
+                        })
+                    }
                 )*
 
                 /// Resolves the slot index and data index for a given entity.
@@ -869,6 +1058,30 @@ macro_rules! declare_storage_n {
                 }
             }
 
+            impl<A: Archetype, #(T~I,)*> PartialEq for $name<A, #(T~I,)*>
+            where
+                A::Components: $components<#(T~I,)*>,
+                #(T~I: PartialEq,)*
+            {
+                /// Compares the stored entities and their component data, in storage order.
+                ///
+                /// # Panics
+                ///
+                /// This function will panic if any of its components are mutably borrowed.
+                #[inline]
+                fn eq(&self, other: &Self) -> bool {
+                    self.get_slice_entities() == other.get_slice_entities()
+                        #(&& *self.borrow_slice_~I() == *other.borrow_slice_~I())*
+                }
+            }
+
+            impl<A: Archetype, #(T~I,)*> Eq for $name<A, #(T~I,)*>
+            where
+                A::Components: $components<#(T~I,)*>,
+                #(T~I: Eq,)*
+            {
+            }
+
             pub struct $borrow<'a, A: Archetype, #(T~I,)*> {
                 index: usize,
                 source: &'a $name<A, #(T~I,)*>,
@@ -918,6 +1131,32 @@ macro_rules! declare_storage_n {
                             slice.slice_mut(self.source.len).get_unchecked_mut(self.index)
                         })
                     }
+
+                    /// Borrows the element of the given component index, failing if its
+                    /// component slice is currently mutably borrowed.
+                    #[inline(always)]
+                    pub fn try_borrow_component_~I(&self) -> Result<Ref<'_, T~I>, BorrowError> {
+                        self.source.d~I.try_borrow().map(|borrowed| {
+                            Ref::map(borrowed, |slice| unsafe {
+                                debug_assert!(self.index < self.source.len);
+                                // SAFETY: See borrow_component.
+                                slice.slice(self.source.len).get_unchecked(self.index)
+                            })
+                        })
+                    }
+
+                    /// Mutably borrows the element of the given component index, failing if
+                    /// its component slice is currently borrowed.
+                    #[inline(always)]
+                    pub fn try_borrow_component_mut_~I(&self) -> Result<RefMut<'_, T~I>, BorrowMutError> {
+                        self.source.d~I.try_borrow_mut().map(|borrowed| {
+                            RefMut::map(borrowed, |slice| unsafe {
+                                debug_assert!(self.index < self.source.len);
+                                // SAFETY: See borrow_component_mut.
+                                slice.slice_mut(self.source.len).get_unchecked_mut(self.index)
+                            })
+                        })
+                    }
                 )*
             }
 
@@ -962,6 +1201,24 @@ seq!(N in 17..=32 {
     );
 });
 
+/// Resolves entity keys to dense indices for a storage whose component slices are currently
+/// mutably borrowed. See `get_all_slices_mut_resolver` on the storage types.
+pub struct StorageResolver<'a, S> {
+    storage: &'a S,
+}
+
+impl<S> StorageResolver<'_, S> {
+    /// Resolves an entity key to an index in the storage data slices.
+    /// This index is guaranteed to be in bounds and point to valid data.
+    #[inline(always)]
+    pub fn resolve<K: EntityKey>(&self, entity: K) -> Option<usize>
+    where
+        S: StorageCanResolve<K>,
+    {
+        <S as StorageCanResolve<K>>::resolve_for(self.storage, entity)
+    }
+}
+
 pub struct DataPtr<T>(NonNull<MaybeUninit<T>>);
 
 // SAFETY: There's no explicit interior mutability going on here -- this is similar to a Vec-type
@@ -4,6 +4,7 @@ use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::mem;
 use std::num::NonZeroU32;
+use std::slice;
 
 use crate::error::EcsError;
 use crate::index::{MAX_DATA_INDEX, TrimmedIndex};
@@ -506,10 +507,32 @@ where
         world.archetype_mut::<A>().view_mut(self)
     }
 
+    #[inline(always)]
+    fn resolve_view_many_mut<const N: usize>(
+        entities: [Self; N],
+        world: &'a mut W,
+    ) -> Option<[Self::ViewMut; N]> {
+        world.archetype_mut::<A>().view_many_mut(entities)
+    }
+
     #[inline(always)]
     fn resolve_borrow(self, world: &'a W) -> Option<Self::Borrow> {
         world.archetype::<A>().borrow(self)
     }
+
+    #[inline(always)]
+    fn resolve_visit_components(self, world: &'a W, visitor: &mut impl ComponentVisitor) -> bool {
+        world.archetype::<A>().visit_components(self, visitor)
+    }
+
+    #[inline(always)]
+    fn resolve_visit_components_mut(
+        self,
+        world: &'a mut W,
+        visitor: &mut impl ComponentVisitorMut,
+    ) -> bool {
+        world.archetype_mut::<A>().visit_components_mut(self, visitor)
+    }
 }
 
 impl<'a, A: Archetype + 'a, W: World> EntityKeySelectable<'a, W> for EntityDirect<A>
@@ -530,10 +553,32 @@ where
         world.archetype_mut::<A>().view_mut(self)
     }
 
+    #[inline(always)]
+    fn resolve_view_many_mut<const N: usize>(
+        entities: [Self; N],
+        world: &'a mut W,
+    ) -> Option<[Self::ViewMut; N]> {
+        world.archetype_mut::<A>().view_many_mut(entities)
+    }
+
     #[inline(always)]
     fn resolve_borrow(self, world: &'a W) -> Option<Self::Borrow> {
         world.archetype::<A>().borrow(self)
     }
+
+    #[inline(always)]
+    fn resolve_visit_components(self, world: &'a W, visitor: &mut impl ComponentVisitor) -> bool {
+        world.archetype::<A>().visit_components(self, visitor)
+    }
+
+    #[inline(always)]
+    fn resolve_visit_components_mut(
+        self,
+        world: &'a mut W,
+        visitor: &mut impl ComponentVisitorMut,
+    ) -> bool {
+        world.archetype_mut::<A>().visit_components_mut(self, visitor)
+    }
 }
 
 impl<'a, A: Archetype> From<&'a Entity<A>> for &'a EntityAny {
@@ -684,4 +729,45 @@ pub mod __internal {
     ) -> EntityDirect<A> {
         EntityDirect::new(TrimmedIndex::new_usize(index).unwrap(), version)
     }
+
+    /// The archetype an entity handle is typed to, if any. Used by find queries to reject
+    /// typed handles at compile-time when the query can never match their archetype.
+    #[doc(hidden)]
+    pub trait EntityKeyArchetype {
+        const ARCHETYPE_ID: Option<ArchetypeId>;
+    }
+
+    impl<A: Archetype> EntityKeyArchetype for Entity<A> {
+        const ARCHETYPE_ID: Option<ArchetypeId> = Some(A::ARCHETYPE_ID);
+    }
+
+    impl<A: Archetype> EntityKeyArchetype for EntityDirect<A> {
+        const ARCHETYPE_ID: Option<ArchetypeId> = Some(A::ARCHETYPE_ID);
+    }
+
+    impl EntityKeyArchetype for EntityAny {
+        const ARCHETYPE_ID: Option<ArchetypeId> = None;
+    }
+
+    impl EntityKeyArchetype for EntityDirectAny {
+        const ARCHETYPE_ID: Option<ArchetypeId> = None;
+    }
+
+    impl<K: EntityKeyArchetype> EntityKeyArchetype for &K {
+        const ARCHETYPE_ID: Option<ArchetypeId> = K::ARCHETYPE_ID;
+    }
+
+    impl<K: EntityKeyArchetype> EntityKeyArchetype for &mut K {
+        const ARCHETYPE_ID: Option<ArchetypeId> = K::ARCHETYPE_ID;
+    }
+
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn entity_slice_to_any<A: Archetype>(entities: &[Entity<A>]) -> &[EntityAny] {
+        unsafe {
+            // SAFETY: Entity<A> is a transparent struct containing only an EntityAny,
+            // which guarantees that they have the same representation in memory
+            slice::from_raw_parts(entities.as_ptr() as *const EntityAny, entities.len())
+        }
+    }
 }
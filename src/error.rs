@@ -1,5 +1,7 @@
 use std::fmt::{Display, Formatter, Result};
 
+use crate::entity::ArchetypeId;
+
 /// Error reporting enum for ECS operation failure.
 #[derive(Debug, Clone, PartialEq)]
 #[non_exhaustive]
@@ -8,16 +10,120 @@ pub enum EcsError {
     InvalidEntityType,
     /// We failed to construct a locally-valid entity handle from raw data.
     InvalidRawEntity,
+    /// The entity handle is stale, and the entity it referred to no longer exists.
+    Stale,
+    /// The entity exists, but its archetype does not match what this operation requires.
+    ArchetypeMismatch {
+        /// The archetype ID of the entity.
+        archetype_id: ArchetypeId,
+    },
+    /// The direct entity handle was invalidated by a change to its archetype.
+    DirectInvalidated,
+    /// A runtime borrow of a component column failed because it is already borrowed in a way
+    /// that conflicts with the requested access.
+    BorrowConflict {
+        /// The name of the archetype owning the component column.
+        archetype: &'static str,
+        /// The name of the component, as declared in the archetype.
+        component: &'static str,
+    },
+}
+
+/// Error reporting enum for a failed lookup with `ecs_try_find!`.
+///
+/// Unlike `ecs_find!`, which returns `None` in all of these cases, this indicates why the
+/// query closure could not be run for the given entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FindError {
+    /// The entity handle is stale, and the entity it referred to no longer exists.
+    Stale,
+    /// The entity's archetype does not have the components requested by the query.
+    /// This is reported whether or not the entity handle is stale.
+    ArchetypeMismatch {
+        /// The archetype ID of the entity.
+        archetype_id: ArchetypeId,
+    },
+    /// The direct entity handle was invalidated by a change to its archetype. The entity
+    /// may still exist, and can be found again using a regular entity handle.
+    DirectInvalidated,
+    /// The entity handle's archetype is not part of this world.
+    InvalidEntityType,
+    /// One of the query's components could not be runtime-borrowed because its column is
+    /// already borrowed in a way that conflicts with the requested access.
+    BorrowConflict {
+        /// The name of the archetype owning the component column.
+        archetype: &'static str,
+        /// The name of the component, as declared in the archetype.
+        component: &'static str,
+    },
 }
 
 impl std::error::Error for EcsError {}
 
+impl std::error::Error for FindError {}
+
 impl Display for EcsError {
     #[cold]
     fn fmt(&self, f: &mut Formatter) -> Result {
         match self {
             EcsError::InvalidEntityType => write!(f, "invalid type for entity"),
             EcsError::InvalidRawEntity => write!(f, "invalid raw entity data"),
+            EcsError::Stale => write!(f, "stale entity handle"),
+            EcsError::ArchetypeMismatch { archetype_id } => {
+                write!(f, "entity archetype {} does not match", archetype_id)
+            }
+            EcsError::DirectInvalidated => write!(f, "invalidated direct entity handle"),
+            EcsError::BorrowConflict { archetype, component } => {
+                write!(f, "conflicting borrow of {} in archetype {}", component, archetype)
+            }
+        }
+    }
+}
+
+impl Display for FindError {
+    #[cold]
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            FindError::Stale => write!(f, "stale entity handle"),
+            FindError::ArchetypeMismatch { archetype_id } => {
+                write!(f, "entity archetype {} does not match query", archetype_id)
+            }
+            FindError::DirectInvalidated => write!(f, "invalidated direct entity handle"),
+            FindError::InvalidEntityType => write!(f, "invalid type for entity"),
+            FindError::BorrowConflict { archetype, component } => {
+                write!(f, "conflicting borrow of {} in archetype {}", component, archetype)
+            }
+        }
+    }
+}
+
+impl From<FindError> for EcsError {
+    #[inline(always)]
+    fn from(error: FindError) -> Self {
+        match error {
+            FindError::Stale => EcsError::Stale,
+            FindError::ArchetypeMismatch { archetype_id } => {
+                EcsError::ArchetypeMismatch { archetype_id }
+            }
+            FindError::DirectInvalidated => EcsError::DirectInvalidated,
+            FindError::InvalidEntityType => EcsError::InvalidEntityType,
+            FindError::BorrowConflict { archetype, component } => {
+                EcsError::BorrowConflict { archetype, component }
+            }
+        }
+    }
+}
+
+impl FindError {
+    #[doc(hidden)]
+    #[cold]
+    pub fn __from_borrow(error: EcsError) -> Self {
+        match error {
+            EcsError::BorrowConflict { archetype, component } => {
+                FindError::BorrowConflict { archetype, component }
+            }
+            _ => unreachable!("borrow failures only report conflicts"),
         }
     }
 }
@@ -1,3 +1,6 @@
+use crate::entity::ArchetypeId;
+use crate::version::ArchetypeVersion;
+
 #[derive(Default)]
 pub enum EcsStep {
     #[default]
@@ -49,3 +52,111 @@ impl From<EcsStep> for EcsStepDestroy {
         }
     }
 }
+
+/// A resumable position for time-sliced iteration with `ecs_iter_budget!`.
+///
+/// The cursor records the archetype and dense index that iteration stopped at, along with
+/// that archetype's [`ArchetypeVersion`]. If an entity has since been destroyed in that
+/// archetype (which may reorder its entities), iteration of that archetype restarts from
+/// its beginning so that no entity is skipped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IterCursor {
+    position: Option<(ArchetypeId, usize, ArchetypeVersion)>,
+}
+
+impl IterCursor {
+    /// Creates a new cursor positioned at the start of a full pass.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self { position: None }
+    }
+
+    /// Resets this cursor to the start of a full pass.
+    #[inline(always)]
+    pub fn reset(&mut self) {
+        self.position = None;
+    }
+
+    /// Returns `true` if this cursor is at the start of a full pass.
+    #[inline(always)]
+    pub fn is_start(&self) -> bool {
+        self.position.is_none()
+    }
+
+    /// Returns the archetype that this cursor will resume iteration in, if any.
+    #[inline(always)]
+    pub fn archetype_id(&self) -> Option<ArchetypeId> {
+        self.position.map(|(archetype_id, _, _)| archetype_id)
+    }
+
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn take_position(&mut self) -> Option<(ArchetypeId, usize, ArchetypeVersion)> {
+        self.position.take()
+    }
+
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn set_position(&mut self, archetype_id: ArchetypeId, index: usize, version: ArchetypeVersion) {
+        self.position = Some((archetype_id, index, version));
+    }
+}
+
+#[doc(hidden)]
+pub mod __internal {
+    use std::marker::PhantomData;
+
+    use crate::entity::{Entity, EntityDirect};
+    use crate::traits::{Archetype, ArchetypeHas};
+    use crate::version::ArchetypeVersion;
+
+    /// Captures the type of an archetype for the archetype query macros, which may not be
+    /// able to name it (e.g. when the archetype is a generic parameter).
+    #[inline(always)]
+    pub fn archetype_marker<A: Archetype>(_: &A) -> PhantomData<A> {
+        PhantomData
+    }
+
+    /// Takes the slice for the given component out of an archetype's slices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice was already taken, i.e. if a query accesses a component twice.
+    #[inline(always)]
+    pub fn take_archetype_slice<'a, A: ArchetypeHas<C> + 'a, C>(
+        _: PhantomData<A>,
+        slices: &mut A::Slices<'a>,
+        len: usize,
+    ) -> &'a mut [C] {
+        let slice = <A as ArchetypeHas<C>>::resolve_take_slice(slices);
+        assert!(slice.len() == len, "query accesses the same component more than once");
+        slice
+    }
+
+    /// Gets the entity slice out of an archetype's slices.
+    #[inline(always)]
+    pub fn archetype_slices_entities<'a, A: Archetype + 'a>(
+        _: PhantomData<A>,
+        slices: &A::Slices<'a>,
+    ) -> &'a [Entity<A>] {
+        A::resolve_slices_entities(slices)
+    }
+
+    /// Creates a direct entity handle for the given dense index in an archetype.
+    #[inline(always)]
+    pub fn archetype_entity_direct<A: Archetype>(
+        _: PhantomData<A>,
+        index: usize,
+        version: ArchetypeVersion,
+    ) -> EntityDirect<A> {
+        crate::entity::__internal::new_entity_direct(index, version)
+    }
+
+    /// Returns mutable references to two distinct elements of a slice, where `a < b`.
+    #[inline(always)]
+    pub fn get_pair_mut<T>(slice: &mut [T], a: usize, b: usize) -> (&mut T, &mut T) {
+        debug_assert!(a < b);
+        let (lo, hi) = slice.split_at_mut(b);
+        (&mut lo[a], &mut hi[0])
+    }
+}
@@ -31,6 +31,18 @@ pub mod version;
 /// Enums for controlling iteration stepping.
 pub mod iter;
 
+/// Memory and occupancy statistics for archetype storage.
+pub mod stats;
+
+/// Typed queries that can be named as types and used without macros.
+pub mod query;
+
+/// Access metadata for declarative system functions.
+pub mod system;
+
+/// Scheduling for running systems in parallel based on their data access.
+pub mod schedule;
+
 mod macros {
     /// Macro for declaring a new ECS world struct with archetype storage.
     ///
@@ -56,18 +68,28 @@ mod macros {
     /// ```
     /// The `ecs_name!` inner pseudo-macro is used for setting the name (in PascalCase) of the
     /// ECS world struct. Without this declaration, the world's name will default to `EcsWorld`.
+    /// It supports a `#[derive(...)]` attribute, which applies to the world and all of its
+    /// archetypes (see below).
     ///
     /// ## ecs_archetype!
     ///
     /// ```ignore
     /// ecs_archetype!(Name, Component, ...);
+    /// ecs_archetype!(Name: Base + ..., Component, ...);
     /// ```
     /// The `ecs_archetype!` inner pseudo-macro is used for declaring an archetype in an ECS
     /// world. It takes the following arguments:
     ///
     /// - `Name`: The name (in PascalCase) of the archetype Rust type.
+    /// - `Base + ...`: Optionally, one or more archetypes or templates (see `ecs_template!`)
+    ///   declared earlier in the world, each listed at most once. The archetype starts with all
+    ///   of their components, in order, including their `#[cfg]` and `#[component_id]`
+    ///   attributes. Attributes on the base archetype itself (such as its `#[cfg]`) are not
+    ///   inherited.
     /// - `Component, ...`: One or more component types to include in this archetype. Because
     ///   generated archetypes are `pub` with `pub` members, all components must be `pub` too.
+    ///   When the archetype has a base, this list may be empty. No component may be inherited
+    ///   more than once, or both inherited and listed.
     ///
     /// The `ecs_archetype!` declaration supports the following attributes:
     ///
@@ -77,6 +99,82 @@ mod macros {
     ///   (which must be between `0` and `255`). By default, archetype IDs start at `0` and
     ///   count up sequentially from the last value, similar to enum discriminants. No two
     ///   archetypes may have the same archetype ID (this is compiler-enforced).
+    /// - `#[implements(Trait, ...)]` can be used on individual component parameters to declare
+    ///   that the component implements the given traits (this is compiler-enforced). This lets
+    ///   queries access the component as a `&dyn Trait` without naming its concrete type. The
+    ///   declaration applies to that component type in every archetype of the world.
+    /// - `#[derive(...)]` can be used to implement `PartialEq` and `Eq` for the archetype and
+    ///   its `Components` struct, comparing entities and component data in storage order.
+    ///   Like the generated `Default` and `Clone` implementations, these only apply when all
+    ///   of the archetype's components implement the trait. `Debug` and `Clone` are always
+    ///   generated (under the same condition), so deriving them is an error, as is deriving
+    ///   any other trait:
+    ///
+    /// ```compile_fail
+    /// use gecs::prelude::*;
+    ///
+    /// #[derive(Debug)]
+    /// pub struct CompA(pub u32);
+    ///
+    /// ecs_world! {
+    ///     #[derive(Debug)] // Error: `Debug` is always implemented
+    ///     ecs_archetype!(ArchFoo, CompA);
+    /// }
+    ///
+    /// # fn main() {}
+    /// ```
+    ///
+    /// ## ecs_template!
+    ///
+    /// ```ignore
+    /// ecs_template!(Name, Component, ...);
+    /// ecs_template!(Name: Base + ..., Component, ...);
+    /// ```
+    /// The `ecs_template!` inner pseudo-macro declares a list of components to be used as a
+    /// base by later `ecs_archetype!` or `ecs_template!` declarations. It takes the same
+    /// arguments as `ecs_archetype!`, but has no storage and generates no types. Templates
+    /// don't support attributes of their own, but their components do.
+    ///
+    /// ```
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct Position(pub f32);
+    /// pub struct Velocity(pub f32);
+    /// pub struct Health(pub u32);
+    /// pub struct BossPhase(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_template!(Movement, Position, Velocity);
+    ///
+    ///     ecs_archetype!(ArchEnemy: Movement, Health);
+    ///     ecs_archetype!(ArchBoss: ArchEnemy, BossPhase);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///     world.create::<ArchEnemy>((Position(0.0), Velocity(1.0), Health(10)));
+    ///     world.create::<ArchBoss>((Position(0.0), Velocity(1.0), Health(100), BossPhase(0)));
+    ///
+    ///     let mut count = 0;
+    ///     ecs_iter!(world, |_: &Position, _: &Velocity| count += 1);
+    ///     assert_eq!(count, 2);
+    /// }
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct Position(pub f32);
+    ///
+    /// ecs_world! {
+    ///     ecs_template!(Movement, Position);
+    ///
+    ///     // Error: base `Movement` is listed more than once
+    ///     ecs_archetype!(ArchMover: Movement + Movement);
+    /// }
+    ///
+    /// fn main() {}
+    /// ```
     ///
     /// # Examples
     ///
@@ -317,11 +415,95 @@ mod macros {
     ///   of this query will return a typed (direct) entity handle for the exact archetype matched
     ///   for this specific execution. This can be used to optimize switched behavior by type.
     /// - `&OneOf<A, B, ...>` or `&mut OneOf<A, B, ...>`: See [`OneOf`](crate::OneOf).
+    /// - `&dyn Trait` or `&mut dyn Trait`: Matches archetypes with exactly one component declared
+    ///   with `#[implements(Trait)]` in `ecs_world!`, and accesses it as a trait object. If an
+    ///   archetype has more than one such component, this will result in a compilation error.
+    /// - `&[&dyn Trait]` or `&mut [&mut dyn Trait]`: As above, but matches archetypes with at
+    ///   least one such component, and accesses all of them in declaration order.
+    /// - `With<C>`/`Without<C>`: Filters the query to archetypes that have (or do not have) the
+    ///   component `C`, without accessing it. These are written without a reference, and the
+    ///   closure receives no value for them, so they are usually named `_`.
     ///
     /// In query closures, a special `MatchedArchetype` type alias is set to the currently
     /// matched archetype being accessed during this execution of the closure. This can be used
     /// for generic operations.
     ///
+    /// # Compile Errors
+    ///
+    /// If an `&Entity<A>` or `&EntityDirect<A>` parameter pins the query to an archetype that the
+    /// rest of the query can't match, the error names the parameter at fault and lists the
+    /// archetype's components:
+    ///
+    /// ```compile_fail
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA);
+    ///     ecs_archetype!(ArchBar, CompA, CompB);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///     let entity = world.create::<ArchFoo>((CompA(0),));
+    ///
+    ///     // Error: archetype `ArchFoo` has no component `CompB`
+    ///     ecs_find!(world, entity, |_: &Entity<ArchFoo>, b: &CompB| {});
+    /// }
+    /// ```
+    ///
+    /// The same goes for a typed `Entity<A>` or `EntityDirect<A>` handle passed as the `entity`
+    /// argument, since the lookup could never succeed. The handle's type is only known after
+    /// macro expansion, so this is reported when the query is compiled to code (by `cargo build`,
+    /// but not by `cargo check`), and the error is attributed to the `ecs_find!` call as a whole.
+    /// This check doesn't apply to `EntityAny` and `EntityDirectAny` handles, or to
+    /// `ecs_try_find!`, which reports [`FindError::ArchetypeMismatch`](crate::error::FindError)
+    /// at runtime instead.
+    ///
+    /// ```compile_fail
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA);
+    ///     ecs_archetype!(ArchBar, CompA, CompB);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///     let foo: Entity<ArchFoo> = world.create::<ArchFoo>((CompA(0),));
+    ///
+    ///     // Error: handle is typed to `ArchFoo`, which this query can never match
+    ///     ecs_find!(world, foo, |b: &CompB| b.0);
+    /// }
+    /// ```
+    ///
+    /// Likewise, a `OneOf` parameter that matches more than one component in an archetype is an
+    /// error, which lists that archetype's components:
+    ///
+    /// ```compile_fail
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///     let entity = world.create::<ArchFoo>((CompA(0), CompB(0)));
+    ///
+    ///     // Error: OneOf parameter is ambiguous for ArchFoo, matching both CompB and CompA
+    ///     ecs_find!(world, entity, |_: &OneOf<CompA, CompB>| {});
+    /// }
+    /// ```
+    ///
     /// # Examples
     ///
     /// ```
@@ -422,6 +604,174 @@ mod macros {
         (...) => {...};
     }
 
+    /// Variant of `ecs_find!` that reports why the entity could not be found.
+    ///
+    /// ```ignore
+    /// ecs_try_find!(world, entity, |comp_a: &CompA, comp_b: &mut CompB, ...| { ... });
+    /// ```
+    ///
+    /// This takes the same arguments as [`ecs_find!`], but evaluates to a
+    /// `Result<R, FindError>` rather than an `Option<R>`. See
+    /// [`FindError`](crate::error::FindError) for the possible failure cases. Unlike
+    /// [`ecs_find!`], this does not panic if given an [`EntityAny`](crate::entity::EntityAny)
+    /// for an archetype that is not part of this world, and instead returns
+    /// `FindError::InvalidEntityType`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    ///     ecs_archetype!(ArchBar, CompA);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///
+    ///     let entity_a = world.create::<ArchFoo>((CompA(1), CompB(10)));
+    ///     let entity_b = world.create::<ArchBar>((CompA(2),));
+    ///
+    ///     assert_eq!(ecs_try_find!(world, entity_a, |b: &CompB| b.0), Ok(10));
+    ///     assert_eq!(
+    ///         ecs_try_find!(world, entity_b, |b: &CompB| b.0),
+    ///         Err(FindError::ArchetypeMismatch { archetype_id: ArchBar::ARCHETYPE_ID }),
+    ///     );
+    ///
+    ///     world.destroy(entity_a);
+    ///     assert_eq!(ecs_try_find!(world, entity_a, |b: &CompB| b.0), Err(FindError::Stale));
+    /// }
+    /// ```
+    #[cfg(doc)]
+    #[macro_export]
+    macro_rules! ecs_try_find {
+        (...) => {...};
+    }
+
+    /// Variant of `ecs_find_borrow!` that reports why the entity could not be found.
+    ///
+    /// ```ignore
+    /// ecs_try_find_borrow!(world, entity, |comp_a: &CompA, comp_b: &mut CompB, ...| { ... });
+    /// ```
+    ///
+    /// This takes the same arguments as [`ecs_find_borrow!`], but evaluates to a
+    /// `Result<R, FindError>` in the same way as [`ecs_try_find!`]. Rather than panicking when
+    /// a component's column is already borrowed in a conflicting way, this returns
+    /// `FindError::BorrowConflict` without running the query closure. This allows nested
+    /// queries to detect a conflict and back off.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///
+    ///     let entity_a = world.create::<ArchFoo>((CompA(1), CompB(10)));
+    ///     let entity_b = world.create::<ArchFoo>((CompA(2), CompB(20)));
+    ///
+    ///     let world = &world;
+    ///     ecs_iter_borrow!(world, |a: &CompA, entity: &Entity<ArchFoo>| {
+    ///         // CompA is borrowed by the outer iteration, so this can't proceed
+    ///         let result = ecs_try_find_borrow!(world, entity_b, |other: &mut CompA| {});
+    ///         assert_eq!(
+    ///             result,
+    ///             Err(FindError::BorrowConflict { archetype: "ArchFoo", component: "CompA" }),
+    ///         );
+    ///
+    ///         // CompB is not borrowed by the outer iteration, so this is fine
+    ///         let result = ecs_try_find_borrow!(world, entity_b, |b: &mut CompB| b.0 += a.0);
+    ///         assert_eq!(result, Ok(()));
+    ///     });
+    /// }
+    /// ```
+    #[cfg(doc)]
+    #[macro_export]
+    macro_rules! ecs_try_find_borrow {
+        (...) => {...};
+    }
+
+    /// Runs a query closure on each of the given entities, grouped by archetype.
+    ///
+    /// ```ignore
+    /// let missed = ecs_find_each!(world, handles, |comp_a: &CompA, comp_b: &mut CompB, ...| { ... });
+    /// ```
+    ///
+    /// This is a batched version of [`ecs_find!`] for running the same query on many entities.
+    /// It takes the following arguments:
+    ///
+    /// - `world`: The world (as an expression) that you want to query.
+    /// - `handles`: Any `IntoIterator` of entity keys (`Entity<A>`, `EntityAny`, and so on).
+    /// - `|comp_a: &CompA, comp_b: &mut CompB, ...| { ... }`: A query closure, as in [`ecs_iter!`].
+    ///
+    /// All handles are first resolved and grouped by archetype, and the closure is then run for
+    /// each group in a single pass over that archetype's data. This avoids dispatching on the
+    /// entity's archetype for every call. The closure may return [`EcsStep`](crate::iter::EcsStep)
+    /// to stop early.
+    ///
+    /// Evaluates to a `Vec` of the given handles that could not be found (for example because
+    /// they are stale) or whose archetype does not match the query, in their original order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any handle is an `EntityAny` or `EntityDirectAny` for an archetype that does
+    /// not exist in this world.
+    ///
+    /// # Ordering
+    ///
+    /// Entities are visited one archetype at a time. Within an archetype, entities are visited
+    /// in the order their handles were given. If a handle is given more than once, the closure
+    /// will run once for each time it appears.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    /// pub struct CompC(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    ///     ecs_archetype!(ArchBar, CompA, CompC);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///
+    ///     let entity_a = world.create::<ArchFoo>((CompA(1), CompB(10))).into_any();
+    ///     let entity_b = world.create::<ArchBar>((CompA(2), CompC(20))).into_any();
+    ///     let entity_c = world.create::<ArchFoo>((CompA(3), CompB(30))).into_any();
+    ///     world.destroy(entity_c);
+    ///
+    ///     let mut sum = 0;
+    ///     let missed = ecs_find_each!(world, [entity_a, entity_b, entity_c], |a: &CompA| {
+    ///         sum += a.0;
+    ///     });
+    ///
+    ///     assert_eq!(sum, 3);
+    ///     assert_eq!(missed, [entity_c]);
+    /// }
+    /// ```
+    #[cfg(doc)]
+    #[macro_export]
+    macro_rules! ecs_find_each {
+        (...) => {...};
+    }
+
     /// Iterates over all entities across all archetypes that match the given component bounds.
     ///
     /// ```ignore
@@ -454,11 +804,58 @@ mod macros {
     ///   of this query will return a typed (direct) entity handle for the exact archetype matched
     ///   for this specific execution. This can be used to optimize switched behavior by type.
     /// - `&OneOf<A, B, ...>` or `&mut OneOf<A, B, ...>`: See [`OneOf`](crate::OneOf).
+    /// - `&dyn Trait` or `&mut dyn Trait`: Matches archetypes with exactly one component declared
+    ///   with `#[implements(Trait)]` in `ecs_world!`, and accesses it as a trait object. If an
+    ///   archetype has more than one such component, this will result in a compilation error.
+    /// - `&[&dyn Trait]` or `&mut [&mut dyn Trait]`: As above, but matches archetypes with at
+    ///   least one such component, and accesses all of them in declaration order.
+    /// - `With<C>`/`Without<C>`: Filters the query to archetypes that have (or do not have) the
+    ///   component `C`, without accessing it. These are written without a reference, and the
+    ///   closure receives no value for them, so they are usually named `_`.
     ///
     /// In query closures, a special `MatchedArchetype` type alias is set to the currently
     /// matched archetype being accessed during this execution of the closure. This can be used
     /// for generic operations.
     ///
+    /// # Nested Queries
+    ///
+    /// An `ecs_iter!` can declare a single nested query after its closure:
+    ///
+    /// ```ignore
+    /// ecs_iter!(world, |comp_a: &mut CompA, parent: &Parent| {
+    ///     let parent_b = find_b(parent.0); // Returns an Option with the nested closure's result
+    /// }, nested find_b = |comp_b: &CompB| comp_b.0);
+    /// ```
+    ///
+    /// The nested query is callable by name from within the outer closure, and works like an
+    /// `ecs_find!` on the given entity handle (which must be the same handle type at every call).
+    /// Whether the nested query aliases the outer query's data is checked at compile-time:
+    /// within any archetype the outer query matches, the nested query can't access a component
+    /// the outer query accesses mutably, nor access mutably a component the outer query
+    /// accesses at all. Any such conflict is a compilation error. Otherwise, both queries access
+    /// the world's data directly, without the runtime checks and possible panics of
+    /// [`ecs_iter_borrow!`] and [`ecs_find_borrow!`].
+    ///
+    /// ```compile_fail
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct Parent(pub Entity<ArchFoo>);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, Parent);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///
+    ///     // Error: the nested query reads the CompA that the outer query is writing.
+    ///     ecs_iter!(world, |a: &mut CompA, parent: &Parent| {
+    ///         a.0 += find_a(parent.0).unwrap();
+    ///     }, nested find_a = |a: &CompA| a.0);
+    /// }
+    /// ```
+    ///
     /// # Ordering
     ///
     /// There are no guarantees on order in this iteration, nor any guarantees that the order will
@@ -516,6 +913,12 @@ mod macros {
     ///         sum += a.0;
     ///     });
     ///     assert_eq!(sum, 12);
+    ///
+    ///     // The nested query reads CompB, which the outer query doesn't access.
+    ///     ecs_iter!(world, |a: &mut CompA| {
+    ///         a.0 += find_b(entity_a).unwrap();
+    ///     }, nested find_b = |b: &CompB| b.0 + 1);
+    ///     assert_eq!(ecs_find!(world, entity_b, |a: &CompA| a.0), Some(7));
     /// }
     /// ```
     #[cfg(doc)]
@@ -550,23 +953,32 @@ mod macros {
         (...) => {...};
     }
 
-    /// Variant of `ecs_iter!` that allows for destroying the current entity while iterating.
+    /// Iterates over all entities in a single archetype, which may be a generic type parameter.
     ///
-    /// See [`ecs_iter`] for more information on iter queries.
+    /// ```ignore
+    /// ecs_iter_archetype!(archetype, |comp_a: &CompA, comp_b: &mut CompB, ...| { ... });
+    /// ```
     ///
-    /// This version works similarly to [`ecs_iter`], but with the `EcsStepDestroy` enum that
-    /// supports two additional values: `BreakDestroy` and `ContinueDestroy`. These will break
-    /// or continue and also immediately remove that entity after that iteration step. The
-    /// entity and its handle are not preserved after this process.
+    /// Unlike [`ecs_iter!`], this macro is not tied to any specific world, and can be used in
+    /// generic code that is only given an archetype, such as a function with a parameter
+    /// `archetype: &mut A` where `A: ArchetypeHas<CompA> + ArchetypeHas<CompB>`. It takes the
+    /// following arguments:
     ///
-    /// Note that performing an early-out break will end the iteration for all archetypes. This
-    /// can have unpredictable order, and is recommended only for searching for single entities.
+    /// - `archetype`: An expression evaluating to a `&mut` reference to the archetype.
+    /// - `|comp_a: &CompA, comp_b: &mut CompB, ...| { ... }`: A closure as in [`ecs_iter!`].
+    ///   The archetype must implement [`ArchetypeHas`](crate::traits::ArchetypeHas) for each
+    ///   component in the closure, otherwise this will result in a compilation error.
     ///
-    /// # Ordering
+    /// The closure may also take the `&Entity<_>`, `&EntityAny`, `&EntityDirect<_>`, and
+    /// `&EntityDirectAny` special arguments as described in [`ecs_iter!`]. Other special
+    /// arguments, and the `MatchedArchetype` type alias, are not supported since they depend on
+    /// the world's archetype definitions. As with [`ecs_iter!`], the closure can return an
+    /// [`EcsStep`](crate::iter::EcsStep) to stop iteration early.
     ///
-    /// There are no guarantees on order in this iteration, nor any guarantees that the order will
-    /// be the same as that of any other iteration operation. However, the order is guaranteed to
-    /// be deterministic on runs given identical ECS world state and prior operation order.
+    /// # Panics
+    ///
+    /// Panics if a component is accessed by more than one parameter. This can only happen
+    /// with generic component types that turn out to be the same type.
     ///
     /// # Examples
     ///
@@ -575,53 +987,712 @@ mod macros {
     ///
     /// pub struct CompA(pub u32);
     /// pub struct CompB(pub u32);
-    /// pub struct CompC(pub u32);
     ///
     /// ecs_world! {
     ///     ecs_archetype!(ArchFoo, CompA, CompB);
-    ///     ecs_archetype!(ArchBar, CompA, CompC);
+    ///     ecs_archetype!(ArchBar, CompB, CompA);
+    /// }
+    ///
+    /// fn add_b_to_a<A: ArchetypeHas<CompA> + ArchetypeHas<CompB>>(archetype: &mut A) {
+    ///     ecs_iter_archetype!(archetype, |a: &mut CompA, b: &CompB| {
+    ///         a.0 += b.0;
+    ///     });
     /// }
     ///
     /// fn main() {
     ///     let mut world = EcsWorld::default();
     ///
-    ///     world.archetype_mut::<ArchFoo>().create((CompA(1), CompB(10)));
-    ///     world.archetype_mut::<ArchFoo>().create((CompA(2), CompB(20)));
-    ///     world.archetype_mut::<ArchFoo>().create((CompA(3), CompB(30)));
-    ///
-    ///     world.archetype_mut::<ArchBar>().create((CompA(4), CompC(10)));
-    ///     world.archetype_mut::<ArchBar>().create((CompA(5), CompC(10)));
-    ///     world.archetype_mut::<ArchBar>().create((CompA(6), CompC(10)));
-    ///
-    ///     let mut vec_a = Vec::<u32>::new();
-    ///     let mut vec_b = Vec::<u32>::new();
-    ///
-    ///     ecs_iter_destroy!(world, |comp_a: &CompA| {
-    ///         if comp_a.0 & 1 == 0 {
-    ///             vec_a.push(comp_a.0);
-    ///             EcsStepDestroy::ContinueDestroy
-    ///         } else {
-    ///             EcsStepDestroy::Continue
-    ///         }
-    ///     });
+    ///     let entity_a = world.create::<ArchFoo>((CompA(1), CompB(10)));
+    ///     let entity_b = world.create::<ArchBar>((CompB(20), CompA(2)));
     ///
-    ///     ecs_iter!(world, |comp_a: &CompA| {
-    ///         vec_b.push(comp_a.0);
-    ///     });
+    ///     add_b_to_a(&mut world.arch_foo);
+    ///     add_b_to_a(world.archetype_mut::<ArchBar>());
     ///
-    ///     assert_eq!(vec_a.iter().copied().sum::<u32>(), 2 + 4 + 6);
-    ///     assert_eq!(vec_b.iter().copied().sum::<u32>(), 1 + 3 + 5);
+    ///     assert_eq!(ecs_find!(world, entity_a, |a: &CompA| a.0), Some(11));
+    ///     assert_eq!(ecs_find!(world, entity_b, |a: &CompA| a.0), Some(22));
     /// }
     /// ```
     #[cfg(doc)]
     #[macro_export]
-    macro_rules! ecs_iter_destroy {
+    macro_rules! ecs_iter_archetype {
         (...) => {...};
     }
-}
 
-/// A special parameter type for ECS query closures to match one of multiple components.
-///
+    /// Finds a single entity in a single archetype, which may be a generic type parameter.
+    ///
+    /// ```ignore
+    /// ecs_find_archetype!(archetype, entity, |comp_a: &CompA, comp_b: &mut CompB, ...| { ... });
+    /// ```
+    ///
+    /// This is the [`ecs_find!`] equivalent of [`ecs_iter_archetype!`], and takes the same
+    /// arguments, along with an entity handle (typed, or direct) for the given archetype.
+    /// It returns an `Option` containing the closure's result, or `None` if the entity could
+    /// not be found.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a component is accessed by more than one parameter. This can only happen
+    /// with generic component types that turn out to be the same type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA);
+    /// }
+    ///
+    /// fn get_a<A: ArchetypeHas<CompA>>(archetype: &mut A, entity: Entity<A>) -> Option<u32> {
+    ///     ecs_find_archetype!(archetype, entity, |a: &CompA| a.0)
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///     let entity = world.create::<ArchFoo>((CompA(1),));
+    ///
+    ///     assert_eq!(get_a(&mut world.arch_foo, entity), Some(1));
+    /// }
+    /// ```
+    #[cfg(doc)]
+    #[macro_export]
+    macro_rules! ecs_find_archetype {
+        (...) => {...};
+    }
+
+    /// Variant of `ecs_iter!` that allows for destroying the current entity while iterating.
+    ///
+    /// See [`ecs_iter`] for more information on iter queries.
+    ///
+    /// This version works similarly to [`ecs_iter`], but with the `EcsStepDestroy` enum that
+    /// supports two additional values: `BreakDestroy` and `ContinueDestroy`. These will break
+    /// or continue and also immediately remove that entity after that iteration step. The
+    /// entity and its handle are not preserved after this process.
+    ///
+    /// Note that performing an early-out break will end the iteration for all archetypes. This
+    /// can have unpredictable order, and is recommended only for searching for single entities.
+    ///
+    /// # Ordering
+    ///
+    /// There are no guarantees on order in this iteration, nor any guarantees that the order will
+    /// be the same as that of any other iteration operation. However, the order is guaranteed to
+    /// be deterministic on runs given identical ECS world state and prior operation order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    /// pub struct CompC(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    ///     ecs_archetype!(ArchBar, CompA, CompC);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///
+    ///     world.archetype_mut::<ArchFoo>().create((CompA(1), CompB(10)));
+    ///     world.archetype_mut::<ArchFoo>().create((CompA(2), CompB(20)));
+    ///     world.archetype_mut::<ArchFoo>().create((CompA(3), CompB(30)));
+    ///
+    ///     world.archetype_mut::<ArchBar>().create((CompA(4), CompC(10)));
+    ///     world.archetype_mut::<ArchBar>().create((CompA(5), CompC(10)));
+    ///     world.archetype_mut::<ArchBar>().create((CompA(6), CompC(10)));
+    ///
+    ///     let mut vec_a = Vec::<u32>::new();
+    ///     let mut vec_b = Vec::<u32>::new();
+    ///
+    ///     ecs_iter_destroy!(world, |comp_a: &CompA| {
+    ///         if comp_a.0 & 1 == 0 {
+    ///             vec_a.push(comp_a.0);
+    ///             EcsStepDestroy::ContinueDestroy
+    ///         } else {
+    ///             EcsStepDestroy::Continue
+    ///         }
+    ///     });
+    ///
+    ///     ecs_iter!(world, |comp_a: &CompA| {
+    ///         vec_b.push(comp_a.0);
+    ///     });
+    ///
+    ///     assert_eq!(vec_a.iter().copied().sum::<u32>(), 2 + 4 + 6);
+    ///     assert_eq!(vec_b.iter().copied().sum::<u32>(), 1 + 3 + 5);
+    /// }
+    /// ```
+    #[cfg(doc)]
+    #[macro_export]
+    macro_rules! ecs_iter_destroy {
+        (...) => {...};
+    }
+
+    /// Variant of `ecs_iter!` that stops after a fixed number of entities and can be resumed.
+    ///
+    /// ```ignore
+    /// let finished = ecs_iter_budget!(world, &mut cursor, max_rows, |comp_a: &CompA, ...| { ... });
+    /// ```
+    ///
+    /// This macro takes the following arguments:
+    ///
+    /// - `world`: The world (as an expression) that you want to query.
+    /// - `&mut cursor`: An [`IterCursor`](crate::iter::IterCursor) storing where the previous
+    ///   call stopped. Use a separate cursor for each distinct query.
+    /// - `max_rows`: The maximum number of entities to visit in this call, as a `usize`.
+    /// - `|comp_a: &CompA, ...| { ... }`: A query closure, as in [`ecs_iter!`].
+    ///
+    /// Iteration resumes from the cursor's position and visits at most `max_rows` entities,
+    /// updating the cursor to where it stopped. This allows spreading the work of a large
+    /// iteration across multiple frames. Evaluates to `true` if this call reached the end of a
+    /// full pass, at which point the cursor is reset so the next call starts a new pass. The
+    /// closure may also return [`EcsStep`](crate::iter::EcsStep) to stop early, in which case
+    /// the next call will resume from the following entity.
+    ///
+    /// Entities created between calls will be visited in the current pass if they belong to an
+    /// archetype that has not been fully visited yet. If an entity is destroyed in the archetype
+    /// that the cursor stopped in, that archetype will be restarted from the beginning, since
+    /// destroying an entity may reorder the others. In that case some entities may be visited
+    /// more than once in that pass, but none will be skipped.
+    ///
+    /// # Ordering
+    ///
+    /// This iterates in the same order as [`ecs_iter!`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA);
+    ///     ecs_archetype!(ArchBar, CompA);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///
+    ///     for i in 0..10 {
+    ///         world.create::<ArchFoo>((CompA(i),));
+    ///         world.create::<ArchBar>((CompA(i),));
+    ///     }
+    ///
+    ///     let mut cursor = IterCursor::new();
+    ///     let mut frames = 0;
+    ///
+    ///     // Visit at most 8 entities per frame.
+    ///     loop {
+    ///         frames += 1;
+    ///         if ecs_iter_budget!(world, &mut cursor, 8, |a: &mut CompA| a.0 += 1) {
+    ///             break;
+    ///         }
+    ///     }
+    ///
+    ///     assert_eq!(frames, 3);
+    /// }
+    /// ```
+    #[cfg(doc)]
+    #[macro_export]
+    macro_rules! ecs_iter_budget {
+        (...) => {...};
+    }
+
+    /// Variant of `ecs_iter!` that visits entities across all archetypes in order of a sort key.
+    ///
+    /// ```ignore
+    /// ecs_iter_sorted!(world, |depth: &Depth| depth.0, |comp_a: &CompA, comp_b: &mut CompB, ...| { ... });
+    /// ```
+    ///
+    /// This macro takes the following arguments:
+    ///
+    /// - `world`: The world (as an expression) that you want to query.
+    /// - `|depth: &Depth| depth.0`: A key closure computing the sort key for each entity. Its
+    ///   parameters work as in [`ecs_iter!`], but must all be read-only. The key must implement
+    ///   [`Ord`]. Use [`Reverse`](std::cmp::Reverse) to sort in descending order.
+    /// - `|comp_a: &CompA, comp_b: &mut CompB, ...| { ... }`: A query closure, as in [`ecs_iter!`].
+    ///
+    /// Only archetypes matching the parameters of both closures are visited. The key for every
+    /// matching entity is computed up front, and the keys are then sorted before running the
+    /// query closure on each entity in that order. This allocates a buffer holding one key and
+    /// location for each matching entity. The closure may return [`EcsStep`](crate::iter::EcsStep)
+    /// to stop early.
+    ///
+    /// # Ordering
+    ///
+    /// Entities are visited in ascending order of their key. Entities with equal keys are visited
+    /// in the same relative order as they would be in [`ecs_iter!`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct Depth(pub u32);
+    /// pub struct Sprite(pub &'static str);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchPlayer, Depth, Sprite);
+    ///     ecs_archetype!(ArchProp, Depth, Sprite);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///
+    ///     world.create::<ArchProp>((Depth(2), Sprite("tree")));
+    ///     world.create::<ArchPlayer>((Depth(1), Sprite("player")));
+    ///     world.create::<ArchProp>((Depth(0), Sprite("grass")));
+    ///
+    ///     let mut drawn = Vec::new();
+    ///     ecs_iter_sorted!(world, |depth: &Depth| depth.0, |sprite: &Sprite| {
+    ///         drawn.push(sprite.0);
+    ///     });
+    ///
+    ///     assert_eq!(drawn, ["grass", "player", "tree"]);
+    /// }
+    /// ```
+    #[cfg(doc)]
+    #[macro_export]
+    macro_rules! ecs_iter_sorted {
+        (...) => {...};
+    }
+
+    /// Variant of `ecs_iter!` that visits each unordered pair of distinct matching entities.
+    ///
+    /// ```ignore
+    /// ecs_iter_pairs!(world, |(pos: &Pos, vel: &mut Vel, ...), (other_pos, other_vel, ...)| { ... });
+    /// ```
+    ///
+    /// The first tuple in the closure declares the query parameters as in [`ecs_iter!`], and the
+    /// second tuple gives names for the same parameters on the other entity of the pair. Both
+    /// entities are accessed with the same mutability, so `&mut` parameters allow mutation of
+    /// both sides at once, regardless of whether the two entities share an archetype. Each pair
+    /// is visited exactly once, and an entity is never paired with itself. Direct entity handles
+    /// (`&EntityDirect<A>` and similar) are not supported here.
+    ///
+    /// The closure may return [`EcsStep`](crate::iter::EcsStep) to stop iteration early.
+    ///
+    /// # Ordering
+    ///
+    /// There are no guarantees on the order of pairs, nor on which entity of a pair is bound to
+    /// the first tuple. However, the order is guaranteed to be deterministic on runs given
+    /// identical ECS world state and prior operation order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct Pos(pub i32);
+    /// pub struct Force(pub i32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, Pos, Force);
+    ///     ecs_archetype!(ArchBar, Pos, Force);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///
+    ///     let entity_a = world.create::<ArchFoo>((Pos(0), Force(0)));
+    ///     let entity_b = world.create::<ArchFoo>((Pos(3), Force(0)));
+    ///     let entity_c = world.create::<ArchBar>((Pos(5), Force(0)));
+    ///
+    ///     ecs_iter_pairs!(world, |(a_pos: &Pos, a_force: &mut Force), (b_pos, b_force)| {
+    ///         a_force.0 += b_pos.0 - a_pos.0;
+    ///         b_force.0 += a_pos.0 - b_pos.0;
+    ///     });
+    ///
+    ///     assert_eq!(ecs_find!(world, entity_a, |f: &Force| f.0), Some(8));
+    ///     assert_eq!(ecs_find!(world, entity_b, |f: &Force| f.0), Some(-1));
+    ///     assert_eq!(ecs_find!(world, entity_c, |f: &Force| f.0), Some(-7));
+    /// }
+    /// ```
+    #[cfg(doc)]
+    #[macro_export]
+    macro_rules! ecs_iter_pairs {
+        (...) => {...};
+    }
+
+    /// Returns an iterator over all entities in the world that match the given query parameters.
+    ///
+    /// ```ignore
+    /// ecs_query!(world, |comp_a: &CompA, comp_b: &mut CompB, ...|)
+    /// ```
+    ///
+    /// The query closure takes the same parameters as in [`ecs_iter!`], but has no body. Instead,
+    /// this macro evaluates to an iterator of tuples containing one element for each parameter,
+    /// chained across every matching archetype. Unlike [`ecs_iter!`], this allows the use of
+    /// iterator adapters, early returns from the enclosing function, and holding references
+    /// across iterations. The world is mutably borrowed for as long as the iterator is alive.
+    ///
+    /// Because every matching archetype must yield the same item type, parameters that would
+    /// bind to a different type in each archetype (such as `&Entity<_>` or a `OneOf` that
+    /// resolves to different components) will result in a compilation error. Direct entity
+    /// handles (`&EntityDirect<A>` and similar) and `&[&dyn Trait]` are not supported here.
+    ///
+    /// # Ordering
+    ///
+    /// There are no guarantees on order in this iteration, nor any guarantees that the order will
+    /// be the same as that of any other iteration operation. However, the order is guaranteed to
+    /// be deterministic on runs given identical ECS world state and prior operation order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    /// pub struct CompC(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    ///     ecs_archetype!(ArchBar, CompA, CompC);
+    /// }
+    ///
+    /// fn find_entity(world: &mut EcsWorld, value: u32) -> Option<EntityAny> {
+    ///     for (entity, a) in ecs_query!(world, |entity: &EntityAny, a: &CompA|) {
+    ///         if a.0 == value {
+    ///             return Some(*entity);
+    ///         }
+    ///     }
+    ///     None
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///
+    ///     let entity_a = world.create::<ArchFoo>((CompA(1), CompB(10)));
+    ///     let entity_b = world.create::<ArchBar>((CompA(2), CompC(20)));
+    ///
+    ///     // This iterates both ArchFoo and ArchBar since both have a CompA.
+    ///     for (a,) in ecs_query!(world, |a: &mut CompA|) {
+    ///         a.0 += 3;
+    ///     }
+    ///
+    ///     let sum: u32 = ecs_query!(world, |a: &CompA|).map(|(a,)| a.0).sum();
+    ///     assert_eq!(sum, 9);
+    ///
+    ///     assert_eq!(find_entity(&mut world, 5), Some(entity_b.into()));
+    ///     assert_eq!(find_entity(&mut world, 6), None);
+    /// }
+    /// ```
+    #[cfg(doc)]
+    #[macro_export]
+    macro_rules! ecs_query {
+        (...) => {...};
+    }
+
+    /// Counts the number of entities in the world that match the given query parameters.
+    ///
+    /// ```ignore
+    /// ecs_count!(world, |comp_a: &CompA, _: Without<CompB>, ...|)
+    /// ecs_count!(world, |comp_a: &CompA, ...| comp_a.0 > 1)
+    /// ```
+    ///
+    /// The query closure takes the same parameters as in [`ecs_iter!`]. If the closure has no
+    /// body, this evaluates to the sum of the lengths of every matching archetype, and does not
+    /// touch any individual entity's data. If the closure has a body, it must evaluate to a `bool`,
+    /// and only entities for which it returns `true` are counted. Evaluates to a `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    ///     ecs_archetype!(ArchBar, CompA);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///
+    ///     world.create::<ArchFoo>((CompA(1), CompB(10)));
+    ///     world.create::<ArchBar>((CompA(2),));
+    ///     world.create::<ArchBar>((CompA(3),));
+    ///
+    ///     assert_eq!(ecs_count!(world, |_: &CompA|), 3);
+    ///     assert_eq!(ecs_count!(world, |_: &CompA, _: Without<CompB>|), 2);
+    ///     assert_eq!(ecs_count!(world, |a: &CompA| a.0 % 2 == 1), 2);
+    /// }
+    /// ```
+    #[cfg(doc)]
+    #[macro_export]
+    macro_rules! ecs_count {
+        (...) => {...};
+    }
+
+    /// Checks whether any entity in the world matches the given query parameters.
+    ///
+    /// ```ignore
+    /// ecs_any!(world, |comp_a: &CompA, _: Without<CompB>, ...|)
+    /// ecs_any!(world, |comp_a: &CompA, ...| comp_a.0 > 1)
+    /// ```
+    ///
+    /// The query closure takes the same parameters as in [`ecs_iter!`]. If the closure has no
+    /// body, this checks whether any matching archetype is non-empty. If the closure has a body,
+    /// it must evaluate to a `bool`, and iteration stops at the first entity for which it returns
+    /// `true`. Evaluates to a `bool`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    ///     ecs_archetype!(ArchBar, CompA);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///     assert!(!ecs_any!(world, |_: &CompA|));
+    ///
+    ///     world.create::<ArchBar>((CompA(2),));
+    ///
+    ///     assert!(ecs_any!(world, |_: &CompA|));
+    ///     assert!(!ecs_any!(world, |_: &CompA, _: With<CompB>|));
+    ///     assert!(ecs_any!(world, |a: &CompA| a.0 == 2));
+    /// }
+    /// ```
+    #[cfg(doc)]
+    #[macro_export]
+    macro_rules! ecs_any {
+        (...) => {...};
+    }
+
+    /// Folds over all entities in the world that match the given query parameters.
+    ///
+    /// ```ignore
+    /// ecs_fold!(world, init, |acc, comp_a: &CompA, comp_b: &mut CompB, ...| { ... })
+    /// ```
+    ///
+    /// The first closure parameter is the accumulator, which starts as `init` and is replaced
+    /// by the result of the closure body for each matching entity. The accumulator may be
+    /// declared `mut` and given an explicit type, but otherwise takes the type of `init`. The
+    /// remaining parameters are the same as in [`ecs_iter!`]. Evaluates to the final accumulator.
+    ///
+    /// # Ordering
+    ///
+    /// There are no guarantees on order in this iteration, nor any guarantees that the order will
+    /// be the same as that of any other iteration operation. However, the order is guaranteed to
+    /// be deterministic on runs given identical ECS world state and prior operation order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    ///     ecs_archetype!(ArchBar, CompA);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///
+    ///     world.create::<ArchFoo>((CompA(1), CompB(10)));
+    ///     world.create::<ArchBar>((CompA(2),));
+    ///
+    ///     let sum = ecs_fold!(world, 0, |acc, a: &CompA| acc + a.0);
+    ///     assert_eq!(sum, 3);
+    ///
+    ///     let max = ecs_fold!(world, None, |acc: Option<u32>, b: &CompB| {
+    ///         Some(acc.map_or(b.0, |acc| acc.max(b.0)))
+    ///     });
+    ///     assert_eq!(max, Some(10));
+    /// }
+    /// ```
+    #[cfg(doc)]
+    #[macro_export]
+    macro_rules! ecs_fold {
+        (...) => {...};
+    }
+
+    /// Lists the archetypes in the world that the given query parameters would match.
+    ///
+    /// ```ignore
+    /// ecs_query_archetypes!(|comp_a: &CompA, _: Without<CompB>, ...|)
+    /// ```
+    ///
+    /// The query closure takes the same parameters as in [`ecs_iter!`], but has no world argument
+    /// and no body. Evaluates to a constant `&[(ArchetypeId, &str)]` of the matched archetypes'
+    /// IDs and names, in declaration order. Unlike other queries, matching no archetypes is not
+    /// an error, and gives an empty slice.
+    ///
+    /// # Expected Archetypes
+    ///
+    /// Any query closure may be preceded by an `#[expect_archetypes(...)]` attribute, listing the
+    /// archetypes it should match. Compilation fails if the query matches a different set, so a
+    /// query can't silently start or stop matching archetypes as the world changes.
+    ///
+    /// ```ignore
+    /// ecs_iter!(world, #[expect_archetypes(ArchFoo, ArchBar)] |comp_a: &CompA| { ... });
+    /// ```
+    ///
+    /// Each of these fails to compile:
+    ///
+    /// ```compile_fail
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    ///     ecs_archetype!(ArchBar, CompA);
+    /// }
+    ///
+    /// fn main() {
+    ///     // Error: there is no archetype `ArchBaz` in the world.
+    ///     ecs_query_archetypes!(#[expect_archetypes(ArchFoo, ArchBaz)] |_: &CompA, _: &CompB|);
+    /// }
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    ///     ecs_archetype!(ArchBar, CompA);
+    /// }
+    ///
+    /// fn main() {
+    ///     // Error: the query does not match the expected archetype `ArchBar`.
+    ///     ecs_query_archetypes!(#[expect_archetypes(ArchFoo, ArchBar)] |_: &CompA, _: &CompB|);
+    /// }
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    ///     ecs_archetype!(ArchBar, CompA);
+    /// }
+    ///
+    /// fn main() {
+    ///     // Error: the query matches the unexpected archetype `ArchFoo`.
+    ///     ecs_query_archetypes!(#[expect_archetypes()] |_: &CompA, _: &CompB|);
+    /// }
+    /// ```
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    ///     ecs_archetype!(ArchBar, CompA);
+    /// }
+    ///
+    /// fn main() {
+    ///     const MATCHED: &[(ArchetypeId, &str)] = ecs_query_archetypes!(|_: &CompA|);
+    ///     assert_eq!(MATCHED, &[(ArchFoo::ARCHETYPE_ID, "ArchFoo"), (ArchBar::ARCHETYPE_ID, "ArchBar")]);
+    ///
+    ///     let mut world = EcsWorld::default();
+    ///     world.create::<ArchBar>((CompA(1),));
+    ///
+    ///     ecs_iter!(world, #[expect_archetypes(ArchBar)] |a: &mut CompA, _: Without<CompB>| {
+    ///         a.0 += 1;
+    ///     });
+    /// }
+    /// ```
+    #[cfg(doc)]
+    #[macro_export]
+    macro_rules! ecs_query_archetypes {
+        (...) => {...};
+    }
+
+    /// Describes the component data the given query parameters would access in each archetype.
+    ///
+    /// ```ignore
+    /// ecs_query_access!(|comp_a: &CompA, comp_b: &mut CompB, ...|)
+    /// ```
+    ///
+    /// The query closure takes the same parameters as in [`ecs_query_archetypes!`], including an
+    /// optional `#[expect_archetypes(...)]` attribute. Evaluates to a constant
+    /// `&[ArchetypeAccess]` with an entry for each matched archetype, in declaration order.
+    /// Each entry lists the names of the components the query reads and writes in that
+    /// archetype, with `OneOf` and `dyn Trait` parameters resolved to the actual components.
+    /// This is the same metadata [`ecs_system`](crate::ecs_system) uses for its `ACCESS` constant.
+    ///
+    /// Like [`ecs_iter!`], a query that accesses a component mutably through more than one
+    /// parameter, or both mutably and immutably, fails to compile:
+    ///
+    /// ```compile_fail
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA);
+    /// }
+    ///
+    /// fn main() {
+    ///     // Error: query accesses CompA in ArchFoo mutably and through another parameter
+    ///     let access = ecs_query_access!(|a: &mut CompA, b: &CompA|);
+    /// }
+    /// ```
+    ///
+    /// See also: [`ArchetypeAccess`](crate::system::ArchetypeAccess)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    ///     ecs_archetype!(ArchBar, CompA);
+    /// }
+    ///
+    /// fn main() {
+    ///     let access = ecs_query_access!(|a: &mut CompA, b: &CompB|);
+    ///
+    ///     assert_eq!(access.len(), 1);
+    ///     assert_eq!(access[0].id, ArchFoo::ARCHETYPE_ID);
+    ///     assert_eq!(access[0].reads, ["CompB"]);
+    ///     assert_eq!(access[0].writes, ["CompA"]);
+    /// }
+    /// ```
+    #[cfg(doc)]
+    #[macro_export]
+    macro_rules! ecs_query_access {
+        (...) => {...};
+    }
+}
+
+/// A special parameter type for ECS query closures to match one of multiple components.
+///
 /// The `OneOf<A, B, C, ...>` pseudo-type argument to an ECS closure allows a query to match
 /// exactly one of the given component types. The component can be accessed as a `&T` (or
 /// `&mut T` for a `&mut OneOf<...>`), and is effectively duck-typed within the body of the
@@ -683,6 +1754,25 @@ pub struct OneOf {
     hidden: (),
 }
 
+/// A special parameter type for [`ecs_system`] functions, for data passed in by the caller.
+///
+/// An `ecs_system` parameter of type `Res<T>` isn't part of the system's query. Instead, it
+/// becomes a `&T` argument of the generated function, after the world. Use [`ResMut`] for a
+/// `&mut T` argument instead. Like [`OneOf`], this is a pseudo-type that only has meaning in
+/// an `ecs_system` signature, and it must be written as `Res<T>` rather than with a path.
+#[cfg(doc)]
+pub struct Res {
+    hidden: (),
+}
+
+/// A special parameter type for [`ecs_system`] functions, for data passed in by the caller.
+///
+/// The mutable version of [`Res`], which becomes a `&mut T` argument of the generated function.
+#[cfg(doc)]
+pub struct ResMut {
+    hidden: (),
+}
+
 /// A dispatch enum for resolving a dynamic [`EntityAny`](crate::entity::EntityAny) or
 /// [`EntityDirectAny`](crate::entity::EntityDirectAny) key to a specific Archetype disambiguation.
 /// Use `try_into` to perform the conversion (which returns `Result<SelectArchetype, EcsError>`).
@@ -806,6 +1896,204 @@ pub enum SelectBorrow {}
 
 #[cfg(not(doc))]
 pub use gecs_macros::{ecs_component_id, ecs_world};
+#[cfg(not(doc))]
+pub use gecs_macros::{ecs_find_archetype, ecs_iter_archetype};
+
+/// Derives a reusable query struct that can be used as a single query closure parameter.
+///
+/// ```ignore
+/// #[derive(EcsQuery)]
+/// struct Movement<'a> {
+///     pos: &'a mut Position,
+///     vel: &'a Velocity,
+///     entity: &'a EntityAny,
+/// }
+///
+/// ecs_iter!(world, |m: Movement| { ... });
+/// ```
+///
+/// Each field of the struct must be a `&` or `&mut` reference to something that could be a
+/// query closure parameter, such as a component, an entity handle, or a `dyn Trait`. The struct
+/// can then be taken by value in the closure of any query macro (except `ecs_query!` and
+/// `ecs_iter_pairs!`), where it behaves as if each of its fields had been written out as a
+/// separate parameter. It can also be mixed with regular parameters, or other query structs.
+///
+/// Deriving `EcsQuery` also creates a hidden macro with the same name as the struct, which is
+/// how the query macros find the struct's fields. This means that the struct must be brought
+/// into scope with `use` (or referred to by path) at the query site, rather than be aliased.
+/// Like any other query, the struct's component types are matched to archetypes by name.
+///
+/// # Examples
+///
+/// ```
+/// use gecs::prelude::*;
+///
+/// pub struct Position(pub u32);
+/// pub struct Velocity(pub u32);
+///
+/// ecs_world! {
+///     ecs_archetype!(ArchFoo, Position, Velocity);
+///     ecs_archetype!(ArchBar, Velocity, Position);
+/// }
+///
+/// #[derive(EcsQuery)]
+/// pub struct Movement<'a> {
+///     pos: &'a mut Position,
+///     vel: &'a Velocity,
+/// }
+///
+/// fn apply(m: Movement) {
+///     m.pos.0 += m.vel.0;
+/// }
+///
+/// fn main() {
+///     let mut world = EcsWorld::default();
+///
+///     let entity_a = world.create::<ArchFoo>((Position(1), Velocity(10)));
+///     let entity_b = world.create::<ArchBar>((Velocity(20), Position(2)));
+///
+///     ecs_iter!(world, |m: Movement| apply(m));
+///
+///     assert_eq!(ecs_find!(world, entity_a, |m: Movement| m.pos.0), Some(11));
+///     assert_eq!(ecs_find!(world, entity_b, |m: Movement| m.pos.0), Some(22));
+/// }
+/// ```
+pub use gecs_macros::EcsQuery;
+
+/// Turns a function into a named system that runs a query over an ECS world.
+///
+/// ```ignore
+/// #[ecs_system(world = EcsWorld)]
+/// fn name(comp_a: &CompA, comp_b: &mut CompB, res: Res<Resource>, ...) { ... }
+///
+/// name(&mut world, &resource); // Equivalent to ecs_iter!(world, |comp_a: &CompA, ...| { ... })
+/// ```
+///
+/// The function's parameters are query closure parameters, as in `ecs_iter!`, and its body is
+/// run once for each matching entity. The attribute rewrites the function to take the world as
+/// its first argument, followed by a `&T` or `&mut T` argument for each [`Res<T>`](Res) or
+/// [`ResMut<T>`](ResMut) parameter, in order. System functions can't be generic or return a
+/// value. A `return` in the body skips to the next entity, like it would in a closure.
+///
+/// The attribute also declares a module with the same name as the function, holding a
+/// `const ACCESS:` [`SystemAccess`](crate::system::SystemAccess) that describes which
+/// components the system reads and writes in each archetype it matches. This is resolved at
+/// compile-time with `ecs_query_access!`, and lets systems run in parallel in a
+/// [`Schedule`](crate::schedule::Schedule). The world argument can be a `&mut` reference to
+/// the world or the [`SystemWorld`](crate::schedule::SystemWorld) a schedule passes in.
+///
+/// The `world` argument names the world type, and is required. The generated code uses that
+/// world's `ecs_iter!` and `ecs_query_access!` macros, found in the module the path points
+/// into: `world = crate::worlds::MyWorld` uses `crate::worlds::ecs_iter!`, and a bare name like
+/// `world = EcsWorld` uses the macros visible from the module declaring the system.
+///
+/// # Examples
+///
+/// ```
+/// use gecs::prelude::*;
+///
+/// pub struct Position(pub f32);
+/// pub struct Velocity(pub f32);
+/// pub struct DeltaTime(pub f32);
+///
+/// ecs_world! {
+///     ecs_archetype!(ArchFoo, Position, Velocity);
+///     ecs_archetype!(ArchBar, Position);
+/// }
+///
+/// #[ecs_system(world = EcsWorld)]
+/// fn integrate(pos: &mut Position, vel: &Velocity, dt: Res<DeltaTime>) {
+///     pos.0 += vel.0 * dt.0;
+/// }
+///
+/// fn main() {
+///     let mut world = EcsWorld::default();
+///     let entity = world.create::<ArchFoo>((Position(0.0), Velocity(2.0)));
+///
+///     integrate(&mut world, &DeltaTime(0.5));
+///     assert_eq!(ecs_find!(world, entity, |p: &Position| p.0), Some(1.0));
+///
+///     assert_eq!(integrate::ACCESS.archetypes.len(), 1);
+///     assert_eq!(integrate::ACCESS.archetypes[0].reads, ["Velocity"]);
+///     assert_eq!(integrate::ACCESS.archetypes[0].writes, ["Position"]);
+/// }
+/// ```
+pub use gecs_macros::ecs_system;
+
+/// Declares an ECS world from a TOML file, exactly as if it were declared with `ecs_world!`.
+///
+/// ```ignore
+/// ecs_world_from_file!("path/to/world.toml");
+/// ```
+///
+/// The path is relative to the directory of the crate's `Cargo.toml`. The file is read at
+/// compile-time and converted to the equivalent `ecs_world!` body, so the generated world,
+/// archetypes, and query macros are the same. The crate is rebuilt when the file changes.
+/// Requires the `world_file` crate feature.
+///
+/// The file may have a top-level `name` and `derive` (as for `ecs_name!`, so `derive` requires
+/// a `name`), and an ordered list of `[[archetype]]` tables with the following keys:
+///
+/// - `name`: The name of the archetype. Required.
+/// - `template`: If `true`, this is declared with `ecs_template!` instead, and can't use the
+///   `id`, `cfg`, or `derive` keys.
+/// - `id`: The archetype's ID, as with `#[archetype_id(N)]`.
+/// - `cfg`: A `#[cfg]` predicate string, or an array of them.
+/// - `derive`: An array of traits to derive, as with `#[derive(...)]`.
+/// - `bases`: An array of archetypes or templates to inherit components from.
+/// - `components`: An array of components. Each is either a name string, or a table with a
+///   `name` and optional `id`, `cfg`, and `implements` (an array of trait names) keys.
+///
+/// ```toml
+/// name = "EcsWorld"
+///
+/// [[archetype]]
+/// name = "Movement"
+/// template = true
+/// components = ["Position", "Velocity"]
+///
+/// [[archetype]]
+/// name = "ArchShip"
+/// bases = ["Movement"]
+/// components = ["Health", { name = "Debug", cfg = 'feature = "debug"' }]
+///
+/// [[archetype]]
+/// name = "ArchRock"
+/// id = 5
+/// components = ["Position", { name = "Value<u32>", id = 4, implements = ["Describe"] }]
+/// ```
+///
+/// # Compile Errors
+///
+/// Problems with the file, such as an unknown key, an `id` outside of `0..=255`, or a `derive`
+/// without a `name`, are compile errors that name the file and where in it the problem is:
+///
+/// ```compile_fail
+/// # use gecs::prelude::*;
+/// # pub struct Position(pub u32);
+/// // Error: in world file `.../tests/worlds/invalid_unknown_key.toml`
+/// //        at archetype 0 (`ArchShip`): unknown key `component`
+/// ecs_world_from_file!("tests/worlds/invalid_unknown_key.toml");
+/// # fn main() {}
+/// ```
+///
+/// ```compile_fail
+/// # use gecs::prelude::*;
+/// # pub struct Position(pub u32);
+/// // Error: ... at archetype 0 (`ArchShip`): `id` must be between 0 and 255
+/// ecs_world_from_file!("tests/worlds/invalid_archetype_id.toml");
+/// # fn main() {}
+/// ```
+///
+/// ```compile_fail
+/// # use gecs::prelude::*;
+/// # pub struct Position(pub u32);
+/// // Error: ... at the top level: `derive` requires a `name`
+/// ecs_world_from_file!("tests/worlds/invalid_derive_without_name.toml");
+/// # fn main() {}
+/// ```
+#[cfg(feature = "world_file")]
+pub use gecs_macros::ecs_world_from_file;
 
 /// You can `use gecs::prelude::*` to import common macros, traits, and types.
 #[rustfmt::skip]
@@ -813,19 +2101,27 @@ pub mod prelude {
     use super::*;
 
     pub use gecs_macros::{ecs_component_id, ecs_world};
+    pub use gecs_macros::{ecs_find_archetype, ecs_iter_archetype};
+    pub use gecs_macros::EcsQuery;
+    pub use gecs_macros::ecs_system;
+    #[cfg(feature = "world_file")]
+    pub use gecs_macros::ecs_world_from_file;
 
-    pub use error::EcsError;
+    pub use error::{EcsError, FindError};
 
     pub use entity::{ArchetypeId, Entity, EntityAny, EntityDirect, EntityDirectAny};
 
-    pub use iter::{EcsStepDestroy, EcsStep};
+    pub use iter::{EcsStepDestroy, EcsStep, IterCursor};
+
+    pub use query::{Query, QueryTerm};
 
     pub use traits::{EntityKey, EntityKeyTyped, EntityKeySelectable};
     pub use traits::{WorldCanResolve, ArchetypeCanResolve, StorageCanResolve};
 
-    pub use traits::{World, WorldHas};
+    pub use traits::{World, WorldHas, WorldHasComponent};
     pub use traits::{Archetype, ArchetypeHas};
     pub use traits::{Components, View, ViewMut, Borrow};
+    pub use traits::{ComponentVisitor, ComponentVisitorMut};
 }
 
 #[doc(hidden)]
@@ -837,28 +2133,50 @@ pub mod __internal {
     
     pub use gecs_macros::{__expand_ecs_find, __impl_ecs_find};
     pub use gecs_macros::{__expand_ecs_find_borrow, __impl_ecs_find_borrow};
+    pub use gecs_macros::{__expand_ecs_try_find, __impl_ecs_try_find};
+    pub use gecs_macros::{__expand_ecs_try_find_borrow, __impl_ecs_try_find_borrow};
 
     pub use gecs_macros::{__expand_ecs_iter, __impl_ecs_iter};
     pub use gecs_macros::{__expand_ecs_iter_borrow, __impl_ecs_iter_borrow};
     pub use gecs_macros::{__expand_ecs_iter_destroy, __impl_ecs_iter_destroy};
 
-    pub use error::EcsError;
+    pub use gecs_macros::{__expand_ecs_query, __impl_ecs_query};
+    pub use gecs_macros::{__expand_ecs_count, __impl_ecs_count};
+    pub use gecs_macros::{__expand_ecs_any, __impl_ecs_any};
+    pub use gecs_macros::{__expand_ecs_fold, __impl_ecs_fold};
+    pub use gecs_macros::{__expand_ecs_iter_pairs, __impl_ecs_iter_pairs};
+    pub use gecs_macros::{__expand_ecs_find_each, __impl_ecs_find_each};
+    pub use gecs_macros::{__expand_ecs_iter_budget, __impl_ecs_iter_budget};
+    pub use gecs_macros::{__expand_ecs_iter_sorted, __impl_ecs_iter_sorted};
+    pub use gecs_macros::{__expand_ecs_query_archetypes, __impl_ecs_query_archetypes};
+    pub use gecs_macros::{__expand_ecs_query_access, __impl_ecs_query_access};
+
+    pub use gecs_macros::__resolve_ecs_query_struct;
+
+    pub use error::{EcsError, FindError};
 
     pub use entity::__internal::*;
+    pub use iter::__internal::*;
 
     pub use version::{ArchetypeVersion, SlotVersion};
+    pub use util::{DebugBorrowed, DebugWith};
+    pub use query::ArchetypeSet;
+    pub use schedule::ArchetypeSource;
 
     pub use archetype::slices::*;
     pub use archetype::storage::*;
     pub use archetype::components::*;
     pub use archetype::view::*;
 
-    pub use iter::{EcsStepDestroy, EcsStep};
+    pub use iter::{EcsStepDestroy, EcsStep, IterCursor};
+
+    pub use stats::{WorldMemoryStats, ArchetypeMemoryStats, ComponentMemoryStats};
 
     pub use traits::EntityKey;
     pub use traits::{WorldCanResolve, ArchetypeCanResolve, StorageCanResolve};
 
-    pub use traits::{World, WorldHas};
+    pub use traits::{World, WorldHas, WorldHasComponent};
     pub use traits::{Archetype, ArchetypeHas};
     pub use traits::{Components, View, ViewMut, Borrow};
+    pub use traits::{ComponentVisitor, ComponentVisitorMut};
 }
@@ -0,0 +1,272 @@
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use crate::entity::{ArchetypeId, EntityAny};
+use crate::traits::{World, WorldHasComponent};
+
+/// A typed query over the entities in a world `W`, used with [`World::query`].
+///
+/// This is implemented for tuples of up to 12 [`QueryTerm`]s, such as `(&CompA, &mut CompB)`
+/// or `(EntityAny, &CompA)`. An archetype matches a query if it has every component the query
+/// accesses. Unlike the query macros, a typed query can be named as a type and used through a
+/// generic world bound, such as [`WorldHasComponent`].
+///
+/// Archetypes are matched at compile-time, from what each world knows about its components.
+/// Queries that access the same component mutably more than once, as in `(&mut CompA, &CompA)`,
+/// fail to compile when used.
+pub trait Query<W: World> {
+    /// The data yielded by this query for each matched entity.
+    type Item<'a>;
+
+    #[doc(hidden)]
+    type Fetch: Copy;
+
+    #[doc(hidden)]
+    const ARCHETYPES: ArchetypeSet;
+
+    #[doc(hidden)]
+    const CHECK_ACCESS: ();
+
+    #[doc(hidden)]
+    fn resolve_fetch(world: &mut W, archetype_id: ArchetypeId) -> Option<Self::Fetch>;
+
+    #[doc(hidden)]
+    unsafe fn resolve_get<'a>(fetch: Self::Fetch, index: usize) -> Self::Item<'a>;
+}
+
+/// A single element of a typed [`Query`]: `&C`, `&mut C`, or [`EntityAny`].
+pub trait QueryTerm<W: World> {
+    /// The data yielded by this term for each matched entity.
+    type Item<'a>;
+
+    #[doc(hidden)]
+    type Fetch: Copy;
+
+    #[doc(hidden)]
+    const ACCESS: Option<(usize, bool)>;
+
+    #[doc(hidden)]
+    const ARCHETYPES: ArchetypeSet;
+
+    #[doc(hidden)]
+    fn resolve_fetch(world: &mut W, archetype_id: ArchetypeId) -> Option<Self::Fetch>;
+
+    #[doc(hidden)]
+    unsafe fn resolve_get<'a>(fetch: Self::Fetch, index: usize) -> Self::Item<'a>;
+}
+
+/// An iterator over the entities matching a typed [`Query`]. See [`World::query`].
+pub struct QueryIter<'a, W: World, Q: Query<W>> {
+    world: NonNull<W>,
+    next_archetype: usize,
+    fetch: Option<Q::Fetch>,
+    index: usize,
+    len: usize,
+    _world: PhantomData<&'a mut W>,
+}
+
+/// A fixed set of archetype IDs, used to match typed queries to archetypes at compile-time.
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub struct ArchetypeSet([u64; 4]);
+
+impl ArchetypeSet {
+    pub const ALL: Self = Self([u64::MAX; 4]);
+
+    pub const fn new(archetype_ids: &[ArchetypeId]) -> Self {
+        let mut bits = [0; 4];
+        let mut idx = 0;
+        while idx < archetype_ids.len() {
+            let id = archetype_ids[idx] as usize;
+            bits[id / 64] |= 1 << (id % 64);
+            idx += 1;
+        }
+        Self(bits)
+    }
+
+    pub const fn intersect(self, other: Self) -> Self {
+        let [a0, a1, a2, a3] = self.0;
+        let [b0, b1, b2, b3] = other.0;
+        Self([a0 & b0, a1 & b1, a2 & b2, a3 & b3])
+    }
+
+    #[inline(always)]
+    pub const fn contains(&self, archetype_id: ArchetypeId) -> bool {
+        let id = archetype_id as usize;
+        (self.0[id / 64] & (1 << (id % 64))) != 0
+    }
+}
+
+impl<W: WorldHasComponent<C>, C: 'static> QueryTerm<W> for &C {
+    type Item<'a> = &'a C;
+    type Fetch = NonNull<C>;
+
+    const ACCESS: Option<(usize, bool)> = Some((W::QUERY_COMPONENT_INDEX, false));
+    const ARCHETYPES: ArchetypeSet = W::QUERY_ARCHETYPES;
+
+    #[inline(always)]
+    fn resolve_fetch(world: &mut W, archetype_id: ArchetypeId) -> Option<Self::Fetch> {
+        world.resolve_query_column(archetype_id)
+    }
+
+    #[inline(always)]
+    unsafe fn resolve_get<'a>(fetch: Self::Fetch, index: usize) -> Self::Item<'a> {
+        unsafe {
+            // SAFETY: The caller guarantees that the index is in bounds for the column
+            fetch.add(index).as_ref()
+        }
+    }
+}
+
+impl<W: WorldHasComponent<C>, C: 'static> QueryTerm<W> for &mut C {
+    type Item<'a> = &'a mut C;
+    type Fetch = NonNull<C>;
+
+    const ACCESS: Option<(usize, bool)> = Some((W::QUERY_COMPONENT_INDEX, true));
+    const ARCHETYPES: ArchetypeSet = W::QUERY_ARCHETYPES;
+
+    #[inline(always)]
+    fn resolve_fetch(world: &mut W, archetype_id: ArchetypeId) -> Option<Self::Fetch> {
+        world.resolve_query_column(archetype_id)
+    }
+
+    #[inline(always)]
+    unsafe fn resolve_get<'a>(fetch: Self::Fetch, index: usize) -> Self::Item<'a> {
+        unsafe {
+            // SAFETY: The caller guarantees that the index is in bounds for the column, and
+            // that no other reference to this element exists (see `check_access`)
+            fetch.add(index).as_mut()
+        }
+    }
+}
+
+impl<W: World> QueryTerm<W> for EntityAny {
+    type Item<'a> = EntityAny;
+    type Fetch = NonNull<EntityAny>;
+
+    const ACCESS: Option<(usize, bool)> = None;
+    const ARCHETYPES: ArchetypeSet = ArchetypeSet::ALL;
+
+    #[inline(always)]
+    fn resolve_fetch(world: &mut W, archetype_id: ArchetypeId) -> Option<Self::Fetch> {
+        Some(NonNull::from(world.archetype_dyn(archetype_id)?.entities()).cast())
+    }
+
+    #[inline(always)]
+    unsafe fn resolve_get<'a>(fetch: Self::Fetch, index: usize) -> Self::Item<'a> {
+        unsafe {
+            // SAFETY: The caller guarantees that the index is in bounds for the entities
+            fetch.add(index).read()
+        }
+    }
+}
+
+macro_rules! impl_query_tuple {
+    ($($term:ident),+) => {
+        #[allow(non_snake_case)]
+        impl<W: World, $($term: QueryTerm<W>),+> Query<W> for ($($term,)+) {
+            type Item<'a> = ($($term::Item<'a>,)+);
+            type Fetch = ($($term::Fetch,)+);
+
+            const ARCHETYPES: ArchetypeSet = ArchetypeSet::ALL$(.intersect($term::ARCHETYPES))+;
+            const CHECK_ACCESS: () = check_access(&[$($term::ACCESS),+]);
+
+            #[inline(always)]
+            fn resolve_fetch(world: &mut W, archetype_id: ArchetypeId) -> Option<Self::Fetch> {
+                Some(($($term::resolve_fetch(world, archetype_id)?,)+))
+            }
+
+            #[inline(always)]
+            unsafe fn resolve_get<'a>(fetch: Self::Fetch, index: usize) -> Self::Item<'a> {
+                let ($($term,)+) = fetch;
+                unsafe { ($($term::resolve_get($term, index),)+) }
+            }
+        }
+    };
+}
+
+impl_query_tuple!(T0);
+impl_query_tuple!(T0, T1);
+impl_query_tuple!(T0, T1, T2);
+impl_query_tuple!(T0, T1, T2, T3);
+impl_query_tuple!(T0, T1, T2, T3, T4);
+impl_query_tuple!(T0, T1, T2, T3, T4, T5);
+impl_query_tuple!(T0, T1, T2, T3, T4, T5, T6);
+impl_query_tuple!(T0, T1, T2, T3, T4, T5, T6, T7);
+impl_query_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8);
+impl_query_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_query_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_query_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+
+impl<'a, W: World, Q: Query<W>> QueryIter<'a, W, Q> {
+    #[inline]
+    pub(crate) fn new(world: &'a mut W) -> Self {
+        let () = Q::CHECK_ACCESS;
+
+        Self {
+            world: NonNull::from(world),
+            next_archetype: 0,
+            fetch: None,
+            index: 0,
+            len: 0,
+            _world: PhantomData,
+        }
+    }
+}
+
+impl<'a, W: World, Q: Query<W>> Iterator for QueryIter<'a, W, Q> {
+    type Item = Q::Item<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(fetch) = self.fetch
+                && self.index < self.len
+            {
+                let index = self.index;
+                self.index += 1;
+
+                return Some(unsafe {
+                    // SAFETY: We checked the index against the archetype's length, and each
+                    // index is only visited once, so mutable items are never aliased
+                    Q::resolve_get(fetch, index)
+                });
+            }
+
+            // Move on to the next archetype (if any) that the query matched at compile-time
+            let (archetype_id, _) = W::ARCHETYPES.get(self.next_archetype)?;
+            self.next_archetype += 1;
+            if Q::ARCHETYPES.contains(*archetype_id) == false {
+                continue;
+            }
+
+            let world = unsafe {
+                // SAFETY: We hold the world's exclusive borrow for 'a, and items we've already
+                // yielded point into the columns of other archetypes, not the world itself
+                self.world.as_mut()
+            };
+
+            self.fetch = Q::resolve_fetch(world, *archetype_id);
+            self.index = 0;
+            self.len = world.archetype_dyn(*archetype_id).map_or(0, |archetype| archetype.len());
+        }
+    }
+}
+
+const fn check_access(access: &[Option<(usize, bool)>]) {
+    let mut first = 0;
+    while first < access.len() {
+        let mut second = first + 1;
+        while second < access.len() {
+            if let (Some((first, first_mut)), Some((second, second_mut))) =
+                (access[first], access[second])
+                && first == second
+                && (first_mut || second_mut)
+            {
+                panic!("query accesses the same component mutably more than once");
+            }
+            second += 1;
+        }
+        first += 1;
+    }
+}
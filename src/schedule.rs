@@ -0,0 +1,651 @@
+use std::collections::BTreeSet;
+use std::marker::PhantomData;
+use std::num::NonZeroUsize;
+use std::ptr::NonNull;
+use std::thread;
+
+use crate::entity::Entity;
+use crate::system::{ArchetypeAccess, SystemAccess};
+use crate::traits::{Archetype, ArchetypeHas, World};
+use crate::version::ArchetypeVersion;
+
+/// A world that can be split into separately accessible archetypes for parallel systems.
+///
+/// This is implemented automatically for every world declared with `ecs_world!`.
+pub trait WorldParts: World {
+    /// The split-up world, with one [`ArchetypePart`] field for each archetype.
+    type Parts<'a>
+    where
+        Self: 'a;
+
+    #[doc(hidden)]
+    unsafe fn split<'a>(world: NonNull<Self>, access: &SystemAccess) -> Self::Parts<'a>;
+}
+
+/// A world whose component data can be read from more than one thread at once.
+///
+/// This is implemented automatically for every world declared with `ecs_world!` whose
+/// components are all `Sync`. Along with `Send`, it's needed to run a [`Schedule`], which may
+/// run read-only systems on the same archetype in parallel.
+///
+/// # Safety
+///
+/// Every component stored in the world must be `Sync`.
+pub unsafe trait WorldSync: WorldParts {}
+
+/// A world handle given to a system, limited to the archetypes the system may access.
+///
+/// A `SystemWorld` is created by a [`Schedule`] for each system it runs, or with
+/// [`SystemWorld::new`] for running a system directly with exclusive access to a world.
+pub struct SystemWorld<'a, W: WorldParts> {
+    world: NonNull<W>,
+    granted: Option<&'a SystemAccess>,
+    _world: PhantomData<&'a mut W>,
+}
+
+/// A single archetype in a split-up world. See [`SystemWorld::parts`].
+///
+/// A part never dereferences to its archetype, because the same archetype may be shared with
+/// other read-only systems running on other threads. Use [`ArchetypePart::read`] for a
+/// restricted read-only view, or [`ArchetypePart::get_mut`] if the access the world was split
+/// for writes to the archetype (in which case no other system can be using it).
+///
+/// A read-only part can't reach the archetype's borrow-checked accessors at all:
+///
+/// ```compile_fail
+/// use gecs::prelude::*;
+/// use gecs::schedule::SystemWorld;
+///
+/// pub struct Position(pub f32);
+///
+/// ecs_world! {
+///     ecs_archetype!(ArchShip, Position);
+/// }
+///
+/// #[ecs_system(world = EcsWorld)]
+/// fn inspect(_: &Position) {}
+///
+/// fn main() {
+///     let mut world = EcsWorld::default();
+///     let mut world = SystemWorld::new(&mut world);
+///     let parts = world.parts(&inspect::ACCESS);
+///     let _ = parts.arch_ship.read().borrow_slice_mut::<Position>(); // Not available
+/// }
+/// ```
+pub struct ArchetypePart<'a, A: Archetype> {
+    archetype: NonNull<A>,
+    can_read: bool,
+    can_write: bool,
+    _world: PhantomData<&'a mut A>,
+}
+
+/// A read-only view of an archetype in a split-up world. See [`ArchetypePart::read`].
+///
+/// This only reads component data directly, without touching the archetype's runtime borrow
+/// state, so that any number of read-only systems can view the archetype from different
+/// threads at the same time.
+pub struct ArchetypeRead<'a, A: Archetype> {
+    archetype: &'a A,
+}
+
+/// An identifier for a system added to a [`Schedule`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SystemId(usize);
+
+/// A pair of systems in a [`Schedule`] with conflicting data access but no explicit ordering.
+///
+/// Ambiguous systems still run one after the other, in the order they were added, but that
+/// order is likely accidental. See [`Schedule::ambiguities`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScheduleAmbiguity {
+    /// The system that was added first.
+    pub first: SystemId,
+    /// The system that was added second.
+    pub second: SystemId,
+    /// The name of an archetype both systems access.
+    pub archetype: &'static str,
+    /// The name of a component in that archetype that at least one of the systems writes.
+    pub component: &'static str,
+}
+
+/// A collection of systems to run on a world, in parallel where their data access allows.
+///
+/// Each system is added with its [`SystemAccess`] (such as the `ACCESS` constant generated by
+/// `ecs_system`) and a closure to run it with a [`SystemWorld`]. Because every system's access
+/// is resolved per archetype at compile-time, two systems only conflict if they access the
+/// same archetype. For example, two systems that each write `Position` can run at the same
+/// time if they match different archetypes.
+///
+/// Systems that access the same archetype never run at the same time, unless both only read
+/// from it. If neither has been explicitly ordered before the other with [`Schedule::order`],
+/// they run in the order they were added. Any such pair where at least one system writes data
+/// the other accesses is reported by [`Schedule::ambiguities`], since its order likely matters.
+///
+/// # Examples
+///
+/// ```
+/// use gecs::prelude::*;
+/// use gecs::schedule::{Schedule, SystemWorld};
+///
+/// pub struct Position(pub f32);
+/// pub struct Velocity(pub f32);
+/// pub struct Health(pub f32);
+///
+/// ecs_world! {
+///     ecs_archetype!(ArchShip, Position, Velocity);
+///     ecs_archetype!(ArchStation, Health);
+/// }
+///
+/// #[ecs_system(world = EcsWorld)]
+/// fn integrate(pos: &mut Position, vel: &Velocity) {
+///     pos.0 += vel.0;
+/// }
+///
+/// #[ecs_system(world = EcsWorld)]
+/// fn repair(health: &mut Health) {
+///     health.0 = 100.0;
+/// }
+///
+/// fn main() {
+///     let mut world = EcsWorld::default();
+///     let ship = world.create::<ArchShip>((Position(0.0), Velocity(1.0)));
+///     let station = world.create::<ArchStation>((Health(50.0),));
+///
+///     // These systems share no archetypes, so they can run in parallel
+///     let mut schedule = Schedule::new();
+///     schedule.add(integrate::ACCESS, |world| integrate(world));
+///     schedule.add(repair::ACCESS, |world| repair(world));
+///     assert!(schedule.ambiguities().is_empty());
+///
+///     schedule.run(&mut world);
+///     assert_eq!(ecs_find!(world, ship, |p: &Position| p.0), Some(1.0));
+///     assert_eq!(ecs_find!(world, station, |h: &Health| h.0), Some(100.0));
+/// }
+/// ```
+pub struct Schedule<'s, W: WorldParts> {
+    systems: Vec<ScheduleSystem<'s, W>>,
+    orderings: Vec<(usize, usize)>,
+    threads: usize,
+}
+
+type SystemRun<'s, W> = Box<dyn FnMut(SystemWorld<'_, W>) + Send + 's>;
+
+struct ScheduleSystem<'s, W: WorldParts> {
+    access: SystemAccess,
+    run: SystemRun<'s, W>,
+}
+
+impl<'a, W: WorldParts> SystemWorld<'a, W> {
+    /// Creates a handle with exclusive access to the whole world.
+    #[inline]
+    pub fn new(world: &'a mut W) -> Self {
+        Self {
+            world: NonNull::from(world),
+            granted: None,
+            _world: PhantomData,
+        }
+    }
+
+    /// Splits the world into its archetypes, allowing access to those in the given access.
+    ///
+    /// This is what systems generated by `ecs_system` use to run their query.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this handle was created by a [`Schedule`] for a system, and the given access
+    /// isn't within the access that system was added with.
+    #[inline]
+    pub fn parts(&mut self, access: &SystemAccess) -> W::Parts<'_> {
+        if let Some(granted) = self.granted
+            && is_access_within(access, granted) == false
+        {
+            panic!(
+                "system `{}` accessed data outside of the access it was scheduled with",
+                access.name,
+            );
+        }
+
+        unsafe {
+            // SAFETY: We have exclusive access to the world, or the schedule guarantees that
+            // no other running system can access any archetype in our granted access
+            W::split(self.world, access)
+        }
+    }
+}
+
+impl<'a, W: WorldParts> From<&'a mut W> for SystemWorld<'a, W> {
+    #[inline]
+    fn from(world: &'a mut W) -> Self {
+        Self::new(world)
+    }
+}
+
+// SAFETY: A SystemWorld only grants access to what its schedule (or exclusive borrow) allows.
+// Archetypes it writes to are used by no other running system, so their data only needs to be
+// Send. Archetypes it only reads may be shared with other threads, but only through parts that
+// read the (Sync) component data directly and never touch the archetype's borrow state.
+unsafe impl<W: WorldSync + Send> Send for SystemWorld<'_, W> {}
+
+impl<'a, A: Archetype> ArchetypePart<'a, A> {
+    #[doc(hidden)]
+    #[inline(always)]
+    pub unsafe fn new(archetype: NonNull<A>, access: Option<&ArchetypeAccess>) -> Self {
+        Self {
+            archetype,
+            can_read: access.is_some(),
+            can_write: access.is_some_and(|access| access.writes.is_empty() == false),
+            _world: PhantomData,
+        }
+    }
+
+    /// Returns a read-only view of the archetype.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the archetype is outside of the access the world was split for.
+    #[inline(always)]
+    pub fn read(&self) -> ArchetypeRead<'_, A> {
+        if self.can_read == false {
+            panic_not_allowed::<A>();
+        }
+
+        ArchetypeRead {
+            archetype: unsafe {
+                // SAFETY: The world was split for an access that includes this archetype, and
+                // the view only offers reads that don't touch any runtime borrow state
+                self.archetype.as_ref()
+            },
+        }
+    }
+
+    /// Returns the archetype for exclusive access.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the access the world was split for doesn't write to the archetype.
+    #[inline(always)]
+    pub fn get_mut(&mut self) -> &mut A {
+        if self.can_write == false {
+            panic_not_writable::<A>(self.can_read);
+        }
+
+        unsafe {
+            // SAFETY: The world was split for an access that writes to this archetype, so no
+            // other system that could be running at the same time accesses it
+            self.archetype.as_mut()
+        }
+    }
+}
+
+impl<'a, A: Archetype> ArchetypeRead<'a, A> {
+    /// Returns the number of entities in the archetype.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.archetype.len()
+    }
+
+    /// Returns `true` if the archetype contains no entities.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.archetype.is_empty()
+    }
+
+    /// Returns the generational version of the archetype. Intended for internal use.
+    #[inline(always)]
+    pub fn version(&self) -> ArchetypeVersion {
+        self.archetype.version()
+    }
+
+    /// Returns a slice of every entity in the archetype, in the same order as its components.
+    #[inline(always)]
+    pub fn entities(&self) -> &'a [Entity<A>] {
+        self.archetype.entities()
+    }
+
+    /// Returns a slice of the given component for every entity in the archetype.
+    #[inline(always)]
+    pub fn get_slice<C>(&self) -> &'a [C]
+    where
+        A: ArchetypeHas<C>,
+    {
+        unsafe {
+            // SAFETY: Nothing can mutably access the archetype while this view exists, either
+            // because the view came from a read-only part (so no running system writes to the
+            // archetype) or because it came from an exclusive borrow of the archetype
+            self.archetype.resolve_get_slice_unchecked()
+        }
+    }
+}
+
+/// Resolves an archetype field of either a world or a split-up world in generated queries.
+#[doc(hidden)]
+pub trait ArchetypeSource<A: Archetype> {
+    fn archetype_read(&mut self) -> ArchetypeRead<'_, A>;
+    fn archetype_mut(&mut self) -> &mut A;
+}
+
+impl<A: Archetype> ArchetypeSource<A> for A {
+    #[inline(always)]
+    fn archetype_read(&mut self) -> ArchetypeRead<'_, A> {
+        ArchetypeRead { archetype: self }
+    }
+
+    #[inline(always)]
+    fn archetype_mut(&mut self) -> &mut A {
+        self
+    }
+}
+
+impl<A: Archetype> ArchetypeSource<A> for ArchetypePart<'_, A> {
+    #[inline(always)]
+    fn archetype_read(&mut self) -> ArchetypeRead<'_, A> {
+        self.read()
+    }
+
+    #[inline(always)]
+    fn archetype_mut(&mut self) -> &mut A {
+        self.get_mut()
+    }
+}
+
+impl<'s, W: WorldParts> Schedule<'s, W> {
+    /// Creates an empty schedule that runs systems on up to as many threads as are available.
+    pub fn new() -> Self {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        Self::with_threads(threads)
+    }
+
+    /// Creates an empty schedule that runs systems on up to the given number of threads.
+    ///
+    /// With a single thread, no threads are spawned and every system runs on the caller's.
+    pub fn with_threads(threads: usize) -> Self {
+        Self {
+            systems: Vec::new(),
+            orderings: Vec::new(),
+            threads: threads.max(1),
+        }
+    }
+
+    /// Adds a system with the given access, to be run by the given closure.
+    ///
+    /// The closure should run the system with the [`SystemWorld`] it's given, as in
+    /// `|world| integrate(world)`. Any data the system needs besides the world (such as
+    /// `Res<T>` parameters) can be captured by the closure.
+    pub fn add(
+        &mut self,
+        access: SystemAccess,
+        run: impl FnMut(SystemWorld<'_, W>) + Send + 's,
+    ) -> SystemId {
+        self.systems.push(ScheduleSystem {
+            access,
+            run: Box::new(run),
+        });
+        SystemId(self.systems.len() - 1)
+    }
+
+    /// Requires that the system `before` always runs before the system `after`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either system isn't in this schedule, or if the ordering would contradict
+    /// the orderings already added (directly or transitively).
+    pub fn order(&mut self, before: SystemId, after: SystemId) {
+        assert!(before.0 < self.systems.len(), "system not in this schedule");
+        assert!(after.0 < self.systems.len(), "system not in this schedule");
+
+        if before == after || self.is_ordered(after.0, before.0) {
+            panic!(
+                "ordering `{}` before `{}` would create a cycle",
+                self.systems[before.0].access.name,
+                self.systems[after.0].access.name,
+            );
+        }
+
+        self.orderings.push((before.0, after.0));
+    }
+
+    /// Returns the name of the given system, as given by its access.
+    pub fn name(&self, system: SystemId) -> &'static str {
+        self.systems[system.0].access.name
+    }
+
+    /// Finds every pair of systems that has conflicting data access but no explicit ordering.
+    ///
+    /// Two systems conflict if they access the same archetype, and one of them writes to a
+    /// component in that archetype that the other accesses. Pairs are listed in the order the
+    /// systems were added, with one entry per pair.
+    pub fn ambiguities(&self) -> Vec<ScheduleAmbiguity> {
+        let mut result = Vec::new();
+
+        for first in 0..self.systems.len() {
+            for second in first + 1..self.systems.len() {
+                if self.is_ordered(first, second) || self.is_ordered(second, first) {
+                    continue;
+                }
+
+                let first_access = &self.systems[first].access;
+                let second_access = &self.systems[second].access;
+                if let Some((archetype, component)) = find_conflict(first_access, second_access) {
+                    result.push(ScheduleAmbiguity {
+                        first: SystemId(first),
+                        second: SystemId(second),
+                        archetype,
+                        component,
+                    });
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Runs every system in this schedule once.
+    ///
+    /// Systems run in stages. Each stage runs, in parallel, every remaining system whose
+    /// predecessors have all finished. A system's predecessors are the systems explicitly
+    /// ordered before it, and any earlier system that shares an archetype with it where
+    /// either system writes to that archetype.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any system panics, after the other systems in its stage have finished.
+    pub fn run(&mut self, world: &mut W)
+    where
+        W: WorldSync + Send,
+    {
+        let stages = self.build_stages();
+        let world = NonNull::from(world);
+
+        for stage in stages {
+            // Split the stage's systems (which only share read-only archetypes) into batches
+            let threads = self.threads.min(stage.len());
+            let mut batches = (0..threads).map(|_| Vec::new()).collect::<Vec<_>>();
+            let systems = self
+                .systems
+                .iter_mut()
+                .enumerate()
+                .filter(|(idx, _)| stage.contains(idx));
+            for (batch_idx, (_, system)) in systems.enumerate() {
+                let system_world = SystemWorld {
+                    world,
+                    granted: Some(&system.access),
+                    _world: PhantomData,
+                };
+                batches[batch_idx % threads].push((&mut system.run, system_world));
+            }
+
+            let mut batches = batches.into_iter();
+            let first = batches.next().unwrap_or_default();
+            thread::scope(|scope| {
+                for batch in batches {
+                    scope.spawn(move || run_batch(batch));
+                }
+                run_batch(first); // Use this thread too
+            });
+        }
+    }
+
+    /// Returns true if `first` is explicitly ordered before `second`, directly or transitively.
+    fn is_ordered(&self, first: usize, second: usize) -> bool {
+        let mut visited = vec![false; self.systems.len()];
+        let mut pending = vec![first];
+
+        while let Some(current) = pending.pop() {
+            for &(before, after) in self.orderings.iter() {
+                if before == current && visited[after] == false {
+                    if after == second {
+                        return true;
+                    }
+                    visited[after] = true;
+                    pending.push(after);
+                }
+            }
+        }
+
+        false
+    }
+
+    fn build_stages(&self) -> Vec<Vec<usize>> {
+        let count = self.systems.len();
+
+        // Find a total order that respects the explicit orderings, preferring insertion order
+        let mut in_degree = vec![0; count];
+        for &(_, after) in self.orderings.iter() {
+            in_degree[after] += 1;
+        }
+
+        let mut ready = (0..count).filter(|&idx| in_degree[idx] == 0).collect::<BTreeSet<_>>();
+        let mut rank = vec![0; count];
+        let mut next_rank = 0;
+        while let Some(current) = ready.pop_first() {
+            rank[current] = next_rank;
+            next_rank += 1;
+
+            for &(before, after) in self.orderings.iter() {
+                if before == current {
+                    in_degree[after] -= 1;
+                    if in_degree[after] == 0 {
+                        ready.insert(after);
+                    }
+                }
+            }
+        }
+
+        // Systems that share an archetype that either writes to can't overlap, so we follow
+        // the total order there
+        let mut predecessors = vec![Vec::new(); count];
+        for &(before, after) in self.orderings.iter() {
+            predecessors[after].push(before);
+        }
+        for first in 0..count {
+            for second in first + 1..count {
+                if needs_exclusive(&self.systems[first].access, &self.systems[second].access) {
+                    match rank[first] < rank[second] {
+                        true => predecessors[second].push(first),
+                        false => predecessors[first].push(second),
+                    }
+                }
+            }
+        }
+
+        // Run each system in the stage after its last predecessor
+        let mut by_rank = (0..count).collect::<Vec<_>>();
+        by_rank.sort_by_key(|&idx| rank[idx]);
+
+        let mut stage_of = vec![0; count];
+        let mut stages = Vec::<Vec<usize>>::new();
+        for idx in by_rank {
+            let stage = predecessors[idx]
+                .iter()
+                .map(|&pred| stage_of[pred] + 1)
+                .max()
+                .unwrap_or(0);
+
+            stage_of[idx] = stage;
+            if stages.len() <= stage {
+                stages.resize_with(stage + 1, Vec::new);
+            }
+            stages[stage].push(idx);
+        }
+
+        stages
+    }
+}
+
+impl<W: WorldParts> Default for Schedule<'_, W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn run_batch<W: WorldParts>(batch: Vec<(&mut SystemRun<'_, W>, SystemWorld<'_, W>)>) {
+    for (run, system_world) in batch {
+        run(system_world);
+    }
+}
+
+fn is_access_within(access: &SystemAccess, granted: &SystemAccess) -> bool {
+    access.archetypes.iter().all(|access| {
+        let Some(granted) = granted.get(access.id) else {
+            return false;
+        };
+
+        let can_read = |name| granted.reads.contains(name) || granted.writes.contains(name);
+        let can_write = |name| granted.writes.contains(name);
+        access.reads.iter().all(can_read) && access.writes.iter().all(can_write)
+    })
+}
+
+fn needs_exclusive(first: &SystemAccess, second: &SystemAccess) -> bool {
+    first.archetypes.iter().any(|first| {
+        second.get(first.id).is_some_and(|second| {
+            first.writes.is_empty() == false || second.writes.is_empty() == false
+        })
+    })
+}
+
+fn find_conflict(
+    first: &SystemAccess,
+    second: &SystemAccess,
+) -> Option<(&'static str, &'static str)> {
+    for first in first.archetypes.iter() {
+        let Some(second) = second.get(first.id) else {
+            continue;
+        };
+
+        for name in first.writes.iter() {
+            if second.reads.contains(name) || second.writes.contains(name) {
+                return Some((first.name, name));
+            }
+        }
+        for name in second.writes.iter() {
+            if first.reads.contains(name) {
+                return Some((first.name, name));
+            }
+        }
+    }
+
+    None
+}
+
+#[cold]
+#[inline(never)]
+fn panic_not_allowed<A: Archetype>() -> ! {
+    panic!(
+        "archetype `{}` is outside of the access this world was split for",
+        std::any::type_name::<A>(),
+    );
+}
+
+#[cold]
+#[inline(never)]
+fn panic_not_writable<A: Archetype>(can_read: bool) -> ! {
+    if can_read == false {
+        panic_not_allowed::<A>();
+    }
+
+    panic!(
+        "archetype `{}` is read-only in the access this world was split for",
+        std::any::type_name::<A>(),
+    );
+}
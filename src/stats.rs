@@ -0,0 +1,174 @@
+use crate::entity::ArchetypeId;
+
+/// Memory and occupancy statistics for an entire ECS world.
+///
+/// Returned by [`World::memory_stats`](crate::traits::World::memory_stats).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorldMemoryStats {
+    /// Statistics for each archetype in the world, in declaration order.
+    pub archetypes: Vec<ArchetypeMemoryStats>,
+}
+
+impl WorldMemoryStats {
+    /// Returns the total number of live entities across all archetypes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.archetypes.iter().map(|archetype| archetype.len).sum()
+    }
+
+    /// Returns true if no archetype in the world contains any entities.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the total number of bytes occupied by live data across all archetypes.
+    #[inline]
+    pub fn bytes_used(&self) -> usize {
+        self.archetypes.iter().map(|archetype| archetype.bytes_used()).sum()
+    }
+
+    /// Returns the total number of bytes allocated across all archetypes.
+    #[inline]
+    pub fn bytes_reserved(&self) -> usize {
+        self.archetypes.iter().map(|archetype| archetype.bytes_reserved()).sum()
+    }
+
+    /// Finds the statistics for the archetype with the given ID, if it exists in this world.
+    #[inline]
+    pub fn archetype(&self, archetype_id: ArchetypeId) -> Option<&ArchetypeMemoryStats> {
+        self.archetypes
+            .iter()
+            .find(|archetype| archetype.archetype_id == archetype_id)
+    }
+}
+
+/// Memory and occupancy statistics for a single archetype's storage.
+///
+/// Returned by [`Archetype::memory_stats`](crate::traits::Archetype::memory_stats).
+///
+/// Byte counts only include the storage's own allocations. Any heap memory owned
+/// by the components themselves (e.g. the contents of a `Vec` component) is not
+/// included, since the storage has no way to know about it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchetypeMemoryStats {
+    /// The archetype's ID.
+    pub archetype_id: ArchetypeId,
+    /// The archetype's name as declared in `ecs_world!`.
+    pub name: &'static str,
+    /// The number of live entities stored in this archetype.
+    pub len: usize,
+    /// The number of entities this archetype can store before it needs to reallocate.
+    pub capacity: usize,
+    /// The size in bytes of a single entry in the sparse slot array.
+    pub slot_size: usize,
+    /// The size in bytes of a single stored entity handle.
+    pub entity_size: usize,
+    /// The number of slots currently available on the slot free list.
+    pub free_slots: usize,
+    /// The number of entity handles currently held in the created/destroyed event queues.
+    ///
+    /// This is always zero if the `events` feature is not enabled.
+    pub event_queue_len: usize,
+    /// The number of entity handles the created/destroyed event queues can hold
+    /// before they need to reallocate.
+    ///
+    /// This is always zero if the `events` feature is not enabled.
+    pub event_queue_capacity: usize,
+    /// Statistics for each component column in this archetype, in declaration order.
+    pub components: Vec<ComponentMemoryStats>,
+}
+
+impl ArchetypeMemoryStats {
+    /// Returns the number of bytes allocated for the sparse slot array.
+    ///
+    /// The slot array is always fully allocated to the archetype's capacity.
+    #[inline]
+    pub fn slot_bytes(&self) -> usize {
+        self.slot_size * self.capacity
+    }
+
+    /// Returns the number of bytes occupied by live entity handles in the dense array.
+    #[inline]
+    pub fn entity_bytes_used(&self) -> usize {
+        self.entity_size * self.len
+    }
+
+    /// Returns the number of bytes allocated for the dense entity handle array.
+    #[inline]
+    pub fn entity_bytes_reserved(&self) -> usize {
+        self.entity_size * self.capacity
+    }
+
+    /// Returns the number of bytes occupied by handles in the event queues.
+    #[inline]
+    pub fn event_queue_bytes_used(&self) -> usize {
+        self.entity_size * self.event_queue_len
+    }
+
+    /// Returns the number of bytes allocated for the event queues.
+    #[inline]
+    pub fn event_queue_bytes_reserved(&self) -> usize {
+        self.entity_size * self.event_queue_capacity
+    }
+
+    /// Returns the number of bytes occupied by live data in this archetype.
+    ///
+    /// This counts the slots, entity handles, and components of live entities,
+    /// as well as any handles currently held in the event queues.
+    #[inline]
+    pub fn bytes_used(&self) -> usize {
+        (self.slot_size * self.len)
+            + self.entity_bytes_used()
+            + self.event_queue_bytes_used()
+            + self
+                .components
+                .iter()
+                .map(|component| component.bytes_used())
+                .sum::<usize>()
+    }
+
+    /// Returns the number of bytes allocated by this archetype.
+    #[inline]
+    pub fn bytes_reserved(&self) -> usize {
+        self.slot_bytes()
+            + self.entity_bytes_reserved()
+            + self.event_queue_bytes_reserved()
+            + self
+                .components
+                .iter()
+                .map(|component| component.bytes_reserved())
+                .sum::<usize>()
+    }
+}
+
+/// Memory and occupancy statistics for a single component column in an archetype.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentMemoryStats {
+    /// The component's ID within its archetype.
+    pub component_id: u8,
+    /// The component's type name as declared in `ecs_world!`.
+    pub name: &'static str,
+    /// The size in bytes of a single component element.
+    pub element_size: usize,
+    /// The number of elements currently stored in this column.
+    pub len: usize,
+    /// The number of elements this column can store before it needs to reallocate.
+    pub capacity: usize,
+}
+
+impl ComponentMemoryStats {
+    /// Returns the number of bytes occupied by live elements in this column.
+    #[inline]
+    pub fn bytes_used(&self) -> usize {
+        self.element_size * self.len
+    }
+
+    /// Returns the number of bytes allocated for this column.
+    ///
+    /// Zero-sized components never allocate, so this will always be zero for them.
+    #[inline]
+    pub fn bytes_reserved(&self) -> usize {
+        self.element_size * self.capacity
+    }
+}
@@ -0,0 +1,39 @@
+use crate::entity::ArchetypeId;
+
+/// The component data a system accesses, as declared by [`ecs_system`](crate::ecs_system).
+///
+/// Access is resolved at compile-time against the world's archetypes, so it lists exactly the
+/// archetypes the system's query matches and, for each one, which of its components are read
+/// or written. A component that is both read and written is only listed as a write.
+#[derive(Clone, Copy, Debug)]
+pub struct SystemAccess {
+    /// The name of the system function.
+    pub name: &'static str,
+    /// The access for each archetype the system matches, in world declaration order.
+    pub archetypes: &'static [ArchetypeAccess],
+}
+
+/// The component data a query accesses in a single archetype. See `ecs_query_access!`.
+#[derive(Clone, Copy, Debug)]
+pub struct ArchetypeAccess {
+    /// The ID of the accessed archetype.
+    pub id: ArchetypeId,
+    /// The name of the accessed archetype.
+    pub name: &'static str,
+    /// The names of the components that are only read.
+    pub reads: &'static [&'static str],
+    /// The names of the components that are written.
+    pub writes: &'static [&'static str],
+}
+
+impl SystemAccess {
+    /// Returns the access for the given archetype, if this system matches it.
+    pub fn get(&self, archetype_id: ArchetypeId) -> Option<&ArchetypeAccess> {
+        self.archetypes.iter().find(|access| access.id == archetype_id)
+    }
+
+    /// Returns `true` if this system never writes any component data.
+    pub fn is_read_only(&self) -> bool {
+        self.archetypes.iter().all(|access| access.writes.is_empty())
+    }
+}
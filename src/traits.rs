@@ -1,13 +1,16 @@
+use std::any::{Any, TypeId};
 use std::cell::{Ref, RefMut};
+use std::ptr::NonNull;
 
-use crate::entity::{ArchetypeId, Entity, EntityDirect};
+use crate::entity::{ArchetypeId, Entity, EntityAny, EntityDirect};
+use crate::error::EcsError;
+use crate::query::{ArchetypeSet, Query, QueryIter};
+use crate::stats::{ArchetypeMemoryStats, WorldMemoryStats};
 use crate::version::ArchetypeVersion;
 
 #[cfg(doc)]
 use crate::entity::EntityDirectAny;
 
-#[cfg(any(doc, feature = "events"))]
-use crate::entity::EntityAny;
 
 /// The base trait for an ECS world in gecs.
 ///
@@ -18,9 +21,43 @@ use crate::entity::EntityAny;
 pub trait World: Sized {
     const NUM_ARCHETYPES: usize;
 
+    /// The ID and name of each archetype in this world, in declaration order.
+    const ARCHETYPES: &'static [(ArchetypeId, &'static str)];
+
     /// The capacity input builder struct type. Contains one usize for each archetype on init.
     type Capacities;
 
+    /// Returns the name of the archetype with the given ID, if it exists in this world.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA;
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA);
+    ///     #[archetype_id(6)]
+    ///     ecs_archetype!(ArchBar, CompA);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///     let entity: EntityAny = world.create::<ArchBar>((CompA,)).into();
+    ///
+    ///     assert_eq!(EcsWorld::archetype_name(entity.archetype_id()), Some("ArchBar"));
+    ///     assert_eq!(EcsWorld::archetype_name(3), None);
+    /// }
+    /// ```
+    #[inline]
+    fn archetype_name(archetype_id: ArchetypeId) -> Option<&'static str> {
+        Self::ARCHETYPES
+            .iter()
+            .find(|(id, _)| *id == archetype_id)
+            .map(|(_, name)| *name)
+    }
+
     /// Creates a new empty world.
     ///
     /// This will not immediately allocate. All archetypes will begin with 0 capacity.
@@ -196,6 +233,57 @@ pub trait World: Sized {
         entity.resolve_view_mut(self)
     }
 
+    /// Gets a [`ViewMut`] for each of the given entities across archetypes in the full world,
+    /// allowing simultaneous mutable access to all of them. This is a convenience function for
+    /// [`Archetype::view_many_mut`], but also supports entities from different archetypes.
+    ///
+    /// If given [`EntityAny`] or [`EntityDirectAny`] keys, this returns `SelectViewMut` enums.
+    /// Returns `None` if any entity could not be found, or if any two of the given keys refer to
+    /// the same entity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called with [`EntityAny`] or [`EntityDirectAny`] with an archetype unrecognized
+    /// by this world. For fallible access, manually resolve the entity using [`SelectEntity`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct Health(i32);
+    /// pub struct Damage(i32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, Health, Damage);
+    ///     ecs_archetype!(ArchBar, Health, Damage);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///
+    ///     let entity_a = world.create::<ArchFoo>((Health(10), Damage(3))).into_any();
+    ///     let entity_b = world.create::<ArchBar>((Health(20), Damage(5))).into_any();
+    ///
+    ///     let views = world.view_many_mut([entity_a, entity_b]).unwrap();
+    ///     let [SelectViewMut::ArchFoo(mut a), SelectViewMut::ArchBar(mut b)] = views else {
+    ///         panic!("unexpected archetypes");
+    ///     };
+    ///
+    ///     a.component_mut::<Health>().0 -= b.component::<Damage>().0;
+    ///     b.component_mut::<Health>().0 -= a.component::<Damage>().0;
+    ///
+    ///     assert_eq!(ecs_find!(world, entity_a, |h: &Health| h.0), Some(5));
+    ///     assert_eq!(ecs_find!(world, entity_b, |h: &Health| h.0), Some(17));
+    /// }
+    /// ```
+    fn view_many_mut<'a, K: EntityKeySelectable<'a, Self>, const N: usize>(
+        &'a mut self,
+        entities: [K; N],
+    ) -> Option<[K::ViewMut; N]> {
+        K::resolve_view_many_mut(entities, self)
+    }
+
     /// Gets a [`Borrow`] for the given entity across archetypes in the full world.
     /// This is a convenience function for [`Archetype::borrow`].
     ///
@@ -239,6 +327,73 @@ pub trait World: Sized {
         entity.resolve_borrow(self)
     }
 
+    /// Calls [`ComponentVisitor::visit`] with each of the given entity's components, in the
+    /// order they were declared in its archetype. Returns `true` if the entity was found.
+    /// This is a convenience function for [`Archetype::visit_components`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the entity's archetype's components are currently mutably borrowed.
+    /// Also panics if called with [`EntityAny`] or [`EntityDirectAny`] with an archetype
+    /// unrecognized by this world.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gecs::prelude::*;
+    ///
+    /// #[derive(Debug)]
+    /// pub struct CompA(u32);
+    /// #[derive(Debug)]
+    /// pub struct CompB(u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    /// }
+    ///
+    /// struct NameCollector(Vec<&'static str>);
+    ///
+    /// impl ComponentVisitor for NameCollector {
+    ///     fn visit<C: 'static>(&mut self, name: &'static str, _: &C) {
+    ///         self.0.push(name);
+    ///     }
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///     let entity = world.create::<ArchFoo>((CompA(1), CompB(2))).into_any();
+    ///
+    ///     let mut collector = NameCollector(Vec::new());
+    ///     assert!(world.visit_components(entity, &mut collector));
+    ///     assert_eq!(collector.0, ["CompA", "CompB"]);
+    /// }
+    /// ```
+    #[inline(always)]
+    fn visit_components<'a, K: EntityKeySelectable<'a, Self>>(
+        &'a self,
+        entity: K,
+        visitor: &mut impl ComponentVisitor,
+    ) -> bool {
+        entity.resolve_visit_components(self, visitor)
+    }
+
+    /// Calls [`ComponentVisitorMut::visit_mut`] with each of the given entity's components, in
+    /// the order they were declared in its archetype. Returns `true` if the entity was found.
+    /// This is a convenience function for [`Archetype::visit_components_mut`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called with [`EntityAny`] or [`EntityDirectAny`] with an archetype unrecognized
+    /// by this world.
+    #[inline(always)]
+    fn visit_components_mut<'a, K: EntityKeySelectable<'a, Self>>(
+        &'a mut self,
+        entity: K,
+        visitor: &mut impl ComponentVisitorMut,
+    ) -> bool {
+        entity.resolve_visit_components_mut(self, visitor)
+    }
+
     /// If the entity exists in the world, this destroys it.
     ///
     /// This returns an `Option<(C0, C1, ..., Cn)>` where `(C0, C1, ..., Cn)` are the entity's
@@ -275,6 +430,129 @@ pub trait World: Sized {
         <Self as WorldHas<A>>::resolve_archetype_mut(self)
     }
 
+    /// Gets a trait object for the archetype with the given ID, if it exists in this world.
+    ///
+    /// See [`ArchetypeDyn`] for more information.
+    fn archetype_dyn(&self, archetype_id: ArchetypeId) -> Option<&dyn ArchetypeDyn>;
+
+    /// Gets a mutable trait object for the archetype with the given ID, if it exists in this world.
+    ///
+    /// See [`ArchetypeDyn`] for more information.
+    fn archetype_dyn_mut(&mut self, archetype_id: ArchetypeId) -> Option<&mut dyn ArchetypeDyn>;
+
+    /// Iterates over every entity matching the typed query `Q`, across all archetypes.
+    ///
+    /// This is a macro-free alternative to `ecs_iter!` that can be used through a generic world
+    /// bound, such as [`WorldHasComponent`]. See [`Query`] for more information.
+    ///
+    /// Queries that access the same component mutably more than once, as in
+    /// `(&mut CompA, &CompA)`, fail to compile.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    /// pub struct CompB(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA, CompB);
+    ///     ecs_archetype!(ArchBar, CompB);
+    /// }
+    ///
+    /// fn add_b_to_a<W>(world: &mut W)
+    /// where
+    ///     W: WorldHasComponent<CompA> + WorldHasComponent<CompB>,
+    /// {
+    ///     for (a, b) in world.query::<(&mut CompA, &CompB)>() {
+    ///         a.0 += b.0;
+    ///     }
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///     let entity = world.create::<ArchFoo>((CompA(1), CompB(10)));
+    ///     world.create::<ArchBar>((CompB(20),));
+    ///
+    ///     add_b_to_a(&mut world);
+    ///
+    ///     assert_eq!(world.query::<(&CompB,)>().count(), 2);
+    ///     assert_eq!(world.query_one::<(&CompA,)>(entity).map(|(a,)| a.0), Some(11));
+    /// }
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///
+    ///     // Error: the query accesses CompA mutably more than once
+    ///     for (a, b) in world.query::<(&mut CompA, &CompA)>() {}
+    /// }
+    /// ```
+    #[inline]
+    fn query<Q: Query<Self>>(&mut self) -> QueryIter<'_, Self, Q> {
+        QueryIter::new(self)
+    }
+
+    /// Gets the data for the typed query `Q` from the given entity, if the entity exists and
+    /// its archetype matches the query. See [`query`](World::query) for more information.
+    #[inline]
+    fn query_one<Q: Query<Self>>(&mut self, entity: impl Into<EntityAny>) -> Option<Q::Item<'_>> {
+        let () = Q::CHECK_ACCESS;
+
+        let entity = entity.into();
+        let archetype_id = entity.archetype_id();
+        if Q::ARCHETYPES.contains(archetype_id) == false {
+            return None;
+        }
+
+        let index = self.archetype_dyn(archetype_id)?.resolve_query_index(entity)?;
+        let fetch = Q::resolve_fetch(self, archetype_id)?;
+
+        Some(unsafe {
+            // SAFETY: The index was resolved from a live entity in this archetype, and we
+            // hold the world's exclusive borrow for the lifetime of the returned item
+            Q::resolve_get(fetch, index)
+        })
+    }
+
+    /// Collects memory and occupancy statistics for every archetype in this world.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(pub u64);
+    /// pub struct CompB(pub u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA);
+    ///     ecs_archetype!(ArchBar, CompA, CompB);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///     world.create::<ArchFoo>((CompA(0),));
+    ///     world.create::<ArchBar>((CompA(1), CompB(2)));
+    ///
+    ///     let stats = world.memory_stats();
+    ///     assert_eq!(stats.archetypes.len(), 2);
+    ///     assert_eq!(stats.len(), 2);
+    ///     assert!(stats.bytes_used() <= stats.bytes_reserved());
+    /// }
+    /// ```
+    fn memory_stats(&self) -> WorldMemoryStats;
+
     /// Returns an iterator over all the entities created since the last time entity events were
     /// cleared on the world or on any specific archetypes. This list has no ordering guarantees.
     /// Note that entities appear in this list even if they have since been destroyed.
@@ -359,6 +637,19 @@ where
     /// A unique type ID assigned to this archetype in generation.
     const ARCHETYPE_ID: ArchetypeId;
 
+    /// The name of this archetype as declared in `ecs_world!`.
+    const NAME: &'static str;
+
+    /// The name of each component in this archetype, in declaration order.
+    const COMPONENT_NAMES: &'static [&'static str];
+
+    /// The [`COMPONENT_ID`](ArchetypeHas::COMPONENT_ID) of each component in this
+    /// archetype, in declaration order.
+    const COMPONENT_IDS: &'static [u8];
+
+    /// The [`TypeId`] of each component in this archetype, in declaration order.
+    const COMPONENT_TYPE_IDS: &'static [TypeId];
+
     /// A struct with named storage to each component in this archetype.
     type Components: Components<Archetype = Self>;
 
@@ -414,6 +705,25 @@ where
     /// This slice is ordered arbitrarily and may change at later points.
     fn entities(&self) -> &[Entity<Self>];
 
+    /// Returns the entity at the given dense index in this archetype, if the index is in bounds.
+    ///
+    /// Dense indices range from `0` to `len()`, and follow the same order as [`entities`].
+    /// Note that destroying an entity may move another entity to a different dense index.
+    ///
+    /// [`entities`]: Archetype::entities
+    #[inline(always)]
+    fn entity_at(&self, index: usize) -> Option<Entity<Self>> {
+        self.entities().get(index).copied()
+    }
+
+    /// Returns a [`View`] for the entity at the given dense index, if the index is in bounds.
+    /// See [`Archetype::entity_at`] for more information on dense indices.
+    fn view_at(&mut self, index: usize) -> Option<Self::View<'_>>;
+
+    /// Returns a [`ViewMut`] for the entity at the given dense index, if the index is in bounds.
+    /// See [`Archetype::entity_at`] for more information on dense indices.
+    fn view_mut_at(&mut self, index: usize) -> Option<Self::ViewMut<'_>>;
+
     /// Creates a new entity with the given components to this archetype storage.
     /// Returns a typed entity handle pointing to the new entity in the archetype.
     ///
@@ -442,6 +752,39 @@ where
     /// data index for a specific entity using this function, use the `resolve` function.
     fn get_all_slices_mut(&mut self) -> Self::Slices<'_>;
 
+    #[doc(hidden)]
+    fn resolve_slices_entities<'a>(slices: &Self::Slices<'a>) -> &'a [Entity<Self>]
+    where
+        Self: 'a;
+
+    /// Collects memory and occupancy statistics for this archetype's storage, including
+    /// per-component column sizes, the slot array, the free list, and any event queues.
+    fn memory_stats(&self) -> ArchetypeMemoryStats;
+
+    /// Calls [`ComponentVisitor::visit`] with each of the given entity's components, in the
+    /// order they were declared in the archetype. Returns `true` if the entity was found.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of this archetype's components are currently mutably borrowed.
+    fn visit_components<K: EntityKey>(
+        &self,
+        entity: K,
+        visitor: &mut impl ComponentVisitor,
+    ) -> bool
+    where
+        Self: ArchetypeCanResolve<K>;
+
+    /// Calls [`ComponentVisitorMut::visit_mut`] with each of the given entity's components, in
+    /// the order they were declared in the archetype. Returns `true` if the entity was found.
+    fn visit_components_mut<K: EntityKey>(
+        &mut self,
+        entity: K,
+        visitor: &mut impl ComponentVisitorMut,
+    ) -> bool
+    where
+        Self: ArchetypeCanResolve<K>;
+
     /// Returns true if this archetype contains the given entity key.
     #[inline(always)]
     fn contains<K: EntityKey>(&self, entity: K) -> bool
@@ -517,6 +860,43 @@ where
         Some(RefMut::map(slice, |slice| &mut slice[index]))
     }
 
+    /// If the entity exists in the archetype, returns a borrow of a given component.
+    ///
+    /// Unlike [`Archetype::borrow_component`], this returns [`EcsError::BorrowConflict`] rather
+    /// than panicking if the component's column is currently mutably borrowed.
+    #[inline(always)]
+    fn try_borrow_component<C, K: EntityKey>(&self, entity: K) -> Result<Option<Ref<'_, C>>, EcsError>
+    where
+        Self: ArchetypeCanResolve<K>,
+        Self: ArchetypeHas<C>,
+    {
+        let Some(index) = self.resolve(entity) else {
+            return Ok(None);
+        };
+        let slice = self.try_borrow_slice::<C>()?;
+        Ok(Some(Ref::map(slice, |slice| &slice[index])))
+    }
+
+    /// If the entity exists in the archetype, returns a mutable borrow of a given component.
+    ///
+    /// Unlike [`Archetype::borrow_component_mut`], this returns [`EcsError::BorrowConflict`]
+    /// rather than panicking if the component's column is currently borrowed.
+    #[inline(always)]
+    fn try_borrow_component_mut<C, K: EntityKey>(
+        &self,
+        entity: K,
+    ) -> Result<Option<RefMut<'_, C>>, EcsError>
+    where
+        Self: ArchetypeCanResolve<K>,
+        Self: ArchetypeHas<C>,
+    {
+        let Some(index) = self.resolve(entity) else {
+            return Ok(None);
+        };
+        let slice = self.try_borrow_slice_mut::<C>()?;
+        Ok(Some(RefMut::map(slice, |slice| &mut slice[index])))
+    }
+
     /// Returns a ['View'] with references to all of this entity's components.
     /// Despite returning a read-only view, this requires mutable access to the archetype.
     /// For accessing components with immutable access, see [`Archetype::borrow`]..
@@ -598,6 +978,57 @@ where
         <Self as ArchetypeCanResolve<K>>::resolve_view_mut(self, entity)
     }
 
+    /// Returns a ['ViewMut'] for each of the given entities, allowing simultaneous mutable
+    /// access to all of their components. Returns `None` if any entity could not be found,
+    /// or if any two of the given keys refer to the same entity.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use gecs::prelude::*;
+    ///
+    /// pub struct CompA(u32);
+    ///
+    /// ecs_world! {
+    ///     ecs_archetype!(ArchFoo, CompA);
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut world = EcsWorld::default();
+    ///
+    ///     let entity_a = world.arch_foo.create((CompA(1),));
+    ///     let entity_b = world.arch_foo.create((CompA(2),));
+    ///
+    ///     let [mut view_a, mut view_b] = world.arch_foo.view_many_mut([entity_a, entity_b]).unwrap();
+    ///     std::mem::swap(view_a.component_mut::<CompA>(), view_b.component_mut::<CompA>());
+    ///
+    ///     assert_eq!(world.arch_foo.view(entity_a).unwrap().component::<CompA>().0, 2);
+    ///     assert!(world.arch_foo.view_many_mut([entity_a, entity_a]).is_none());
+    /// }
+    /// ```
+    fn view_many_mut<K: EntityKey, const N: usize>(
+        &mut self,
+        entities: [K; N],
+    ) -> Option<[Self::ViewMut<'_>; N]>
+    where
+        Self: ArchetypeCanResolve<K>,
+    {
+        let mut indices = [None; N];
+        for (index, entity) in indices.iter_mut().zip(entities) {
+            *index = Some(<Self as ArchetypeCanResolve<K>>::resolve_for(self, entity)?);
+        }
+
+        let views = self.get_view_many_mut_at(indices)?;
+        // Every index was given, so every view is populated
+        Some(views.map(|view| view.unwrap()))
+    }
+
+    #[doc(hidden)]
+    fn get_view_many_mut_at<const N: usize>(
+        &mut self,
+        indices: [Option<usize>; N],
+    ) -> Option<[Option<Self::ViewMut<'_>>; N]>;
+
     /// Returns a ['ViewMut'] with mutable references to all of this entity's components.
     /// This version also outputs an [`EntityDirect`] for the given input entity.
     #[inline(always)]
@@ -700,6 +1131,7 @@ where
     /// # Panics
     ///
     /// Panics if the runtime borrow fails, see [`std::cell::RefCell::borrow`].
+    /// See [`Archetype::try_borrow_slice`] for a non-panicking alternative.
     #[inline(always)]
     fn borrow_slice<C>(&self) -> Ref<'_, [C]>
     where
@@ -715,6 +1147,7 @@ where
     /// # Panics
     ///
     /// Panics if the runtime borrow fails, see [`std::cell::RefCell::borrow_mut`].
+    /// See [`Archetype::try_borrow_slice_mut`] for a non-panicking alternative.
     #[inline(always)]
     fn borrow_slice_mut<C>(&self) -> RefMut<'_, [C]>
     where
@@ -723,6 +1156,30 @@ where
         <Self as ArchetypeHas<C>>::resolve_borrow_slice_mut(self)
     }
 
+    /// Borrows the given slice of components from the archetype's dense data.
+    ///
+    /// Unlike [`Archetype::borrow_slice`], this returns [`EcsError::BorrowConflict`] rather than
+    /// panicking if the slice is currently mutably borrowed.
+    #[inline(always)]
+    fn try_borrow_slice<C>(&self) -> Result<Ref<'_, [C]>, EcsError>
+    where
+        Self: ArchetypeHas<C>,
+    {
+        <Self as ArchetypeHas<C>>::resolve_try_borrow_slice(self)
+    }
+
+    /// Borrows the given mutable slice of components from the archetype's dense data.
+    ///
+    /// Unlike [`Archetype::borrow_slice_mut`], this returns [`EcsError::BorrowConflict`] rather
+    /// than panicking if the slice is currently borrowed.
+    #[inline(always)]
+    fn try_borrow_slice_mut<C>(&self) -> Result<RefMut<'_, [C]>, EcsError>
+    where
+        Self: ArchetypeHas<C>,
+    {
+        <Self as ArchetypeHas<C>>::resolve_try_borrow_slice_mut(self)
+    }
+
     /// Returns an iterator over all the entities created since the last time entity events were
     /// cleared on the world or on this specific archetype. This list has no ordering guarantees.
     /// Note that entities appear in this list even if they have since been destroyed.
@@ -791,6 +1248,141 @@ where
     fn clear_events(&mut self);
 }
 
+/// An object-safe view of an archetype for use when its type is only known at runtime.
+///
+/// Use [`World::archetype_dyn`] and [`World::archetype_dyn_mut`] to get a trait object for an
+/// archetype from its [`ArchetypeId`]. This is intended for tools like editors, consoles, or
+/// scripting layers that can't name archetype types statically. Component access goes through
+/// [`Any`] and is keyed by [`TypeId`], so every component type in the world must be `'static`.
+///
+/// Every archetype generated by `ecs_world!` implements this trait. It is deliberately not
+/// included in the prelude, as its method names overlap with those of [`Archetype`].
+///
+/// # Examples
+///
+/// ```rust
+/// use gecs::prelude::*;
+///
+/// pub struct CompA(pub u32);
+/// pub struct CompB(pub u32);
+///
+/// ecs_world! {
+///     ecs_archetype!(ArchFoo, CompA, CompB);
+///     ecs_archetype!(ArchBar, CompA);
+/// }
+///
+/// fn main() {
+///     let mut world = EcsWorld::default();
+///     let entity = world.create::<ArchFoo>((CompA(1), CompB(2))).into_any();
+///
+///     let archetype = world.archetype_dyn_mut(entity.archetype_id()).unwrap();
+///     assert_eq!(archetype.name(), "ArchFoo");
+///     assert_eq!(archetype.len(), 1);
+///     assert!(archetype.contains(entity));
+///
+///     archetype.get_component_mut::<CompB>(entity).unwrap().0 = 5;
+///     assert_eq!(archetype.get_component::<CompB>(entity).unwrap().0, 5);
+///     assert!(archetype.get_component::<String>(entity).is_none());
+///
+///     assert!(archetype.destroy(entity));
+///     assert!(archetype.is_empty());
+/// }
+/// ```
+pub trait ArchetypeDyn {
+    /// Returns this archetype's [`ARCHETYPE_ID`](Archetype::ARCHETYPE_ID).
+    fn archetype_id(&self) -> ArchetypeId;
+
+    /// Returns this archetype's [`NAME`](Archetype::NAME).
+    fn name(&self) -> &'static str;
+
+    /// Returns this archetype's [`COMPONENT_NAMES`](Archetype::COMPONENT_NAMES).
+    fn component_names(&self) -> &'static [&'static str];
+
+    /// Returns this archetype's [`COMPONENT_TYPE_IDS`](Archetype::COMPONENT_TYPE_IDS).
+    fn component_type_ids(&self) -> &'static [TypeId];
+
+    /// Returns the number of entities in the archetype, also referred to as its length.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the archetype contains no elements.
+    fn is_empty(&self) -> bool;
+
+    /// Returns the number of elements the archetype can hold without reallocating.
+    fn capacity(&self) -> usize;
+
+    /// Returns a read-only slice of all entities in this archetype as [`EntityAny`] handles.
+    /// This slice is ordered arbitrarily and may change at later points.
+    fn entities(&self) -> &[EntityAny];
+
+    /// Returns `true` if the given entity belongs to this archetype and is currently alive.
+    fn contains(&self, entity: EntityAny) -> bool;
+
+    /// Destroys the given entity, dropping its components. Returns `true` if it was found.
+    fn destroy(&mut self, entity: EntityAny) -> bool;
+
+    /// Collects memory and occupancy statistics for this archetype's storage.
+    fn memory_stats(&self) -> ArchetypeMemoryStats;
+
+    /// Gets the component with the given [`TypeId`] for the given entity, if both exist.
+    fn component(&mut self, entity: EntityAny, type_id: TypeId) -> Option<&dyn Any>;
+
+    /// Mutably gets the component with the given [`TypeId`] for the given entity, if both exist.
+    fn component_mut(&mut self, entity: EntityAny, type_id: TypeId) -> Option<&mut dyn Any>;
+
+    /// Borrows the component with the given [`TypeId`] for the given entity, if both exist.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component's column is currently mutably borrowed.
+    fn borrow_component(&self, entity: EntityAny, type_id: TypeId) -> Option<Ref<'_, dyn Any>>;
+
+    /// Mutably borrows the component with the given [`TypeId`] for the given entity, if both exist.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the component's column is currently borrowed.
+    fn borrow_component_mut(
+        &self,
+        entity: EntityAny,
+        type_id: TypeId,
+    ) -> Option<RefMut<'_, dyn Any>>;
+
+    /// Borrows the component with the given [`TypeId`] for the given entity, if both exist.
+    ///
+    /// Returns [`EcsError::BorrowConflict`] if the component's column is mutably borrowed.
+    fn try_borrow_component(
+        &self,
+        entity: EntityAny,
+        type_id: TypeId,
+    ) -> Result<Option<Ref<'_, dyn Any>>, EcsError>;
+
+    /// Mutably borrows the component with the given [`TypeId`] for the given entity, if both exist.
+    ///
+    /// Returns [`EcsError::BorrowConflict`] if the component's column is currently borrowed.
+    fn try_borrow_component_mut(
+        &self,
+        entity: EntityAny,
+        type_id: TypeId,
+    ) -> Result<Option<RefMut<'_, dyn Any>>, EcsError>;
+
+    #[doc(hidden)]
+    fn resolve_query_index(&self, entity: EntityAny) -> Option<usize>;
+}
+
+impl dyn ArchetypeDyn + '_ {
+    /// Gets the component of type `C` for the given entity, if both exist.
+    #[inline]
+    pub fn get_component<C: Any>(&mut self, entity: EntityAny) -> Option<&C> {
+        self.component(entity, TypeId::of::<C>())?.downcast_ref()
+    }
+
+    /// Mutably gets the component of type `C` for the given entity, if both exist.
+    #[inline]
+    pub fn get_component_mut<C: Any>(&mut self, entity: EntityAny) -> Option<&mut C> {
+        self.component_mut(entity, TypeId::of::<C>())?.downcast_mut()
+    }
+}
+
 /// A trait promising that an ECS world has the given archetype.
 ///
 /// Used for where bounds on functions that take an ECS world as a generic type.
@@ -842,6 +1434,45 @@ pub trait WorldHas<A: Archetype>: World {
     fn resolve_archetype_mut(&mut self) -> &mut A;
 }
 
+/// A trait promising that at least one archetype in an ECS world has a given component.
+///
+/// Used for where bounds on functions that run typed [`Query`]s on an ECS world as a generic
+/// type. The `ecs_world!` macro implements this once per component type in the world, and
+/// each implementation knows at compile-time which archetypes have that component.
+///
+/// See [`World::query`] for the methods that this enables on a type.
+///
+/// # Examples
+///
+/// ```
+/// use gecs::prelude::*;
+///
+/// pub struct CompA(pub u32);
+///
+/// ecs_world! {
+///     // Declare archetype ArchFoo with one component: CompA
+///     ecs_archetype!(ArchFoo, CompA);
+/// }
+///
+/// fn sum_comp_a<W>(world: &mut W) -> u32
+/// where
+///     W: WorldHasComponent<CompA>,
+/// {
+///     world.query::<(&CompA,)>().map(|(a,)| a.0).sum()
+/// }
+///
+/// # fn main() {} // Not actually running anything here
+/// ```
+pub trait WorldHasComponent<C>: World {
+    #[doc(hidden)]
+    const QUERY_COMPONENT_INDEX: usize;
+    #[doc(hidden)]
+    const QUERY_ARCHETYPES: ArchetypeSet;
+
+    #[doc(hidden)]
+    fn resolve_query_column(&mut self, archetype_id: ArchetypeId) -> Option<NonNull<C>>;
+}
+
 /// A trait promising that an archetype has a given component.
 ///
 /// Used for where bounds on functions that take an archetype as a generic type.
@@ -884,11 +1515,21 @@ pub trait ArchetypeHas<C>: Archetype {
     #[doc(hidden)]
     fn resolve_get_slice(&mut self) -> &[C];
     #[doc(hidden)]
+    unsafe fn resolve_get_slice_unchecked(&self) -> &[C];
+    #[doc(hidden)]
     fn resolve_get_slice_mut(&mut self) -> &mut [C];
     #[doc(hidden)]
     fn resolve_borrow_slice(&self) -> Ref<'_, [C]>;
     #[doc(hidden)]
     fn resolve_borrow_slice_mut(&self) -> RefMut<'_, [C]>;
+    #[doc(hidden)]
+    fn resolve_take_slice<'a>(slices: &mut Self::Slices<'a>) -> &'a mut [C]
+    where
+        Self: 'a;
+    #[doc(hidden)]
+    fn resolve_try_borrow_slice(&self) -> Result<Ref<'_, [C]>, EcsError>;
+    #[doc(hidden)]
+    fn resolve_try_borrow_slice_mut(&self) -> Result<RefMut<'_, [C]>, EcsError>;
 
     #[doc(hidden)]
     fn resolve_extract_components(components: &Self::Components) -> &C;
@@ -904,6 +1545,12 @@ pub trait ArchetypeHas<C>: Archetype {
     fn resolve_extract_borrow<'a>(borrow: &'a Self::Borrow<'_>) -> Ref<'a, C>;
     #[doc(hidden)]
     fn resolve_extract_borrow_mut<'a>(borrow: &'a Self::Borrow<'_>) -> RefMut<'a, C>;
+    #[doc(hidden)]
+    fn resolve_try_extract_borrow<'a>(borrow: &'a Self::Borrow<'_>) -> Result<Ref<'a, C>, EcsError>;
+    #[doc(hidden)]
+    fn resolve_try_extract_borrow_mut<'a>(
+        borrow: &'a Self::Borrow<'_>,
+    ) -> Result<RefMut<'a, C>, EcsError>;
 }
 
 pub trait Components {
@@ -991,6 +1638,48 @@ pub trait Borrow<'a> {
     {
         <Self::Archetype as ArchetypeHas<C>>::resolve_extract_borrow_mut(self)
     }
+
+    /// Gets the given component from this borrow, returning [`EcsError::BorrowConflict`] rather
+    /// than panicking if any other borrow has exclusive/mut access to this type of component
+    /// within this same archetype.
+    #[inline(always)]
+    fn try_component<'b, C>(&'b self) -> Result<Ref<'b, C>, EcsError>
+    where
+        Self::Archetype: ArchetypeHas<C>,
+    {
+        <Self::Archetype as ArchetypeHas<C>>::resolve_try_extract_borrow(self)
+    }
+
+    /// Gets the given component mutably from this borrow, returning [`EcsError::BorrowConflict`]
+    /// rather than panicking if any other borrow has any type of access to this type of
+    /// component within this same archetype.
+    #[inline(always)]
+    fn try_component_mut<'b, C>(&'b self) -> Result<RefMut<'b, C>, EcsError>
+    where
+        Self::Archetype: ArchetypeHas<C>,
+    {
+        <Self::Archetype as ArchetypeHas<C>>::resolve_try_extract_borrow_mut(self)
+    }
+}
+
+/// A visitor for inspecting each of an entity's components in turn.
+///
+/// Passed to [`World::visit_components`] or [`Archetype::visit_components`], which call
+/// [`visit`](ComponentVisitor::visit) once for each component the entity has, in the order
+/// the components were declared in the entity's archetype. This allows generic per-entity
+/// tooling (inspectors, loggers, and so on) without matching on each archetype by hand.
+/// Specific component types can be picked out using [`TypeId`] or [`Any`].
+pub trait ComponentVisitor {
+    /// Visits a single component, along with its type name as declared in `ecs_world!`.
+    fn visit<C: 'static>(&mut self, name: &'static str, component: &C);
+}
+
+/// A visitor for mutably inspecting each of an entity's components in turn.
+///
+/// See [`ComponentVisitor`] for more information.
+pub trait ComponentVisitorMut {
+    /// Visits a single component, along with its type name as declared in `ecs_world!`.
+    fn visit_mut<C: 'static>(&mut self, name: &'static str, component: &mut C);
 }
 
 /// Trait promising that a given ECS world can resolve a type of entity key.
@@ -1095,5 +1784,18 @@ pub trait EntityKeySelectable<'a, W: World>: EntityKey {
     #[doc(hidden)]
     fn resolve_view_mut(self, world: &'a mut W) -> Option<Self::ViewMut>;
     #[doc(hidden)]
+    fn resolve_view_many_mut<const N: usize>(
+        entities: [Self; N],
+        world: &'a mut W,
+    ) -> Option<[Self::ViewMut; N]>;
+    #[doc(hidden)]
     fn resolve_borrow(self, world: &'a W) -> Option<Self::Borrow>;
+    #[doc(hidden)]
+    fn resolve_visit_components(self, world: &'a W, visitor: &mut impl ComponentVisitor) -> bool;
+    #[doc(hidden)]
+    fn resolve_visit_components_mut(
+        self,
+        world: &'a mut W,
+        visitor: &mut impl ComponentVisitorMut,
+    ) -> bool;
 }
@@ -32,3 +32,28 @@ macro_rules! debug_checked_unreachable {
 
 pub(crate) use debug_checked_assume;
 pub(crate) use debug_checked_unreachable;
+
+/// Formats with the given closure. Used for generated `Debug` implementations.
+#[doc(hidden)]
+pub struct DebugWith<F>(pub F)
+where
+    F: Fn(&mut std::fmt::Formatter<'_>) -> std::fmt::Result;
+
+impl<F> std::fmt::Debug for DebugWith<F>
+where
+    F: Fn(&mut std::fmt::Formatter<'_>) -> std::fmt::Result,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self.0)(f)
+    }
+}
+
+/// Placeholder for component data that couldn't be formatted because it's mutably borrowed.
+#[doc(hidden)]
+pub struct DebugBorrowed;
+
+impl std::fmt::Debug for DebugBorrowed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("<borrowed>")
+    }
+}
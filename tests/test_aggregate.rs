@@ -0,0 +1,76 @@
+use gecs::prelude::*;
+
+pub struct CompA(pub u32);
+pub struct CompB(pub u32);
+pub struct Dead;
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, CompA, CompB);
+    ecs_archetype!(ArchBar, CompA, Dead);
+    ecs_archetype!(ArchBaz, CompB);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_aggregate_count() {
+    let mut world = EcsWorld::default();
+
+    world.arch_foo.create((CompA(1), CompB(10)));
+    world.arch_foo.create((CompA(2), CompB(20)));
+    world.arch_bar.create((CompA(3), Dead));
+    world.arch_baz.create((CompB(30),));
+
+    assert_eq!(ecs_count!(world, |_: &CompA|), 3);
+    assert_eq!(ecs_count!(world, |_: &CompA, _: Without<Dead>|), 2);
+    assert_eq!(ecs_count!(world, |_: With<Dead>|), 1);
+    assert_eq!(ecs_count!(world, |_: &EntityAny|), 4);
+    assert_eq!(ecs_count!(world, |b: &CompB| b.0 >= 20), 2);
+    assert_eq!(ecs_count!(world, |a: &CompA, _: With<CompB>| a.0 > 1), 1);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_aggregate_any() {
+    let mut world = EcsWorld::default();
+
+    assert!(!ecs_any!(world, |_: &CompA|));
+
+    world.arch_baz.create((CompB(30),));
+    world.arch_bar.create((CompA(3), Dead));
+
+    assert!(ecs_any!(world, |_: &CompA|));
+    assert!(!ecs_any!(world, |_: &CompA, _: Without<Dead>|));
+    assert!(ecs_any!(world, |b: &CompB| b.0 == 30));
+    assert!(!ecs_any!(world, |b: &CompB| b.0 == 31));
+
+    let mut visited = 0;
+    assert!(ecs_any!(world, |_: &EntityAny| { visited += 1; true }));
+    assert_eq!(visited, 1);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_aggregate_fold() {
+    let mut world = EcsWorld::default();
+
+    world.arch_foo.create((CompA(1), CompB(10)));
+    world.arch_foo.create((CompA(2), CompB(20)));
+    world.arch_bar.create((CompA(3), Dead));
+
+    assert_eq!(ecs_fold!(world, 0, |acc, a: &CompA| acc + a.0), 6);
+    assert_eq!(ecs_fold!(world, 0, |acc, a: &CompA, _: Without<Dead>| acc + a.0), 3);
+    assert_eq!(ecs_fold!(world, 0u64, |acc: u64, b: &CompB| acc + b.0 as u64), 30);
+
+    let names = ecs_fold!(world, Vec::new(), |mut acc, a: &CompA, _: With<Dead>| {
+        acc.push(a.0);
+        acc
+    });
+    assert_eq!(names, [3]);
+
+    let total = ecs_fold!(world, 0, |acc, a: &mut CompA, b: &CompB| {
+        a.0 += b.0;
+        acc + a.0
+    });
+    assert_eq!(total, 33);
+    assert_eq!(ecs_fold!(world, 0, |acc, a: &CompA| acc + a.0), 36);
+}
@@ -0,0 +1,77 @@
+#![allow(unexpected_cfgs)]
+
+use gecs::prelude::*;
+
+#[derive(Debug, PartialEq)]
+pub struct Position(pub u32);
+#[derive(Debug, PartialEq)]
+pub struct Velocity(pub u32);
+pub struct Sprite(pub u32);
+pub struct Health(pub u32);
+pub struct BossPhase(pub u32);
+pub struct Loot(pub u32);
+#[cfg(feature = "some_feature")]
+pub struct Debug(pub u32);
+
+ecs_world! {
+    // Templates have no storage, they only declare components to inherit
+    ecs_template!(
+        Movement,
+        Position,
+        #[component_id(4)]
+        Velocity,
+    );
+    ecs_template!(Render: Movement, Sprite, #[cfg(feature = "some_feature")] Debug);
+
+    ecs_archetype!(ArchEnemy: Render, Health);
+    ecs_archetype!(ArchBoss: ArchEnemy, BossPhase, Loot);
+    ecs_archetype!(ArchProp: Render);
+
+    #[archetype_id(10)]
+    ecs_archetype!(ArchLoot, Loot);
+
+    // Archetypes can be composed from any number of earlier templates or archetypes
+    ecs_archetype!(ArchMover: Movement + ArchLoot);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_archetype_bases_components() {
+    assert_eq!(EcsWorld::ARCHETYPES, &[(0, "ArchEnemy"), (1, "ArchBoss"), (2, "ArchProp"), (10, "ArchLoot"), (11, "ArchMover")]);
+
+    assert_eq!(ArchEnemy::COMPONENT_NAMES, &["Position", "Velocity", "Sprite", "Health"]);
+    assert_eq!(ArchEnemy::COMPONENT_IDS, &[0, 4, 5, 6]);
+
+    assert_eq!(ArchBoss::COMPONENT_NAMES, &["Position", "Velocity", "Sprite", "Health", "BossPhase", "Loot"]);
+    assert_eq!(ArchBoss::COMPONENT_IDS, &[0, 4, 5, 6, 7, 8]);
+
+    assert_eq!(ArchProp::COMPONENT_NAMES, &["Position", "Velocity", "Sprite"]);
+    assert_eq!(ArchMover::COMPONENT_NAMES, &["Position", "Velocity", "Loot"]);
+}
+
+#[test]
+pub fn test_archetype_bases_query() {
+    let mut world = EcsWorld::default();
+
+    let enemy = world.create::<ArchEnemy>((Position(0), Velocity(1), Sprite(0), Health(10)));
+    let boss = world.create::<ArchBoss>((
+        Position(0),
+        Velocity(2),
+        Sprite(0),
+        Health(100),
+        BossPhase(0),
+        Loot(5),
+    ));
+    world.create::<ArchProp>((Position(0), Velocity(0), Sprite(1)));
+
+    ecs_iter!(world, |pos: &mut Position, vel: &Velocity| {
+        pos.0 += vel.0;
+    });
+
+    assert_eq!(ecs_find!(world, enemy, |p: &Position| p.0), Some(1));
+    assert_eq!(ecs_find!(world, boss, |p: &Position| p.0), Some(2));
+
+    let mut health = 0;
+    ecs_iter!(world, |h: &Health, _: Without<BossPhase>| health += h.0);
+    assert_eq!(health, 10);
+}
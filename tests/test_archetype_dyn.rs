@@ -0,0 +1,86 @@
+use std::any::TypeId;
+
+use gecs::prelude::*;
+
+pub struct CompA(pub u32);
+pub struct CompB(pub u32);
+pub struct CompC(pub u32);
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, CompA, CompB);
+    #[archetype_id(7)]
+    ecs_archetype!(ArchBar, CompA, CompC);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_archetype_dyn_lookup() {
+    let mut world = EcsWorld::default();
+
+    let foo = world.archetype_dyn(ArchFoo::ARCHETYPE_ID).unwrap();
+    assert_eq!(foo.archetype_id(), ArchFoo::ARCHETYPE_ID);
+    assert_eq!(foo.name(), "ArchFoo");
+    assert_eq!(foo.component_names(), &["CompA", "CompB"]);
+    assert_eq!(foo.component_type_ids(), &[TypeId::of::<CompA>(), TypeId::of::<CompB>()]);
+    assert!(foo.is_empty());
+
+    assert_eq!(world.archetype_dyn(7).unwrap().name(), "ArchBar");
+    assert!(world.archetype_dyn(3).is_none());
+    assert!(world.archetype_dyn_mut(3).is_none());
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_archetype_dyn_entities() {
+    let mut world = EcsWorld::default();
+
+    let entity_a = world.arch_foo.create((CompA(0), CompB(1))).into_any();
+    let entity_b = world.arch_foo.create((CompA(2), CompB(3))).into_any();
+    let entity_c = world.arch_bar.create((CompA(4), CompC(5))).into_any();
+
+    let foo = world.archetype_dyn_mut(entity_a.archetype_id()).unwrap();
+    assert_eq!(foo.len(), 2);
+    assert_eq!(foo.entities(), &[entity_a, entity_b]);
+    assert!(foo.contains(entity_a));
+    assert!(!foo.contains(entity_c));
+
+    assert!(foo.destroy(entity_a));
+    assert!(!foo.destroy(entity_a));
+    assert!(!foo.destroy(entity_c));
+    assert!(!foo.contains(entity_a));
+    assert_eq!(foo.entities(), &[entity_b]);
+
+    assert_eq!(world.arch_foo.len(), 1);
+    assert_eq!(world.arch_bar.len(), 1);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_archetype_dyn_components() {
+    let mut world = EcsWorld::default();
+
+    let entity_a = world.arch_foo.create((CompA(0), CompB(1))).into_any();
+    let entity_b = world.arch_bar.create((CompA(2), CompC(3))).into_any();
+
+    let bar = world.archetype_dyn_mut(entity_b.archetype_id()).unwrap();
+    assert_eq!(bar.get_component::<CompA>(entity_b).unwrap().0, 2);
+    assert!(bar.get_component::<CompB>(entity_b).is_none());
+    assert!(bar.get_component::<CompA>(entity_a).is_none());
+
+    bar.get_component_mut::<CompC>(entity_b).unwrap().0 = 10;
+    assert_eq!(world.arch_bar.entities().len(), 1);
+
+    let bar = world.archetype_dyn(entity_b.archetype_id()).unwrap();
+    {
+        let comp_c = bar.borrow_component(entity_b, TypeId::of::<CompC>()).unwrap();
+        assert_eq!(comp_c.downcast_ref::<CompC>().unwrap().0, 10);
+    }
+    {
+        let mut comp_a = bar.borrow_component_mut(entity_b, TypeId::of::<CompA>()).unwrap();
+        comp_a.downcast_mut::<CompA>().unwrap().0 = 20;
+    }
+    assert!(bar.borrow_component(entity_b, TypeId::of::<CompB>()).is_none());
+
+    let comp_a = ecs_find!(world, entity_b, |a: &CompA| a.0);
+    assert_eq!(comp_a, Some(20));
+}
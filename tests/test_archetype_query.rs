@@ -0,0 +1,90 @@
+use gecs::prelude::*;
+
+#[derive(Debug, PartialEq)]
+pub struct CompA(pub u32);
+#[derive(Debug, PartialEq)]
+pub struct CompB(pub u32);
+#[derive(Debug, PartialEq)]
+pub struct CompC(pub u32);
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, CompA, CompB);
+    ecs_archetype!(ArchBar, CompC, CompB, CompA);
+}
+
+fn add_b_to_a<A: ArchetypeHas<CompA> + ArchetypeHas<CompB>>(archetype: &mut A) -> Vec<Entity<A>> {
+    let mut visited = Vec::new();
+    ecs_iter_archetype!(archetype, |entity: &Entity<_>, a: &mut CompA, b: &CompB| {
+        visited.push(*entity);
+        a.0 += b.0;
+    });
+    visited
+}
+
+fn find_a<A: ArchetypeHas<CompA>>(archetype: &mut A, entity: Entity<A>) -> Option<u32> {
+    ecs_find_archetype!(archetype, entity, |a: &CompA| a.0)
+}
+
+#[test]
+fn test_archetype_query_generic() {
+    let mut world = EcsWorld::default();
+
+    let foo = world.arch_foo.create((CompA(1), CompB(10)));
+    let bar_a = world.arch_bar.create((CompC(0), CompB(20), CompA(2)));
+    let bar_b = world.arch_bar.create((CompC(0), CompB(30), CompA(3)));
+
+    assert_eq!(add_b_to_a(&mut world.arch_foo), [foo]);
+    assert_eq!(add_b_to_a(world.archetype_mut::<ArchBar>()), [bar_a, bar_b]);
+
+    assert_eq!(find_a(&mut world.arch_foo, foo), Some(11));
+    assert_eq!(find_a(&mut world.arch_bar, bar_a), Some(22));
+    assert_eq!(find_a(&mut world.arch_bar, bar_b), Some(33));
+
+    world.destroy(bar_a);
+    assert_eq!(find_a(&mut world.arch_bar, bar_a), None);
+}
+
+#[test]
+fn test_archetype_query_concrete() {
+    let mut world = EcsWorld::default();
+
+    let entity_a = world.arch_bar.create((CompC(0), CompB(0), CompA(0)));
+    let entity_b = world.arch_bar.create((CompC(1), CompB(0), CompA(0)));
+    world.arch_bar.create((CompC(2), CompB(0), CompA(0)));
+
+    let mut anys = Vec::new();
+    let mut directs = Vec::new();
+    ecs_iter_archetype!(&mut world.arch_bar, |c: &CompC, any: &EntityAny, direct: &EntityDirect<ArchBar>| {
+        anys.push(*any);
+        directs.push(*direct);
+        match c.0 {
+            1 => EcsStep::Break,
+            _ => EcsStep::Continue,
+        }
+    });
+    assert_eq!(anys, [entity_a.into_any(), entity_b.into_any()]);
+
+    let found = ecs_find_archetype!(world.archetype_mut::<ArchBar>(), directs[1], |e: &Entity<_>, c: &mut CompC| {
+        c.0 += 10;
+        (*e, c.0)
+    });
+    assert_eq!(found, Some((entity_b, 11)));
+
+    let found = ecs_find_archetype!(&mut world.arch_bar, entity_a, |e: &EntityDirectAny| *e);
+    assert_eq!(found, Some(directs[0].into()));
+}
+
+fn swap_generic<A: ArchetypeHas<T> + ArchetypeHas<U>, T, U>(archetype: &mut A) {
+    ecs_iter_archetype!(archetype, |t: &mut T, u: &mut U| {
+        let _ = (t, u);
+    });
+}
+
+#[test]
+#[should_panic(expected = "query accesses the same component more than once")]
+fn test_archetype_query_aliased_generic() {
+    let mut world = EcsWorld::default();
+    world.arch_foo.create((CompA(1), CompB(10)));
+
+    swap_generic::<_, CompA, CompA>(&mut world.arch_foo);
+}
@@ -0,0 +1,71 @@
+use gecs::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompA(pub u32);
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompB(pub f32);
+pub struct CompC;
+
+ecs_world! {
+    #[derive(PartialEq)]
+    ecs_name!(DeriveWorld);
+
+    #[derive(Eq)]
+    ecs_archetype!(ArchFoo, CompA);
+    ecs_archetype!(ArchBar, CompA, CompB);
+}
+
+mod other {
+    use super::*;
+
+    // CompC supports neither Debug nor PartialEq, so these impls simply don't apply
+    ecs_world! {
+        #[derive(PartialEq)]
+        ecs_archetype!(ArchBaz, CompA, CompC);
+    }
+}
+
+#[test]
+fn test_derive_debug() {
+    let mut world = DeriveWorld::default();
+    let entity = world.create::<ArchFoo>((CompA(1),));
+    world.create::<ArchBar>((CompA(2), CompB(0.5)));
+
+    let entity_string = format!("{:?}", entity);
+    assert_eq!(
+        format!("{:?}", world.arch_foo),
+        format!("ArchFoo {{{}: ArchFooComponents {{ comp_a: CompA(1) }}}}", entity_string),
+    );
+
+    let world_string = format!("{:?}", world);
+    assert!(world_string.starts_with("DeriveWorld { arch_foo: ArchFoo {"));
+    assert!(world_string.contains("ArchBarComponents { comp_a: CompA(2), comp_b: CompB(0.5) }"));
+
+    // Mutably borrowed components are skipped instead of panicking
+    let _borrow = world.arch_bar.borrow_slice_mut::<CompB>();
+    let world_string = format!("{:?}", world);
+    assert!(world_string.contains("ArchBarComponents { comp_a: CompA(2), comp_b: <borrowed> }"));
+}
+
+#[test]
+fn test_derive_partial_eq() {
+    let mut world = DeriveWorld::default();
+    let entity = world.create::<ArchFoo>((CompA(1),));
+    world.create::<ArchBar>((CompA(2), CompB(0.5)));
+
+    let mut clone = world.clone();
+    assert_eq!(world, clone);
+    assert_eq!(world.arch_foo, clone.arch_foo);
+
+    ecs_find!(clone, entity, |a: &mut CompA| a.0 = 10);
+    assert_ne!(world, clone);
+    assert_ne!(world.arch_foo, clone.arch_foo);
+    assert_eq!(world.arch_bar, clone.arch_bar);
+
+    let components = ArchBarComponents::from((CompA(3), CompB(1.0)));
+    assert_eq!(components.clone(), components);
+
+    fn assert_eq_impl<T: Eq>() {}
+    assert_eq_impl::<ArchFoo>();
+    assert_eq_impl::<ArchFooComponents>();
+}
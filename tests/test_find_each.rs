@@ -0,0 +1,81 @@
+use gecs::prelude::*;
+
+pub struct CompA(pub u32);
+pub struct CompB(pub u32);
+pub struct CompC(pub u32);
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, CompA, CompB);
+    ecs_archetype!(ArchBar, CompA, CompC);
+    ecs_archetype!(ArchBaz, CompB, CompC);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_find_each_basic() {
+    let mut world = EcsWorld::default();
+
+    let entity_a = world.arch_foo.create((CompA(1), CompB(10))).into_any();
+    let entity_b = world.arch_bar.create((CompA(2), CompC(20))).into_any();
+    let entity_c = world.arch_foo.create((CompA(3), CompB(30))).into_any();
+    let entity_d = world.arch_baz.create((CompB(40), CompC(50))).into_any();
+    let entity_e = world.arch_bar.create((CompA(4), CompC(60))).into_any();
+
+    world.destroy(entity_e);
+
+    let handles = [entity_a, entity_b, entity_c, entity_d, entity_e];
+    let mut visited = Vec::new();
+    let missed = ecs_find_each!(world, handles.iter().copied(), |e: &EntityAny, a: &mut CompA| {
+        a.0 += 100;
+        visited.push(*e);
+    });
+
+    // Handles are grouped by archetype, but keep their relative order within each group
+    assert_eq!(visited, [entity_a, entity_c, entity_b]);
+    assert_eq!(missed, [entity_d, entity_e]);
+
+    assert_eq!(ecs_find!(world, entity_a, |a: &CompA| a.0), Some(101));
+    assert_eq!(ecs_find!(world, entity_b, |a: &CompA| a.0), Some(102));
+    assert_eq!(ecs_find!(world, entity_c, |a: &CompA| a.0), Some(103));
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_find_each_typed() {
+    let mut world = EcsWorld::default();
+
+    let entity_a = world.arch_foo.create((CompA(1), CompB(10)));
+    let entity_b = world.arch_foo.create((CompA(2), CompB(20)));
+    let entity_c = world.arch_foo.create((CompA(3), CompB(30)));
+    world.arch_foo.destroy(entity_b);
+
+    let mut sum = 0;
+    let missed = ecs_find_each!(world, [entity_a, entity_b, entity_c, entity_a], |b: &CompB| {
+        sum += b.0;
+    });
+    assert_eq!(sum, 50);
+    assert_eq!(missed, [entity_b]);
+
+    let direct_c = world.arch_foo.to_direct(entity_c).unwrap();
+    let missed = ecs_find_each!(world, [direct_c], |a: &mut CompA, _: Without<CompC>| a.0 = 0);
+    assert!(missed.is_empty());
+    assert_eq!(ecs_find!(world, entity_c, |a: &CompA| a.0), Some(0));
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_find_each_break() {
+    let mut world = EcsWorld::default();
+
+    let handles = (0..10)
+        .map(|i| world.arch_bar.create((CompA(i), CompC(0))).into_any())
+        .collect::<Vec<_>>();
+
+    let mut count = 0;
+    let missed = ecs_find_each!(world, handles, |_: &CompA| {
+        count += 1;
+        if count == 3 { EcsStep::Break } else { EcsStep::Continue }
+    });
+    assert_eq!(count, 3);
+    assert!(missed.is_empty());
+}
@@ -0,0 +1,169 @@
+use gecs::prelude::*;
+
+pub trait Drawable {
+    fn depth(&self) -> u32;
+    fn shift(&mut self, amount: u32);
+}
+
+pub trait Named {
+    fn name(&self) -> &'static str;
+}
+
+pub struct CompA(pub u32);
+pub struct Sprite(pub u32);
+pub struct Mesh(pub u32);
+pub struct Label(pub u32);
+
+impl Drawable for Sprite {
+    fn depth(&self) -> u32 {
+        self.0
+    }
+
+    fn shift(&mut self, amount: u32) {
+        self.0 += amount;
+    }
+}
+
+impl Drawable for Mesh {
+    fn depth(&self) -> u32 {
+        self.0 * 100
+    }
+
+    fn shift(&mut self, amount: u32) {
+        self.0 += amount * 10;
+    }
+}
+
+impl Named for Sprite {
+    fn name(&self) -> &'static str {
+        "sprite"
+    }
+}
+
+impl Named for Label {
+    fn name(&self) -> &'static str {
+        "label"
+    }
+}
+
+ecs_world! {
+    ecs_archetype!(ArchSprite, CompA, #[implements(Drawable, Named)] Sprite);
+    ecs_archetype!(ArchMesh, CompA, #[implements(Drawable)] Mesh, #[implements(Named)] Label);
+    ecs_archetype!(ArchNone, CompA);
+}
+
+
+#[test]
+#[rustfmt::skip]
+pub fn test_implements_iter_single() {
+    let mut world = EcsWorld::default();
+
+    world.arch_sprite.create((CompA(0), Sprite(1)));
+    world.arch_sprite.create((CompA(0), Sprite(2)));
+    world.arch_mesh.create((CompA(0), Mesh(3), Label(0)));
+    world.arch_none.create((CompA(0),));
+
+    let mut sum = 0;
+    ecs_iter!(world, |d: &dyn Drawable| sum += d.depth());
+    assert_eq!(sum, 303);
+
+    ecs_iter!(world, |d: &mut dyn Drawable| d.shift(1));
+
+    let mut sum = 0;
+    ecs_iter_borrow!(world, |d: &dyn Drawable| sum += d.depth());
+    assert_eq!(sum, 2 + 3 + 1300);
+
+    ecs_iter_borrow!(world, |d: &mut dyn Drawable| d.shift(1));
+
+    let mut names = Vec::new();
+    ecs_iter!(world, |n: &dyn Named, _: &CompA| names.push(n.name()));
+    assert_eq!(names, ["sprite", "sprite", "label"]);
+
+    let mut sum = 0;
+    ecs_iter!(world, |d: &dyn Drawable, n: &dyn Named| sum += d.depth() + n.name().len() as u32);
+    assert_eq!(sum, (3 + 6) + (4 + 6) + (2300 + 5));
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_implements_find() {
+    let mut world = EcsWorld::default();
+
+    let entity_a = world.arch_sprite.create((CompA(0), Sprite(1)));
+    let entity_b = world.arch_mesh.create((CompA(0), Mesh(2), Label(0)));
+    let entity_d = world.arch_none.create((CompA(0),));
+
+    assert_eq!(ecs_find!(world, entity_a, |d: &dyn Drawable| d.depth()), Some(1));
+    assert_eq!(ecs_find!(world, entity_b.into_any(), |d: &dyn Drawable| d.depth()), Some(200));
+    assert_eq!(ecs_find!(world, entity_d.into_any(), |d: &dyn Drawable| d.depth()), None);
+
+    ecs_find!(world, entity_b, |d: &mut dyn Drawable| d.shift(1));
+    ecs_find_borrow!(world, entity_a, |d: &mut dyn Drawable| d.shift(1));
+    assert_eq!(ecs_find_borrow!(world, entity_b, |d: &dyn Drawable| d.depth()), Some(1200));
+    assert_eq!(ecs_find_borrow!(world, entity_a, |d: &dyn Drawable| d.depth()), Some(2));
+}
+
+mod multi {
+    use gecs::prelude::*;
+
+    use super::{CompA, Drawable, Mesh, Sprite};
+
+    ecs_world! {
+        ecs_archetype!(ArchSprite, CompA, #[implements(Drawable)] Sprite);
+        ecs_archetype!(ArchBoth, Sprite, #[implements(Drawable)] Mesh); // Sprite declared above
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    pub fn test_implements_iter_slice() {
+        let mut world = EcsWorld::default();
+
+        world.arch_sprite.create((CompA(0), Sprite(1)));
+        world.arch_both.create((Sprite(2), Mesh(3)));
+
+        let mut counts = Vec::new();
+        ecs_iter!(world, |ds: &[&dyn Drawable]| counts.push(ds.len()));
+        counts.sort();
+        assert_eq!(counts, [1, 2]);
+
+        ecs_iter!(world, |ds: &mut [&mut dyn Drawable]| {
+            for d in ds.iter_mut() {
+                d.shift(1);
+            }
+        });
+
+        let mut sum = 0;
+        ecs_iter_borrow!(world, |ds: &[&dyn Drawable]| {
+            sum += ds.iter().map(|d| d.depth()).sum::<u32>();
+        });
+        assert_eq!(sum, 2 + 3 + 1300);
+
+        ecs_iter_destroy!(world, |ds: &[&dyn Drawable]| {
+            match ds.len() > 1 {
+                true => EcsStepDestroy::ContinueDestroy,
+                false => EcsStepDestroy::Continue,
+            }
+        });
+        assert!(world.arch_both.is_empty());
+        assert_eq!(world.arch_sprite.len(), 1);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    pub fn test_implements_find_slice() {
+        let mut world = EcsWorld::default();
+
+        let entity_c = world.arch_both.create((Sprite(3), Mesh(4)));
+
+        ecs_find!(world, entity_c, |ds: &mut [&mut dyn Drawable]| ds.iter_mut().for_each(|d| d.shift(1)));
+        ecs_find_borrow!(world, entity_c, |ds: &mut [&mut dyn Drawable]| ds.iter_mut().for_each(|d| d.shift(1)));
+        let depths = ecs_find!(world, entity_c, |ds: &[&dyn Drawable]| {
+            ds.iter().map(|d| d.depth()).collect::<Vec<_>>()
+        });
+        assert_eq!(depths, Some(vec![5, 2400]));
+        let depths = ecs_find_borrow!(world, entity_c, |ds: &[&dyn Drawable]| {
+            ds.iter().map(|d| d.depth()).collect::<Vec<_>>()
+        });
+        assert_eq!(depths, Some(vec![5, 2400]));
+    }
+}
@@ -0,0 +1,130 @@
+use gecs::prelude::*;
+
+pub struct CompA(pub u32);
+pub struct CompB(pub u32);
+pub struct CompC(pub u32);
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, CompA, CompB);
+    ecs_archetype!(ArchBar, CompA, CompC);
+    ecs_archetype!(ArchBaz, CompB, CompC);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_iter_budget_passes() {
+    let mut world = EcsWorld::default();
+
+    for i in 0..5 {
+        world.arch_foo.create((CompA(i), CompB(0)));
+    }
+    for i in 0..3 {
+        world.arch_bar.create((CompA(i), CompC(0)));
+    }
+    world.arch_baz.create((CompB(0), CompC(0)));
+
+    let mut cursor = IterCursor::new();
+    let mut visited = 0;
+
+    // 8 matching entities with a budget of 3 take three calls to complete a pass
+    assert!(!ecs_iter_budget!(world, &mut cursor, 3, |a: &mut CompA| { a.0 += 1; visited += 1; }));
+    assert!(!cursor.is_start());
+    assert!(!ecs_iter_budget!(world, &mut cursor, 3, |a: &mut CompA| { a.0 += 1; visited += 1; }));
+    assert_eq!(cursor.archetype_id(), Some(ArchBar::ARCHETYPE_ID));
+    assert!(ecs_iter_budget!(world, &mut cursor, 3, |a: &mut CompA| { a.0 += 1; visited += 1; }));
+    assert!(cursor.is_start());
+    assert_eq!(visited, 8);
+
+    // Every entity was visited exactly once
+    let mut values = Vec::new();
+    ecs_iter!(world, |a: &CompA| values.push(a.0));
+    values.sort();
+    assert_eq!(values, [1, 1, 2, 2, 3, 3, 4, 5]);
+
+    // A budget covering everything completes in one call
+    let mut visited = 0;
+    assert!(ecs_iter_budget!(world, &mut cursor, 100, |_: &CompA| visited += 1));
+    assert_eq!(visited, 8);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_iter_budget_resync() {
+    let mut world = EcsWorld::default();
+
+    let entities = (0..6)
+        .map(|i| world.arch_foo.create((CompA(i), CompB(0))))
+        .collect::<Vec<_>>();
+
+    let mut cursor = IterCursor::new();
+    assert!(!ecs_iter_budget!(world, &mut cursor, 4, |b: &mut CompB| b.0 += 1));
+
+    // Creating entities only appends, so iteration continues where it left off
+    world.arch_foo.create((CompA(6), CompB(0)));
+    assert!(ecs_iter_budget!(world, &mut cursor, 4, |b: &mut CompB| b.0 += 1));
+
+    let mut values = Vec::new();
+    ecs_iter!(world, |b: &CompB| values.push(b.0));
+    assert_eq!(values, [1; 7]);
+
+    // Destroying an entity may reorder the archetype, so iteration restarts it
+    assert!(!ecs_iter_budget!(world, &mut cursor, 4, |b: &mut CompB| b.0 += 1));
+    world.arch_foo.destroy(entities[0]);
+    assert!(!ecs_iter_budget!(world, &mut cursor, 4, |b: &mut CompB| b.0 += 1));
+    assert!(ecs_iter_budget!(world, &mut cursor, 4, |b: &mut CompB| b.0 += 1));
+
+    let mut values = Vec::new();
+    ecs_iter!(world, |b: &CompB| values.push(b.0));
+    assert!(values.iter().all(|v| *v >= 2));
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_iter_budget_break() {
+    let mut world = EcsWorld::default();
+
+    for i in 0..4 {
+        world.arch_bar.create((CompA(i), CompC(0)));
+    }
+
+    let mut cursor = IterCursor::new();
+    let mut seen = Vec::new();
+    assert!(!ecs_iter_budget!(world, &mut cursor, 10, |a: &CompA| {
+        seen.push(a.0);
+        if a.0 == 1 { EcsStep::Break } else { EcsStep::Continue }
+    }));
+    assert!(ecs_iter_budget!(world, &mut cursor, 10, |a: &CompA| seen.push(a.0)));
+    assert_eq!(seen, [0, 1, 2, 3]);
+
+    // A cursor from another query restarts from the beginning
+    let mut cursor = IterCursor::new();
+    assert!(!ecs_iter_budget!(world, &mut cursor, 1, |_: &CompA| {}));
+    let mut count = 0;
+    assert!(ecs_iter_budget!(world, &mut cursor, 10, |_: &CompB| count += 1));
+    assert_eq!(count, 0);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_dense_index_access() {
+    let mut world = EcsWorld::default();
+
+    let entity_a = world.arch_foo.create((CompA(1), CompB(10)));
+    let entity_b = world.arch_foo.create((CompA(2), CompB(20)));
+
+    assert_eq!(world.arch_foo.entity_at(0), Some(entity_a));
+    assert_eq!(world.arch_foo.entity_at(1), Some(entity_b));
+    assert_eq!(world.arch_foo.entity_at(2), None);
+
+    assert_eq!(world.arch_foo.view_at(1).unwrap().component::<CompB>().0, 20);
+    assert!(world.arch_foo.view_at(2).is_none());
+
+    world.arch_foo.view_mut_at(0).unwrap().component_mut::<CompA>().0 = 5;
+    assert!(world.arch_foo.view_mut_at(2).is_none());
+    assert_eq!(ecs_find!(world, entity_a, |a: &CompA| a.0), Some(5));
+
+    // Destroying an entity moves the last entity into its dense index
+    world.arch_foo.destroy(entity_a);
+    assert_eq!(world.arch_foo.entity_at(0), Some(entity_b));
+    assert_eq!(world.arch_foo.entity_at(1), None);
+}
@@ -0,0 +1,76 @@
+use gecs::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LocalPos(pub i32);
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorldPos(pub i32);
+pub struct Parent(pub Option<Entity<ArchNode>>);
+pub struct ParentAny(pub EntityAny);
+pub struct Tag;
+
+ecs_world! {
+    ecs_archetype!(ArchNode, LocalPos, WorldPos, Parent);
+    ecs_archetype!(ArchRoot, LocalPos, WorldPos);
+    ecs_archetype!(ArchLeaf, WorldPos, ParentAny, Tag);
+}
+
+#[test]
+pub fn test_iter_nested_same_archetype() {
+    let mut world = EcsWorld::default();
+
+    let root = world.arch_node.create((LocalPos(1), WorldPos(0), Parent(None)));
+    let child = world.arch_node.create((LocalPos(10), WorldPos(0), Parent(Some(root))));
+    let grandchild = world.arch_node.create((LocalPos(100), WorldPos(0), Parent(Some(child))));
+
+    // The nested query only reads LocalPos, which the outer query doesn't touch
+    ecs_iter!(world, |world_pos: &mut WorldPos, local: &LocalPos, parent: &Parent| {
+        let parent_local = parent.0.and_then(&mut parent_local);
+        world_pos.0 = local.0 + parent_local.unwrap_or(0);
+    }, nested parent_local = |local: &LocalPos| local.0);
+
+    assert_eq!(ecs_find!(world, root, |p: &WorldPos| p.0), Some(1));
+    assert_eq!(ecs_find!(world, child, |p: &WorldPos| p.0), Some(11));
+    assert_eq!(ecs_find!(world, grandchild, |p: &WorldPos| p.0), Some(110));
+}
+
+#[test]
+pub fn test_iter_nested_other_archetypes() {
+    let mut world = EcsWorld::default();
+
+    let root = world.arch_root.create((LocalPos(5), WorldPos(50)));
+    let node = world.arch_node.create((LocalPos(7), WorldPos(70), Parent(None)));
+    let leaf_a = world.arch_leaf.create((WorldPos(0), ParentAny(root.into()), Tag));
+    let leaf_b = world.arch_leaf.create((WorldPos(0), ParentAny(node.into()), Tag));
+
+    // The nested query may mutate data in archetypes the outer query doesn't visit
+    ecs_iter!(world, |world_pos: &mut WorldPos, parent: &ParentAny, _: &Tag| {
+        world_pos.0 = parent_pos(parent.0).unwrap();
+    }, nested parent_pos = |pos: &WorldPos, local: &mut LocalPos| -> i32 {
+        local.0 += 1;
+        pos.0
+    });
+
+    assert_eq!(ecs_find!(world, leaf_a, |p: &WorldPos| p.0), Some(50));
+    assert_eq!(ecs_find!(world, leaf_b, |p: &WorldPos| p.0), Some(70));
+    assert_eq!(ecs_find!(world, root, |l: &LocalPos| l.0), Some(6));
+    assert_eq!(ecs_find!(world, node, |l: &LocalPos| l.0), Some(8));
+}
+
+#[test]
+pub fn test_iter_nested_stale_and_break() {
+    let mut world = EcsWorld::default();
+
+    let root = world.arch_root.create((LocalPos(1), WorldPos(0)));
+    world.arch_leaf.create((WorldPos(0), ParentAny(root.into()), Tag));
+    world.arch_leaf.create((WorldPos(0), ParentAny(root.into()), Tag));
+    world.destroy(root);
+
+    let mut visited = 0;
+    ecs_iter!(world, |_: &WorldPos, parent: &ParentAny| {
+        visited += 1;
+        assert_eq!(find_parent(parent.0), None);
+        EcsStep::Break
+    }, nested find_parent = |local: &LocalPos| local.0);
+
+    assert_eq!(visited, 1);
+}
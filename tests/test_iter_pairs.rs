@@ -0,0 +1,105 @@
+use gecs::prelude::*;
+
+pub struct Pos(pub i32);
+pub struct Vel(pub i32);
+pub struct Fixed;
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, Pos, Vel);
+    ecs_archetype!(ArchBar, Pos, Vel);
+    ecs_archetype!(ArchBaz, Pos, Fixed);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_iter_pairs_count() {
+    let mut world = EcsWorld::default();
+
+    for i in 0..4 {
+        world.arch_foo.create((Pos(i), Vel(0)));
+    }
+    for i in 0..3 {
+        world.arch_bar.create((Pos(i), Vel(0)));
+    }
+    world.arch_baz.create((Pos(0), Fixed));
+
+    // Every unordered pair of distinct entities is visited exactly once
+    let mut count = 0;
+    ecs_iter_pairs!(world, |(_a: &Pos), (_b)| count += 1);
+    assert_eq!(count, 8 * 7 / 2);
+
+    let mut count = 0;
+    ecs_iter_pairs!(world, |(_a: &Pos, _v: &Vel), (_b, _w)| count += 1);
+    assert_eq!(count, 7 * 6 / 2);
+
+    let mut count = 0;
+    ecs_iter_pairs!(world, |(_a: &Pos, _: Without<Fixed>), (_b, _)| count += 1);
+    assert_eq!(count, 7 * 6 / 2);
+
+    let mut count = 0;
+    ecs_iter_pairs!(world, |(a: &EntityAny), (b)| {
+        assert_ne!(a, b);
+        count += 1;
+    });
+    assert_eq!(count, 8 * 7 / 2);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_iter_pairs_mut() {
+    let mut world = EcsWorld::default();
+
+    let entity_a = world.arch_foo.create((Pos(1), Vel(0)));
+    let entity_b = world.arch_foo.create((Pos(10), Vel(0)));
+    let entity_c = world.arch_bar.create((Pos(100), Vel(0)));
+
+    // Each entity accumulates the positions of every other entity
+    ecs_iter_pairs!(world, |(a_pos: &Pos, a_vel: &mut Vel), (b_pos, b_vel)| {
+        a_vel.0 += b_pos.0;
+        b_vel.0 += a_pos.0;
+    });
+
+    assert_eq!(ecs_find!(world, entity_a, |v: &Vel| v.0), Some(110));
+    assert_eq!(ecs_find!(world, entity_b, |v: &Vel| v.0), Some(101));
+    assert_eq!(ecs_find!(world, entity_c, |v: &Vel| v.0), Some(11));
+
+    // Both sides can be mutated at once
+    ecs_iter_pairs!(world, |(a: &mut Pos), (b)| {
+        std::mem::swap(&mut a.0, &mut b.0);
+    });
+
+    let mut values = Vec::new();
+    ecs_iter!(world, |p: &Pos| values.push(p.0));
+    values.sort();
+    assert_eq!(values, [1, 10, 100]);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_iter_pairs_break() {
+    fn count_pairs(world: &mut EcsWorld, limit: usize) -> usize {
+        let mut count = 0;
+        ecs_iter_pairs!(world, |(_a: &mut Pos), (_b)| {
+            count += 1;
+            if count == limit { EcsStep::Break } else { EcsStep::Continue }
+        });
+        count
+    }
+
+    let mut world = EcsWorld::default();
+
+    for i in 0..4 {
+        world.arch_foo.create((Pos(i), Vel(0)));
+        world.arch_bar.create((Pos(i), Vel(0)));
+    }
+
+    let mut count = 0;
+    ecs_iter_pairs!(world, |(_a: &Pos), (_b)| {
+        count += 1;
+        if count == 5 { EcsStep::Break } else { EcsStep::Continue }
+    });
+    assert_eq!(count, 5);
+
+    assert_eq!(count_pairs(&mut world, 100), 8 * 7 / 2);
+    assert_eq!(count_pairs(&mut world, 20), 20);
+}
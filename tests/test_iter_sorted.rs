@@ -0,0 +1,71 @@
+use std::cmp::Reverse;
+
+use gecs::prelude::*;
+
+pub struct Depth(pub u32);
+pub struct Name(pub &'static str);
+pub struct Speed(pub u32);
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, Depth, Name);
+    ecs_archetype!(ArchBar, Depth, Name, Speed);
+    ecs_archetype!(ArchBaz, Name, Speed);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_iter_sorted_basic() {
+    let mut world = EcsWorld::default();
+
+    world.arch_foo.create((Depth(3), Name("c")));
+    world.arch_bar.create((Depth(1), Name("a"), Speed(0)));
+    world.arch_foo.create((Depth(0), Name("z")));
+    world.arch_bar.create((Depth(2), Name("b"), Speed(0)));
+    world.arch_baz.create((Name("none"), Speed(0)));
+
+    let mut names = Vec::new();
+    ecs_iter_sorted!(world, |d: &Depth| d.0, |n: &Name| names.push(n.0));
+    assert_eq!(names, ["z", "a", "b", "c"]);
+
+    let mut names = Vec::new();
+    ecs_iter_sorted!(world, |d: &Depth| Reverse(d.0), |n: &Name| names.push(n.0));
+    assert_eq!(names, ["c", "b", "a", "z"]);
+
+    // The body's parameters further filter the matched archetypes
+    let mut names = Vec::new();
+    ecs_iter_sorted!(world, |d: &Depth| d.0, |n: &Name, _: &Speed| names.push(n.0));
+    assert_eq!(names, ["a", "b"]);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_iter_sorted_mut() {
+    let mut world = EcsWorld::default();
+
+    world.arch_foo.create((Depth(5), Name("e")));
+    world.arch_bar.create((Depth(5), Name("f"), Speed(0)));
+    world.arch_foo.create((Depth(1), Name("d")));
+
+    // Each entity is assigned its rank in the sorted order, with ties in iteration order
+    let mut rank = 0;
+    let mut order = Vec::new();
+    ecs_iter_sorted!(world, |d: &Depth| d.0, |e: &EntityAny, d: &mut Depth, n: &Name| {
+        d.0 = rank;
+        rank += 1;
+        order.push((*e, n.0));
+    });
+
+    let names = order.iter().map(|(_, n)| *n).collect::<Vec<_>>();
+    assert_eq!(names, ["d", "e", "f"]);
+
+    for (rank, (entity, _)) in order.iter().enumerate() {
+        assert_eq!(ecs_find!(world, *entity, |d: &Depth| d.0), Some(rank as u32));
+    }
+
+    let mut count = 0;
+    ecs_iter_sorted!(world, |d: &Depth| d.0, |_: &Name| {
+        count += 1;
+        if count == 2 { EcsStep::Break } else { EcsStep::Continue }
+    });
+    assert_eq!(count, 2);
+}
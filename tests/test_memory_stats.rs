@@ -0,0 +1,96 @@
+use std::mem;
+
+use gecs::prelude::*;
+
+pub struct CompA(pub u64);
+pub struct CompB(pub u32);
+pub struct CompZ; // ZST
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, CompA, CompZ);
+    #[archetype_id(5)]
+    ecs_archetype!(ArchBar, CompA, CompB);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_memory_stats_empty() {
+    let world = EcsWorld::default();
+    let stats = world.memory_stats();
+
+    assert_eq!(stats.archetypes.len(), 2);
+    assert!(stats.is_empty());
+    assert_eq!(stats.bytes_used(), 0);
+    assert_eq!(stats.bytes_reserved(), 0);
+
+    let foo = &stats.archetypes[0];
+    assert_eq!(foo.archetype_id, ArchFoo::ARCHETYPE_ID);
+    assert_eq!(foo.name, "ArchFoo");
+    assert_eq!(foo.capacity, 0);
+    assert_eq!(foo.free_slots, 0);
+    assert_eq!(foo.components.len(), 2);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_memory_stats_occupancy() {
+    let mut world = EcsWorld::with_capacity(EcsWorldCapacity {
+        arch_foo: 4,
+        arch_bar: 8,
+    });
+
+    let entity = world.arch_foo.create((CompA(0), CompZ));
+    world.arch_foo.create((CompA(1), CompZ));
+    world.arch_bar.create((CompA(2), CompB(3)));
+
+    let foo = world.arch_foo.memory_stats();
+    assert_eq!(foo.len, 2);
+    assert_eq!(foo.capacity, 4);
+    assert_eq!(foo.free_slots, 2);
+    assert_eq!(foo.entity_bytes_used(), 2 * foo.entity_size);
+    assert_eq!(foo.entity_bytes_reserved(), 4 * foo.entity_size);
+    assert_eq!(foo.slot_bytes(), 4 * foo.slot_size);
+
+    let comp_a = &foo.components[0];
+    assert_eq!(comp_a.name, "CompA");
+    assert_eq!(comp_a.component_id, <ArchFoo as ArchetypeHas<CompA>>::COMPONENT_ID);
+    assert_eq!(comp_a.element_size, mem::size_of::<CompA>());
+    assert_eq!(comp_a.bytes_used(), 2 * mem::size_of::<CompA>());
+    assert_eq!(comp_a.bytes_reserved(), 4 * mem::size_of::<CompA>());
+
+    let comp_z = &foo.components[1];
+    assert_eq!(comp_z.name, "CompZ");
+    assert_eq!(comp_z.bytes_reserved(), 0);
+
+    world.arch_foo.destroy(entity);
+    let foo = world.arch_foo.memory_stats();
+    assert_eq!(foo.len, 1);
+    assert_eq!(foo.free_slots, 3);
+
+    let stats = world.memory_stats();
+    assert_eq!(stats.len(), 2);
+    assert!(stats.bytes_used() < stats.bytes_reserved());
+
+    let bar = stats.archetype(5).unwrap();
+    assert_eq!(bar.name, "ArchBar");
+    assert_eq!(bar.capacity, 8);
+    assert_eq!(bar.components[1].name, "CompB");
+    assert!(stats.archetype(3).is_none());
+}
+
+#[test]
+#[cfg(feature = "events")]
+#[rustfmt::skip]
+pub fn test_memory_stats_events() {
+    let mut world = EcsWorld::default();
+
+    let entity = world.arch_foo.create((CompA(0), CompZ));
+    world.arch_foo.destroy(entity);
+
+    let foo = world.arch_foo.memory_stats();
+    assert_eq!(foo.event_queue_len, 2);
+    assert!(foo.event_queue_capacity >= 2);
+
+    world.clear_events();
+    assert_eq!(world.arch_foo.memory_stats().event_queue_len, 0);
+}
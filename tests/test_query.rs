@@ -0,0 +1,92 @@
+use gecs::prelude::*;
+
+pub struct CompA(pub u32);
+pub struct CompB(pub u32);
+pub struct CompC(pub u32);
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, CompA, CompB);
+    ecs_archetype!(ArchBar, CompA, CompC);
+    ecs_archetype!(ArchBaz, CompB, CompC);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_query_basic() {
+    let mut world = EcsWorld::default();
+
+    world.arch_foo.create((CompA(1), CompB(10)));
+    world.arch_foo.create((CompA(2), CompB(20)));
+    world.arch_bar.create((CompA(3), CompC(30)));
+    world.arch_baz.create((CompB(40), CompC(50)));
+
+    let sum: u32 = ecs_query!(world, |a: &CompA|).map(|(a,)| a.0).sum();
+    assert_eq!(sum, 6);
+
+    for (a, b) in ecs_query!(world, |a: &mut CompA, b: &CompB|) {
+        a.0 += b.0;
+    }
+
+    let values = ecs_query!(world, |a: &CompA|).map(|(a,)| a.0).collect::<Vec<_>>();
+    assert_eq!(values, [11, 22, 3]);
+
+    assert_eq!(ecs_query!(world, |_b: &CompB, _c: &CompC|).count(), 1);
+    assert_eq!(ecs_query!(world, ||).count(), 4);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_query_entities() {
+    let mut world = EcsWorld::default();
+
+    let entity_a = world.arch_foo.create((CompA(1), CompB(10)));
+    let entity_b = world.arch_bar.create((CompA(2), CompC(20)));
+
+    let entities = ecs_query!(world, |e: &EntityAny, _a: &CompA|)
+        .map(|(e, _)| *e)
+        .collect::<Vec<_>>();
+    assert_eq!(entities, [entity_a.into_any(), entity_b.into_any()]);
+
+    let found = ecs_query!(world, |e: &Entity<ArchBar>, a: &CompA|)
+        .map(|(e, a)| (*e, a.0))
+        .collect::<Vec<_>>();
+    assert_eq!(found, [(entity_b, 2)]);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_query_early_return() {
+    fn find_first(world: &mut EcsWorld, value: u32) -> Option<EntityAny> {
+        for (entity, a) in ecs_query!(world, |e: &EntityAny, a: &CompA|) {
+            if a.0 == value {
+                return Some(*entity);
+            }
+        }
+        None
+    }
+
+    let mut world = EcsWorld::default();
+
+    world.arch_foo.create((CompA(1), CompB(10)));
+    let entity = world.arch_bar.create((CompA(2), CompC(20)));
+
+    assert_eq!(find_first(&mut world, 2), Some(entity.into_any()));
+    assert_eq!(find_first(&mut world, 3), None);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_query_collect() {
+    let mut world = EcsWorld::default();
+
+    world.arch_foo.create((CompA(1), CompB(10)));
+    world.arch_bar.create((CompA(2), CompC(20)));
+
+    // References can be held across iterations
+    let mut all = ecs_query!(world, |a: &mut CompA|).map(|(a,)| a).collect::<Vec<_>>();
+    all[0].0 = 5;
+    all[1].0 = 6;
+
+    let values = ecs_query!(world, |a: &CompA|).map(|(a,)| a.0).collect::<Vec<_>>();
+    assert_eq!(values, [5, 6]);
+}
@@ -0,0 +1,57 @@
+use gecs::prelude::*;
+
+pub struct CompA(pub u32);
+pub struct CompB(pub u32);
+pub struct CompC(pub u32);
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, CompA, CompB);
+    ecs_archetype!(ArchBar, CompA, CompC);
+    #[archetype_id(6)]
+    ecs_archetype!(ArchBaz, CompB, CompC);
+}
+
+#[derive(EcsQuery)]
+pub struct Pair<'a> {
+    pub a: &'a CompA,
+    pub b: &'a mut CompB,
+}
+
+const MATCHED_A: &[(ArchetypeId, &str)] = ecs_query_archetypes!(|_: &CompA|);
+
+#[test]
+fn test_query_archetypes() {
+    assert_eq!(MATCHED_A, &[(0, "ArchFoo"), (1, "ArchBar")]);
+
+    assert_eq!(ecs_query_archetypes!(|_: &mut CompC, _: Without<CompA>|), &[(6, "ArchBaz")]);
+    assert_eq!(ecs_query_archetypes!(|_: &Entity<ArchBaz>|), &[(6, "ArchBaz")]);
+    assert_eq!(ecs_query_archetypes!(|_: Pair|), &[(0, "ArchFoo")]);
+    assert!(ecs_query_archetypes!(|_: &CompA, _: &CompB, _: &CompC|).is_empty());
+
+    let all = ecs_query_archetypes!(|_: &EntityAny|);
+    assert_eq!(all.iter().map(|(id, _)| *id).collect::<Vec<_>>(), [0, 1, 6]);
+}
+
+#[test]
+fn test_expect_archetypes() {
+    let mut world = EcsWorld::default();
+
+    let foo = world.create::<ArchFoo>((CompA(1), CompB(10)));
+    world.create::<ArchBar>((CompA(2), CompC(20)));
+    world.create::<ArchBaz>((CompB(3), CompC(30)));
+
+    let mut sum = 0;
+    ecs_iter!(world, #[expect_archetypes(ArchFoo, ArchBar)] |a: &CompA| sum += a.0);
+    assert_eq!(sum, 3);
+
+    let found = ecs_find!(world, foo, #[expect_archetypes(ArchFoo)] |p: Pair| p.b.0 += p.a.0);
+    assert!(found.is_some());
+
+    let total = ecs_fold!(world, 0, #[expect_archetypes(ArchFoo, ArchBaz)] |acc, b: &CompB| acc + b.0);
+    assert_eq!(total, 14);
+
+    ecs_iter_destroy!(world, #[expect_archetypes(ArchBaz)] |_: &CompC, _: Without<CompA>| {
+        EcsStepDestroy::ContinueDestroy
+    });
+    assert_eq!(world.arch_baz.len(), 0);
+}
@@ -0,0 +1,84 @@
+use gecs::prelude::*;
+
+pub struct Position(pub i32);
+pub struct Velocity(pub i32);
+pub struct Health(pub i32);
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, Position, Velocity);
+    ecs_archetype!(ArchBar, Velocity, Position, Health);
+    ecs_archetype!(ArchBaz, Health);
+}
+
+mod systems {
+    use super::*;
+
+    #[derive(EcsQuery)]
+    pub struct Movement<'a> {
+        pub pos: &'a mut Position,
+        pub vel: &'a Velocity,
+        pub entity: &'a EntityAny,
+    }
+
+    impl Movement<'_> {
+        pub fn apply(&mut self) {
+            self.pos.0 += self.vel.0;
+        }
+    }
+}
+
+#[derive(EcsQuery)]
+struct Vitals<'a> {
+    health: &'a mut Health,
+}
+
+use systems::Movement;
+
+#[test]
+fn test_query_struct_iter() {
+    let mut world = EcsWorld::default();
+
+    let foo = world.arch_foo.create((Position(1), Velocity(10)));
+    let bar = world.arch_bar.create((Velocity(20), Position(2), Health(5)));
+    world.arch_baz.create((Health(7),));
+
+    let mut visited = Vec::new();
+    ecs_iter!(world, |m: Movement| {
+        visited.push(*m.entity);
+        m.apply();
+    });
+    assert_eq!(visited, [foo.into_any(), bar.into_any()]);
+
+    assert_eq!(ecs_find!(world, foo, |p: &Position| p.0), Some(11));
+    assert_eq!(ecs_find!(world, bar, |m: systems::Movement<'_>| *m.entity), Some(bar.into_any()));
+
+    // Query structs can be mixed with other structs and regular parameters
+    ecs_iter!(world, |m: Movement, v: Vitals, entity: &Entity<_>| {
+        assert_eq!(*m.entity, entity.into_any());
+        v.health.0 += m.pos.0;
+    });
+
+    assert_eq!(ecs_find!(world, bar, |v: Vitals| v.health.0), Some(27));
+    assert_eq!(ecs_count!(world, |_: Vitals|), 2);
+}
+
+#[test]
+fn test_query_struct_other_queries() {
+    let mut world = EcsWorld::default();
+
+    world.arch_foo.create((Position(1), Velocity(10)));
+    let bar = world.arch_bar.create((Velocity(20), Position(2), Health(5)));
+
+    let total = ecs_fold!(world, 0, |acc, m: Movement| acc + m.pos.0);
+    assert_eq!(total, 3);
+
+    let found = ecs_try_find!(world, bar, |m: Movement, v: &Health| {
+        m.pos.0 + v.0
+    });
+    assert_eq!(found, Ok(7));
+
+    ecs_iter_archetype!(&mut world.arch_bar, |m: Movement| {
+        m.pos.0 = 0;
+    });
+    assert_eq!(ecs_find_archetype!(&mut world.arch_bar, bar, |m: Movement| m.pos.0), Some(0));
+}
@@ -0,0 +1,53 @@
+use gecs::prelude::*;
+
+#[derive(Debug, PartialEq)]
+pub struct CompA(pub u32);
+#[derive(Debug, PartialEq)]
+pub struct CompB(pub u32);
+#[derive(Debug, PartialEq)]
+pub struct CompC(pub u32);
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, CompA, CompB);
+    ecs_archetype!(ArchBar, CompC, CompB);
+    ecs_archetype!(ArchBaz, CompB, CompA, CompC);
+}
+
+fn add_b_to_a<W>(world: &mut W) -> usize
+where
+    W: WorldHasComponent<CompA> + WorldHasComponent<CompB>,
+{
+    let mut count = 0;
+    for (a, b) in world.query::<(&mut CompA, &CompB)>() {
+        a.0 += b.0;
+        count += 1;
+    }
+    count
+}
+
+#[test]
+fn test_query_typed_generic() {
+    let mut world = EcsWorld::default();
+
+    let foo = world.create::<ArchFoo>((CompA(1), CompB(10)));
+    let bar = world.create::<ArchBar>((CompC(2), CompB(20)));
+    let baz = world.create::<ArchBaz>((CompB(30), CompA(3), CompC(4)));
+    world.create::<ArchFoo>((CompA(5), CompB(50)));
+
+    assert_eq!(add_b_to_a(&mut world), 3);
+
+    assert_eq!(world.query_one::<(&CompA,)>(foo), Some((&CompA(11),)));
+    assert_eq!(world.query_one::<(&CompA,)>(bar), None);
+    assert_eq!(world.query_one::<(&CompA, &mut CompC)>(baz), Some((&CompA(33), &mut CompC(4))));
+
+    let entities = world.query::<(EntityAny, &CompC)>().map(|(entity, _)| entity).collect::<Vec<_>>();
+    assert_eq!(entities, [bar.into_any(), baz.into_any()]);
+
+    // Shared access to the same component more than once is fine
+    let sum = world.query::<(&CompB, &CompB)>().map(|(b1, b2)| b1.0 + b2.0).sum::<u32>();
+    assert_eq!(sum, 220);
+
+    world.destroy(foo);
+    assert_eq!(world.query_one::<(&CompA,)>(foo), None);
+    assert_eq!(world.query::<(&CompA,)>().count(), 2);
+}
@@ -0,0 +1,49 @@
+use std::any::TypeId;
+
+use gecs::prelude::*;
+
+pub struct CompA(pub u32);
+pub struct CompB(pub u32);
+pub struct CompC<T>(pub T);
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, CompA, CompB);
+
+    #[archetype_id(4)]
+    ecs_archetype!(
+        ArchBar,
+        CompA,
+        #[component_id(7)]
+        CompC<u16>,
+    );
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_reflection_archetype() {
+    assert_eq!(ArchFoo::NAME, "ArchFoo");
+    assert_eq!(ArchFoo::COMPONENT_NAMES, &["CompA", "CompB"]);
+    assert_eq!(ArchFoo::COMPONENT_IDS, &[0, 1]);
+    assert_eq!(ArchFoo::COMPONENT_TYPE_IDS, &[TypeId::of::<CompA>(), TypeId::of::<CompB>()]);
+
+    assert_eq!(ArchBar::NAME, "ArchBar");
+    assert_eq!(ArchBar::COMPONENT_NAMES, &["CompA", "CompC<u16>"]);
+    assert_eq!(ArchBar::COMPONENT_IDS, &[0, 7]);
+    assert_eq!(ArchBar::COMPONENT_IDS[1], <ArchBar as ArchetypeHas<CompC<u16>>>::COMPONENT_ID);
+    assert_eq!(ArchBar::COMPONENT_TYPE_IDS[1], TypeId::of::<CompC<u16>>());
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_reflection_world() {
+    assert_eq!(EcsWorld::ARCHETYPES, &[(0, "ArchFoo"), (4, "ArchBar")]);
+
+    let mut world = EcsWorld::default();
+    let entity: EntityAny = world.create::<ArchBar>((CompA(0), CompC(1))).into();
+
+    assert_eq!(EcsWorld::archetype_name(entity.archetype_id()), Some("ArchBar"));
+    assert_eq!(EcsWorld::archetype_name(1), None);
+
+    let select: SelectArchetype = entity.try_into().unwrap();
+    assert_eq!(select.name(), "ArchBar");
+}
@@ -0,0 +1,262 @@
+use std::sync::{Arc, Barrier, Mutex};
+
+use gecs::prelude::*;
+use gecs::schedule::{Schedule, SystemWorld};
+
+#[derive(Debug, PartialEq)]
+pub struct Position(pub i32);
+#[derive(Debug, PartialEq)]
+pub struct Velocity(pub i32);
+#[derive(Debug, PartialEq)]
+pub struct Health(pub i32);
+
+pub struct DeltaTime(pub i32);
+
+ecs_world! {
+    ecs_archetype!(ArchShip, Position, Velocity);
+    ecs_archetype!(ArchRock, Position);
+    ecs_archetype!(ArchStation, Health);
+}
+
+#[ecs_system(world = EcsWorld)]
+fn integrate(pos: &mut Position, vel: &Velocity, dt: Res<DeltaTime>) {
+    pos.0 += vel.0 * dt.0;
+}
+
+#[ecs_system(world = EcsWorld)]
+fn accelerate(vel: &mut Velocity) {
+    vel.0 += 1;
+}
+
+#[ecs_system(world = EcsWorld)]
+fn drift(pos: &mut Position, _: Without<Velocity>) {
+    pos.0 -= 1;
+}
+
+#[ecs_system(world = EcsWorld)]
+fn repair(health: &mut Health) {
+    health.0 = 100;
+}
+
+#[ecs_system(world = EcsWorld)]
+fn inspect(_: &Position) {}
+
+fn spawn(world: &mut EcsWorld) -> (Entity<ArchShip>, Entity<ArchRock>, Entity<ArchStation>) {
+    let ship = world.create::<ArchShip>((Position(0), Velocity(1)));
+    let rock = world.create::<ArchRock>((Position(10),));
+    let station = world.create::<ArchStation>((Health(50),));
+    (ship, rock, station)
+}
+
+#[test]
+fn test_schedule_run_disjoint() {
+    for threads in [1, 4] {
+        let mut world = EcsWorld::default();
+        let (ship, rock, station) = spawn(&mut world);
+
+        let dt = DeltaTime(2);
+        let mut schedule = Schedule::with_threads(threads);
+        schedule.add(integrate::ACCESS, |world| integrate(world, &dt));
+        schedule.add(drift::ACCESS, |world| drift(world));
+        schedule.add(repair::ACCESS, |world| repair(world));
+
+        // All three systems touch different archetypes
+        assert!(schedule.ambiguities().is_empty());
+
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+
+        assert_eq!(ecs_find!(world, ship, |p: &Position| p.0), Some(4));
+        assert_eq!(ecs_find!(world, rock, |p: &Position| p.0), Some(8));
+        assert_eq!(ecs_find!(world, station, |h: &Health| h.0), Some(100));
+    }
+}
+
+#[test]
+fn test_schedule_run_concurrent() {
+    let mut world = EcsWorld::default();
+    let (ship, rock, station) = spawn(&mut world);
+
+    // Each system waits for all of the others, so this only finishes if they run in parallel
+    let barrier = Barrier::new(3);
+    let dt = DeltaTime(2);
+    let mut schedule = Schedule::with_threads(3);
+    schedule.add(integrate::ACCESS, |world| {
+        integrate(world, &dt);
+        barrier.wait();
+    });
+    schedule.add(drift::ACCESS, |world| {
+        drift(world);
+        barrier.wait();
+    });
+    schedule.add(repair::ACCESS, |world| {
+        repair(world);
+        barrier.wait();
+    });
+    schedule.run(&mut world);
+
+    assert_eq!(ecs_find!(world, ship, |p: &Position| p.0), Some(2));
+    assert_eq!(ecs_find!(world, rock, |p: &Position| p.0), Some(9));
+    assert_eq!(ecs_find!(world, station, |h: &Health| h.0), Some(100));
+
+    // Read-only systems can share archetypes with each other
+    let barrier = Barrier::new(2);
+    let mut schedule = Schedule::with_threads(2);
+    schedule.add(inspect::ACCESS, |world| {
+        inspect(world);
+        barrier.wait();
+    });
+    schedule.add(inspect::ACCESS, |world| {
+        inspect(world);
+        barrier.wait();
+    });
+    schedule.run(&mut world);
+}
+
+#[test]
+fn test_schedule_read_only_shared() {
+    let mut world = EcsWorld::default();
+    spawn(&mut world);
+    world.create::<ArchShip>((Position(5), Velocity(0)));
+
+    // Both systems read ArchShip at the same time, one directly through its parts
+    let barrier = Barrier::new(2);
+    let sums = Mutex::new(Vec::new());
+    let mut schedule = Schedule::with_threads(2);
+    schedule.add(inspect::ACCESS, |world| {
+        inspect(world);
+        barrier.wait();
+    });
+    schedule.add(inspect::ACCESS, |mut world: SystemWorld<'_, EcsWorld>| {
+        let parts = world.parts(&inspect::ACCESS);
+        let ships = parts.arch_ship.read();
+        barrier.wait();
+        let sum = ships.get_slice::<Position>().iter().map(|p| p.0).sum::<i32>();
+        sums.lock().unwrap().push((ships.len(), sum));
+    });
+    assert!(schedule.ambiguities().is_empty());
+
+    schedule.run(&mut world);
+    schedule.run(&mut world);
+    assert_eq!(*sums.lock().unwrap(), [(2, 5), (2, 5)]);
+}
+
+#[test]
+#[should_panic(expected = "is read-only in the access this world was split for")]
+fn test_schedule_read_only_part_get_mut() {
+    let mut world = EcsWorld::default();
+    spawn(&mut world);
+
+    let mut world = SystemWorld::new(&mut world);
+    let mut parts = world.parts(&inspect::ACCESS);
+    parts.arch_ship.get_mut();
+}
+
+#[test]
+fn test_schedule_order() {
+    let mut world = EcsWorld::default();
+    let (ship, _, _) = spawn(&mut world);
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let dt = DeltaTime(1);
+
+    let mut schedule = Schedule::new();
+    let log_integrate = log.clone();
+    let first = schedule.add(integrate::ACCESS, move |world| {
+        integrate(world, &dt);
+        log_integrate.lock().unwrap().push("integrate");
+    });
+    let log_accelerate = log.clone();
+    let second = schedule.add(accelerate::ACCESS, move |world| {
+        accelerate(world);
+        log_accelerate.lock().unwrap().push("accelerate");
+    });
+
+    // Both systems use ArchShip, and accelerate writes the Velocity that integrate reads
+    let ambiguities = schedule.ambiguities();
+    assert_eq!(ambiguities.len(), 1);
+    assert_eq!(ambiguities[0].first, first);
+    assert_eq!(ambiguities[0].second, second);
+    assert_eq!(ambiguities[0].archetype, "ArchShip");
+    assert_eq!(ambiguities[0].component, "Velocity");
+
+    schedule.order(second, first);
+    assert!(schedule.ambiguities().is_empty());
+    assert_eq!(schedule.name(first), "integrate");
+
+    schedule.run(&mut world);
+    assert_eq!(*log.lock().unwrap(), ["accelerate", "integrate"]);
+    assert_eq!(ecs_find!(world, ship, |p: &Position| p.0), Some(2));
+}
+
+#[test]
+fn test_schedule_read_only_not_ambiguous() {
+    let mut schedule = Schedule::<EcsWorld>::new();
+    schedule.add(inspect::ACCESS, |world| inspect(world));
+    schedule.add(inspect::ACCESS, |world| inspect(world));
+    assert!(schedule.ambiguities().is_empty());
+
+    schedule.add(drift::ACCESS, |world| drift(world));
+    let ambiguities = schedule.ambiguities();
+    assert_eq!(ambiguities.len(), 2);
+    assert_eq!(ambiguities[0].archetype, "ArchRock");
+    assert_eq!(ambiguities[0].component, "Position");
+}
+
+#[test]
+#[should_panic(expected = "would create a cycle")]
+fn test_schedule_order_cycle() {
+    let mut schedule = Schedule::<EcsWorld>::new();
+    let first = schedule.add(integrate::ACCESS, |world| integrate(world, &DeltaTime(1)));
+    let second = schedule.add(accelerate::ACCESS, |world| accelerate(world));
+    let third = schedule.add(repair::ACCESS, |world| repair(world));
+
+    schedule.order(first, second);
+    schedule.order(second, third);
+    schedule.order(third, first);
+}
+
+#[test]
+#[should_panic(expected = "outside of the access it was scheduled with")]
+fn test_schedule_access_exceeded() {
+    let mut world = EcsWorld::default();
+    spawn(&mut world);
+
+    // The closure runs a different system than the access it was added with
+    let mut schedule = Schedule::with_threads(1);
+    schedule.add(repair::ACCESS, |world: SystemWorld<'_, EcsWorld>| drift(world));
+    schedule.run(&mut world);
+}
+
+mod not_send {
+    use std::rc::Rc;
+
+    use gecs::prelude::*;
+    use gecs::schedule::{Schedule, SystemWorld};
+
+    pub struct Shared(pub Rc<i32>);
+
+    // Rc isn't Send, but the world can still be split up to run systems directly
+    ecs_world! {
+        ecs_archetype!(ArchShared, Shared);
+    }
+
+    #[ecs_system(world = EcsWorld)]
+    fn bump(shared: &mut Shared) {
+        shared.0 = Rc::new(*shared.0 + 1);
+    }
+
+    #[test]
+    fn test_schedule_not_send() {
+        let mut world = EcsWorld::default();
+        let entity = world.create::<ArchShared>((Shared(Rc::new(1)),));
+
+        bump(&mut world);
+        bump(SystemWorld::new(&mut world));
+        assert_eq!(ecs_find!(world, entity, |s: &Shared| *s.0), Some(3));
+
+        let mut schedule = Schedule::<EcsWorld>::new();
+        schedule.add(bump::ACCESS, |world| bump(world));
+        assert!(schedule.ambiguities().is_empty());
+    }
+}
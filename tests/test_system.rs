@@ -0,0 +1,92 @@
+use gecs::prelude::*;
+
+#[derive(Debug, PartialEq)]
+pub struct Position(pub i32);
+#[derive(Debug, PartialEq)]
+pub struct Velocity(pub i32);
+pub struct Frozen;
+
+pub struct DeltaTime(pub i32);
+pub struct Moved(pub Vec<EntityAny>);
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, Position, Velocity);
+    ecs_archetype!(ArchBar, Velocity, Position, Frozen);
+    ecs_archetype!(ArchBaz, Position);
+}
+
+#[ecs_system(world = crate::EcsWorld)]
+fn integrate(pos: &mut Position, vel: &Velocity, _: Without<Frozen>, dt: Res<DeltaTime>) {
+    pos.0 += vel.0 * dt.0;
+}
+
+#[ecs_system(world = EcsWorld)]
+pub fn track(entity: &EntityAny, _: &Position, moved: ResMut<Moved>) {
+    moved.0.push(*entity);
+}
+
+mod systems {
+    use super::*;
+
+    #[derive(EcsQuery)]
+    pub struct Motion<'a> {
+        pub pos: &'a Position,
+        pub vel: &'a mut Velocity,
+    }
+
+    /// The world's query macros are found through the module of the world's path.
+    #[ecs_system(world = super::EcsWorld)]
+    pub fn damp(m: Motion) {
+        m.vel.0 -= m.pos.0.signum();
+    }
+}
+
+#[test]
+fn test_system_run() {
+    let mut world = EcsWorld::default();
+
+    let foo = world.create::<ArchFoo>((Position(1), Velocity(10)));
+    let bar = world.create::<ArchBar>((Velocity(20), Position(2), Frozen));
+    let baz = world.create::<ArchBaz>((Position(3),));
+
+    integrate(&mut world, &DeltaTime(2));
+    assert_eq!(ecs_find!(world, foo, |p: &Position| p.0), Some(21));
+    assert_eq!(ecs_find!(world, bar, |p: &Position| p.0), Some(2));
+
+    let mut moved = Moved(Vec::new());
+    track(&mut world, &mut moved);
+    assert_eq!(moved.0, [foo.into_any(), bar.into_any(), baz.into_any()]);
+
+    systems::damp(&mut world);
+    assert_eq!(ecs_find!(world, foo, |v: &Velocity| v.0), Some(9));
+    assert_eq!(ecs_find!(world, bar, |v: &Velocity| v.0), Some(19));
+}
+
+#[test]
+fn test_system_access() {
+    let access = integrate::ACCESS;
+    assert_eq!(access.name, "integrate");
+    assert_eq!(access.archetypes.len(), 1);
+    assert_eq!(access.archetypes[0].id, ArchFoo::ARCHETYPE_ID);
+    assert_eq!(access.archetypes[0].name, "ArchFoo");
+    assert_eq!(access.archetypes[0].reads, ["Velocity"]);
+    assert_eq!(access.archetypes[0].writes, ["Position"]);
+    assert!(!access.is_read_only());
+
+    let access = track::ACCESS;
+    let ids = access.archetypes.iter().map(|a| a.id).collect::<Vec<_>>();
+    assert_eq!(ids, [ArchFoo::ARCHETYPE_ID, ArchBar::ARCHETYPE_ID, ArchBaz::ARCHETYPE_ID]);
+    assert!(access.archetypes.iter().all(|a| a.reads == ["Position"] && a.writes.is_empty()));
+    assert!(access.is_read_only());
+
+    let access = systems::damp::ACCESS;
+    assert_eq!(access.get(ArchBar::ARCHETYPE_ID).unwrap().writes, ["Velocity"]);
+    assert!(access.get(ArchBaz::ARCHETYPE_ID).is_none());
+
+    // The same metadata is available for any query
+    let access = ecs_query_access!(|_: &mut Velocity, _: &Position, _: &Position|);
+    assert_eq!(access.len(), 2);
+    assert_eq!(access[1].name, "ArchBar");
+    assert_eq!(access[1].reads, ["Position"]);
+    assert_eq!(access[1].writes, ["Velocity"]);
+}
@@ -0,0 +1,107 @@
+use std::any::TypeId;
+
+use gecs::prelude::*;
+
+pub struct CompA(pub u32);
+pub struct CompB(pub u32);
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, CompA, CompB);
+    ecs_archetype!(ArchBar, CompA);
+}
+
+#[test]
+pub fn test_try_borrow_slice() {
+    let mut world = EcsWorld::default();
+
+    let entity = world.arch_foo.create((CompA(1), CompB(10)));
+
+    let conflict = EcsError::BorrowConflict {
+        archetype: "ArchFoo",
+        component: "CompA",
+    };
+
+    {
+        let slice = world.arch_foo.try_borrow_slice_mut::<CompA>().unwrap();
+        assert_eq!(slice[0].0, 1);
+
+        assert_eq!(world.arch_foo.try_borrow_slice::<CompA>().err(), Some(conflict.clone()));
+        assert_eq!(world.arch_foo.try_borrow_component::<CompA, _>(entity).err(), Some(conflict.clone()));
+        assert!(world.arch_foo.try_borrow_slice::<CompB>().is_ok());
+
+        let dyn_archetype = world.archetype_dyn(ArchFoo::ARCHETYPE_ID).unwrap();
+        let type_id = TypeId::of::<CompA>();
+        assert_eq!(dyn_archetype.try_borrow_component(entity.into(), type_id).err(), Some(conflict.clone()));
+    }
+
+    {
+        let _slice = world.arch_foo.try_borrow_slice::<CompA>().unwrap();
+
+        assert!(world.arch_foo.try_borrow_slice::<CompA>().is_ok());
+        assert_eq!(world.arch_foo.try_borrow_slice_mut::<CompA>().err(), Some(conflict.clone()));
+        assert_eq!(world.arch_foo.try_borrow_component_mut::<CompA, _>(entity).err(), Some(conflict));
+    }
+
+    let mut component = world.arch_foo.try_borrow_component_mut::<CompB, _>(entity).unwrap().unwrap();
+    component.0 += 1;
+    drop(component);
+
+    world.destroy(entity);
+    assert!(world.arch_foo.try_borrow_component::<CompB, _>(entity).unwrap().is_none());
+}
+
+#[test]
+pub fn test_try_borrow_component() {
+    let mut world = EcsWorld::default();
+
+    let entity = world.arch_foo.create((CompA(1), CompB(10)));
+    let borrow = world.borrow(entity).unwrap();
+
+    let a = borrow.try_component_mut::<CompA>().unwrap();
+    assert!(matches!(
+        borrow.try_component::<CompA>(),
+        Err(EcsError::BorrowConflict { archetype: "ArchFoo", component: "CompA" }),
+    ));
+    assert_eq!(borrow.try_component::<CompB>().unwrap().0, 10);
+    drop(a);
+
+    assert_eq!(borrow.try_component::<CompA>().unwrap().0, 1);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_try_find_borrow() {
+    let mut world = EcsWorld::default();
+
+    let entity_a = world.arch_foo.create((CompA(1), CompB(10)));
+    let entity_b = world.arch_foo.create((CompA(2), CompB(20)));
+    let entity_c = world.arch_bar.create((CompA(3),));
+
+    let world = &world;
+    let mut conflicts = Vec::new();
+
+    ecs_iter_borrow!(world, |a: &CompA| {
+        // Nested lookups can back off rather than panicking on a conflicting borrow
+        let result = ecs_try_find_borrow!(world, entity_b, |b: &mut CompB| {
+            b.0 += a.0;
+        });
+
+        if let Err(err) = result {
+            conflicts.push(err);
+        }
+
+        let result = ecs_try_find_borrow!(world, entity_a, |a: &mut CompA| a.0);
+        if let Err(err) = result {
+            conflicts.push(err);
+        }
+    });
+
+    let conflict_a_foo = FindError::BorrowConflict { archetype: "ArchFoo", component: "CompA" };
+    assert_eq!(conflicts, [conflict_a_foo; 2]);
+
+    assert_eq!(ecs_try_find_borrow!(world, entity_b, |b: &CompB| b.0), Ok(26));
+    assert_eq!(
+        ecs_try_find_borrow!(world, entity_c, |b: &CompB| b.0),
+        Err(FindError::ArchetypeMismatch { archetype_id: ArchBar::ARCHETYPE_ID }),
+    );
+}
@@ -0,0 +1,69 @@
+use gecs::prelude::*;
+
+pub struct CompA(pub u32);
+pub struct CompB(pub u32);
+pub struct CompC(pub u32);
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, CompA, CompB);
+    ecs_archetype!(ArchBar, CompA, CompC);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_try_find_basic() {
+    let mut world = EcsWorld::default();
+
+    let entity_a = world.arch_foo.create((CompA(1), CompB(10)));
+    let entity_b = world.arch_bar.create((CompA(2), CompC(20)));
+
+    assert_eq!(ecs_try_find!(world, entity_a, |a: &mut CompA| { a.0 += 1; a.0 }), Ok(2));
+    assert_eq!(ecs_try_find!(world, entity_b.into_any(), |a: &CompA| a.0), Ok(2));
+
+    assert_eq!(
+        ecs_try_find!(world, entity_b, |b: &CompB| b.0),
+        Err(FindError::ArchetypeMismatch { archetype_id: ArchBar::ARCHETYPE_ID }),
+    );
+    assert_eq!(
+        ecs_try_find!(world, entity_a.into_any(), |_: &CompA, _: Without<CompB>| {}),
+        Err(FindError::ArchetypeMismatch { archetype_id: ArchFoo::ARCHETYPE_ID }),
+    );
+
+    world.destroy(entity_a);
+    assert_eq!(ecs_try_find!(world, entity_a, |a: &CompA| a.0), Err(FindError::Stale));
+    assert_eq!(ecs_try_find!(world, entity_a.into_any(), |_: &CompA| {}), Err(FindError::Stale));
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_try_find_direct() {
+    let mut world = EcsWorld::default();
+
+    let entity_a = world.arch_foo.create((CompA(1), CompB(10)));
+    let entity_b = world.arch_foo.create((CompA(2), CompB(20)));
+    let direct_b = world.arch_foo.to_direct(entity_b).unwrap();
+
+    assert_eq!(ecs_try_find!(world, direct_b, |b: &CompB| b.0), Ok(20));
+    assert_eq!(ecs_try_find!(world, direct_b.into_any(), |b: &CompB| b.0), Ok(20));
+
+    world.destroy(entity_a);
+    assert_eq!(ecs_try_find!(world, direct_b, |b: &CompB| b.0), Err(FindError::DirectInvalidated));
+    assert_eq!(ecs_try_find!(world, entity_b, |b: &CompB| b.0), Ok(20));
+
+    let error: EcsError = FindError::DirectInvalidated.into();
+    assert_eq!(error, EcsError::DirectInvalidated);
+    assert_eq!(FindError::Stale.to_string(), "stale entity handle");
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_try_find_invalid_type() {
+    let mut world = EcsWorld::default();
+
+    let entity = world.arch_foo.create((CompA(1), CompB(10))).into_any();
+    let (key, version) = entity.raw();
+    // The archetype ID is stored in the low bits of the key
+    let bogus = EntityAny::from_raw(((key & !0xFF) | 200, version)).unwrap();
+
+    assert_eq!(ecs_try_find!(world, bogus, |_: &CompA| {}), Err(FindError::InvalidEntityType));
+}
@@ -0,0 +1,83 @@
+use gecs::prelude::*;
+
+pub struct CompA(pub u32);
+pub struct CompB(pub u32);
+pub struct CompC(pub u32);
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, CompA, CompB);
+    ecs_archetype!(ArchBar, CompA, CompC);
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_view_many_archetype() {
+    let mut world = EcsWorld::default();
+
+    let entity_a = world.arch_foo.create((CompA(1), CompB(10)));
+    let entity_b = world.arch_foo.create((CompA(2), CompB(20)));
+    let entity_c = world.arch_foo.create((CompA(3), CompB(30)));
+
+    let [mut a, mut b, mut c] = world.arch_foo.view_many_mut([entity_a, entity_b, entity_c]).unwrap();
+    std::mem::swap(a.component_mut::<CompA>(), c.component_mut::<CompA>());
+    b.component_mut::<CompB>().0 += a.component::<CompB>().0 + c.component::<CompB>().0;
+
+    assert_eq!(ecs_find!(world, entity_a, |a: &CompA| a.0), Some(3));
+    assert_eq!(ecs_find!(world, entity_b, |b: &CompB| b.0), Some(60));
+    assert_eq!(ecs_find!(world, entity_c, |a: &CompA| a.0), Some(1));
+
+    // Duplicate handles are rejected
+    assert!(world.arch_foo.view_many_mut([entity_a, entity_b, entity_a]).is_none());
+
+    // Stale handles are rejected
+    world.arch_foo.destroy(entity_b);
+    assert!(world.arch_foo.view_many_mut([entity_a, entity_b]).is_none());
+    assert!(world.arch_foo.view_many_mut([entity_a, entity_c]).is_some());
+
+    // Direct handles work as well
+    let direct_a = world.arch_foo.to_direct(entity_a).unwrap();
+    let direct_c = world.arch_foo.to_direct(entity_c).unwrap();
+    let [a, c] = world.arch_foo.view_many_mut([direct_a, direct_c]).unwrap();
+    assert_eq!((a.component::<CompA>().0, c.component::<CompA>().0), (3, 1));
+}
+
+fn comp_a_mut<'a>(view: &'a mut SelectViewMut) -> &'a mut CompA {
+    match view {
+        SelectViewMut::ArchFoo(view) => view.component_mut::<CompA>(),
+        SelectViewMut::ArchBar(view) => view.component_mut::<CompA>(),
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_view_many_world() {
+    let mut world = EcsWorld::default();
+
+    let entity_a = world.arch_foo.create((CompA(1), CompB(10))).into_any();
+    let entity_b = world.arch_bar.create((CompA(2), CompC(20))).into_any();
+    let entity_c = world.arch_foo.create((CompA(3), CompB(30))).into_any();
+
+    let [mut a, mut b, mut c] = world.view_many_mut([entity_a, entity_b, entity_c]).unwrap();
+    std::mem::swap(comp_a_mut(&mut a), comp_a_mut(&mut b));
+    comp_a_mut(&mut c).0 += 100;
+
+    match b {
+        SelectViewMut::ArchBar(view) => assert_eq!(view.component::<CompC>().0, 20),
+        _ => panic!(),
+    }
+
+    assert_eq!(ecs_find!(world, entity_a, |a: &CompA| a.0), Some(2));
+    assert_eq!(ecs_find!(world, entity_b, |a: &CompA| a.0), Some(1));
+    assert_eq!(ecs_find!(world, entity_c, |a: &CompA| a.0), Some(103));
+
+    assert!(world.view_many_mut([entity_a, entity_b, entity_b]).is_none());
+
+    world.destroy(entity_c);
+    assert!(world.view_many_mut([entity_a, entity_c]).is_none());
+
+    // Typed handles delegate to the archetype
+    let entity_d = world.arch_bar.create((CompA(4), CompC(40)));
+    let entity_e = world.arch_bar.create((CompA(5), CompC(50)));
+    let [d, e] = world.view_many_mut([entity_d, entity_e]).unwrap();
+    assert_eq!(d.component::<CompC>().0 + e.component::<CompC>().0, 90);
+}
@@ -0,0 +1,88 @@
+use std::any::Any;
+
+use gecs::prelude::*;
+
+pub struct CompA(pub u32);
+pub struct CompB(pub u32);
+pub struct CompC(pub u32);
+
+ecs_world! {
+    ecs_archetype!(ArchFoo, CompA, CompB);
+    ecs_archetype!(ArchBar, CompC, CompA);
+}
+
+#[derive(Default)]
+struct Collector {
+    names: Vec<&'static str>,
+    values: Vec<u32>,
+}
+
+impl ComponentVisitor for Collector {
+    fn visit<C: 'static>(&mut self, name: &'static str, component: &C) {
+        self.names.push(name);
+
+        let component = component as &dyn Any;
+        if let Some(comp_a) = component.downcast_ref::<CompA>() {
+            self.values.push(comp_a.0);
+        } else if let Some(comp_b) = component.downcast_ref::<CompB>() {
+            self.values.push(comp_b.0);
+        } else if let Some(comp_c) = component.downcast_ref::<CompC>() {
+            self.values.push(comp_c.0);
+        }
+    }
+}
+
+struct Incrementer;
+
+impl ComponentVisitorMut for Incrementer {
+    fn visit_mut<C: 'static>(&mut self, _: &'static str, component: &mut C) {
+        let component = component as &mut dyn Any;
+        if let Some(comp_a) = component.downcast_mut::<CompA>() {
+            comp_a.0 += 10;
+        }
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_visitor_archetype() {
+    let mut world = EcsWorld::default();
+
+    let entity = world.arch_foo.create((CompA(1), CompB(2)));
+
+    let mut collector = Collector::default();
+    assert!(world.arch_foo.visit_components(entity, &mut collector));
+    assert_eq!(collector.names, ["CompA", "CompB"]);
+    assert_eq!(collector.values, [1, 2]);
+
+    assert!(world.arch_foo.visit_components_mut(entity, &mut Incrementer));
+    assert_eq!(world.arch_foo.get_component::<CompA, _>(entity).unwrap().0, 11);
+
+    world.arch_foo.destroy(entity);
+    assert!(!world.arch_foo.visit_components(entity, &mut Collector::default()));
+    assert!(!world.arch_foo.visit_components_mut(entity, &mut Incrementer));
+}
+
+#[test]
+#[rustfmt::skip]
+pub fn test_visitor_world() {
+    let mut world = EcsWorld::default();
+
+    let entity_a = world.arch_foo.create((CompA(1), CompB(2)));
+    let entity_b = world.arch_bar.create((CompC(3), CompA(4)));
+
+    let mut collector = Collector::default();
+    assert!(world.visit_components(entity_a, &mut collector));
+    assert!(world.visit_components(entity_b.into_any(), &mut collector));
+    assert!(world.visit_components(world.arch_bar.to_direct(entity_b).unwrap().into_any(), &mut collector));
+    assert_eq!(collector.names, ["CompA", "CompB", "CompC", "CompA", "CompC", "CompA"]);
+    assert_eq!(collector.values, [1, 2, 3, 4, 3, 4]);
+
+    assert!(world.visit_components_mut(entity_a.into_any(), &mut Incrementer));
+    assert!(world.visit_components_mut(entity_b, &mut Incrementer));
+    assert_eq!(ecs_find!(world, entity_a, |a: &CompA| a.0), Some(11));
+    assert_eq!(ecs_find!(world, entity_b, |a: &CompA| a.0), Some(14));
+
+    world.destroy(entity_b);
+    assert!(!world.visit_components(entity_b.into_any(), &mut Collector::default()));
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "world_file")]
+#![allow(unexpected_cfgs)]
+
+use gecs::prelude::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Position(pub u32);
+#[derive(Clone, Debug, PartialEq)]
+pub struct Velocity(pub u32);
+#[derive(Clone, Debug, PartialEq)]
+pub struct Health(pub u32);
+#[derive(Clone, Debug, PartialEq)]
+pub struct Value<T>(pub T);
+#[cfg(feature = "some_feature")]
+pub struct Debris;
+
+pub trait Describe {
+    fn describe(&self) -> String;
+}
+
+impl Describe for Value<u32> {
+    fn describe(&self) -> String {
+        format!("worth {}", self.0)
+    }
+}
+
+ecs_world_from_file!("tests/worlds/test_world.toml");
+
+mod reference {
+    use super::{Describe, Health, Position, Value, Velocity};
+    use gecs::prelude::*;
+
+    // The same world, declared with ecs_world! directly
+    ecs_world! {
+        #[derive(PartialEq)]
+        ecs_name!(FileWorld);
+
+        ecs_template!(Movement, Position, #[component_id(4)] Velocity);
+
+        ecs_archetype!(ArchShip: Movement, Health);
+
+        #[archetype_id(5)]
+        ecs_archetype!(
+            ArchRock,
+            Position,
+            #[implements(Describe)]
+            Value<u32>,
+            #[cfg(feature = "some_feature")]
+            Debris,
+        );
+
+        #[cfg(feature = "some_feature")]
+        ecs_archetype!(ArchDebug, Position);
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_world_file_reference() {
+    assert_eq!(FileWorld::ARCHETYPES, reference::FileWorld::ARCHETYPES);
+    assert_eq!(FileWorld::ARCHETYPES, &[(0, "ArchShip"), (5, "ArchRock")]);
+
+    assert_eq!(ArchShip::COMPONENT_NAMES, reference::ArchShip::COMPONENT_NAMES);
+    assert_eq!(ArchShip::COMPONENT_IDS, reference::ArchShip::COMPONENT_IDS);
+    assert_eq!(ArchRock::COMPONENT_NAMES, reference::ArchRock::COMPONENT_NAMES);
+    assert_eq!(ArchRock::COMPONENT_IDS, reference::ArchRock::COMPONENT_IDS);
+    assert_eq!(ArchShip::COMPONENT_IDS, &[0, 4, 5]);
+}
+
+#[test]
+fn test_world_file_query() {
+    let mut world = FileWorld::default();
+
+    let ship = world.create::<ArchShip>((Position(0), Velocity(2), Health(10)));
+    world.create::<ArchRock>((Position(1), Value(7)));
+
+    ecs_iter!(world, |pos: &mut Position, vel: &Velocity| pos.0 += vel.0);
+    assert_eq!(ecs_find!(world, ship, |p: &Position| p.0), Some(2));
+
+    let mut descriptions = Vec::new();
+    ecs_iter!(world, |value: &dyn Describe| descriptions.push(value.describe()));
+    assert_eq!(descriptions, ["worth 7"]);
+
+    assert_eq!(world.clone(), world);
+}